@@ -1,74 +1,2317 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEvent, KeyModifiers,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::Backend};
 
-use crate::podcast::{Episode, Podcast, PodcastURL};
+use crate::backup::Backups;
+use crate::commands::command_interpreters::PodcastPipelineInterpreter;
+use crate::commands::podcast_algebra::{PipelineData, run_commands};
+use crate::commands::podcast_commands::PodcastCmd;
+use crate::config::Config;
+use crate::episode_download::{
+    DownloadPriority, DownloadQueue, DownloadState, EpisodeDownloader, HttpEpisodeDownloader,
+    QueuedDownload, discard_partial_unless_resumable, episode_path, format_size, numbered_path,
+    reconcile_partial_files,
+};
+use crate::episode_state::EpisodeStateStore;
+use crate::errors::EpisodeDownloadError;
+use crate::events::AppEvent;
+use crate::instance::{InstanceSocket, listen};
+use crate::keymap::help_bindings;
+use crate::metrics::BulkRun;
+use crate::opml::{OpmlFeed, read_subscription_file};
+use crate::podcast::{Episode, EpisodeID, EpisodeKey, Podcast, PodcastURL};
+use crate::podcast_download::{FeedFetcher, HttpFeedFetcher};
+use crate::podcast_factory::PodcastFactory;
+use crate::refresh::{RefreshOutcome, RefreshSchedule};
+use crate::share::{osc52_copy, share_text};
+use crate::show_notes::format_episode_description;
+use crate::tasks::TaskInfo;
+use crate::tasks::{TaskId, TaskKind, TaskTracker};
+use crate::theme::Theme;
+use crate::trash::Trash;
+use crate::widgets::checklist::ChecklistState;
+use crate::widgets::confirm_dialog::{ConfirmChoice, ConfirmDialog};
+use crate::widgets::file_picker::FilePickerState;
+use crate::widgets::input_line::{InputHistory, InputLine};
+use crate::widgets::scrollable_paragraph::ScrollableParagraphState;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+type ShowNotesCache = HashMap<(EpisodeID, usize), Rc<Vec<String>>>;
+
+// How often the activity spinner steps while background tasks are running
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+// How many feeds the end-of-import/refresh summary names
+const SLOWEST_FEEDS_REPORTED: usize = 3;
+
+// A row of the Podcasts panel
+#[derive(Debug, Clone, PartialEq)]
+enum PodcastKey {
+    Podcast(PodcastURL),
+    Favorites,
+}
+
+// A selection by identity, so lists that re-sort or grow under it (refreshes, imports,
+// reordering) keep pointing at the same item. Indices are derived from it when needed;
+// `row` is where the item was when it got selected, for when it drops out of the list.
+#[derive(Debug, Clone, PartialEq)]
+struct Selection<K> {
+    key: K,
+    row: usize,
+}
+
+// Panel receiving navigation keys, cycled with Tab/Shift-Tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FocusedPanel {
+    #[default]
+    Podcasts,
+    Episodes,
+    ShowNotes,
+}
+
+impl FocusedPanel {
+    pub fn next(self) -> Self {
+        match self {
+            FocusedPanel::Podcasts => FocusedPanel::Episodes,
+            FocusedPanel::Episodes => FocusedPanel::ShowNotes,
+            FocusedPanel::ShowNotes => FocusedPanel::Podcasts,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            FocusedPanel::Podcasts => FocusedPanel::ShowNotes,
+            FocusedPanel::Episodes => FocusedPanel::Podcasts,
+            FocusedPanel::ShowNotes => FocusedPanel::Episodes,
+        }
+    }
+}
+
+// Position within the playing episode, reported by the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaybackProgress {
+    pub position: Duration,
+    pub duration: Duration,
+}
+
+impl PlaybackProgress {
+    // Fraction played, clamped to 0.0..=1.0 for the gauge
+    pub fn ratio(&self) -> f64 {
+        if self.duration.is_zero() {
+            return 0.0;
+        }
+        (self.position.as_secs_f64() / self.duration.as_secs_f64()).clamp(0.0, 1.0)
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.position)
+    }
+}
+
+// What a picked file is used for; decides the picker's title, filter and action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilePickerPurpose {
+    ImportOpml,
+}
+
+impl FilePickerPurpose {
+    pub fn title(self) -> &'static str {
+        match self {
+            FilePickerPurpose::ImportOpml => "Import OPML",
+        }
+    }
+
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            FilePickerPurpose::ImportOpml => &["opml", "xml", "txt"],
+        }
+    }
+}
+
+// What happens when a confirmation dialog is answered with "Yes"
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmAction {
+    Subscribe(PodcastURL),
+    Unsubscribe(PodcastURL),
+    RefreshFeeds(Vec<PodcastURL>), // Already subscribed feeds found in an import
+    DownloadEpisodes(PodcastURL, Vec<EpisodeID>),
+}
+
+// What an import does with feeds that are already subscribed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateFeeds {
+    Ask,     // Offer to refresh them
+    Refresh, // Refresh them right away (`rustero import --merge`)
+}
+
+// One line in the task panel
+#[derive(Debug, Clone, Copy)]
+pub enum TaskRow<'a> {
+    Task(&'a TaskInfo),
+    Queued(&'a QueuedDownload),
+}
+
+// Dialogs drawn over the main layout. They stack: the top one gets the keys and
+// Esc closes it, revealing the one below.
+#[derive(Debug, Clone)]
+pub enum Popup {
+    Help(ScrollableParagraphState),
+    Confirm(ConfirmAction, ConfirmDialog),
+    PodcastInfo(PodcastURL),
+    Report { title: String, lines: Vec<String> }, // Read-only, e.g. what a refresh-all found
+    FilePicker(FilePickerPurpose, FilePickerState),
+    // The feeds of an OPML file, to untick the unwanted ones before importing
+    ImportSelection { source: PathBuf, feeds: ChecklistState<OpmlFeed>, duplicates: DuplicateFeeds },
+    Tasks { selected: usize }, // Index into `TaskTracker::list`
+    Note { episode: EpisodeKey, title: String, input: InputLine }, // Editing an episode note
+}
+
+// Limits the Episodes panel to recently published episodes, cycled with 'd'
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EpisodeDateFilter {
+    #[default]
+    All,
+    LastWeek,
+    LastMonth,
+    LastYear,
+}
+
+impl EpisodeDateFilter {
+    pub fn next(self) -> Self {
+        match self {
+            EpisodeDateFilter::All => EpisodeDateFilter::LastWeek,
+            EpisodeDateFilter::LastWeek => EpisodeDateFilter::LastMonth,
+            EpisodeDateFilter::LastMonth => EpisodeDateFilter::LastYear,
+            EpisodeDateFilter::LastYear => EpisodeDateFilter::All,
+        }
+    }
+
+    pub fn label(self) -> Option<&'static str> {
+        match self {
+            EpisodeDateFilter::All => None,
+            EpisodeDateFilter::LastWeek => Some("last week"),
+            EpisodeDateFilter::LastMonth => Some("last month"),
+            EpisodeDateFilter::LastYear => Some("last year"),
+        }
+    }
+
+    pub fn includes(self, episode: &Episode, now: DateTime<Utc>) -> bool {
+        let max_age = match self {
+            EpisodeDateFilter::All => return true,
+            EpisodeDateFilter::LastWeek => chrono::Duration::days(7),
+            EpisodeDateFilter::LastMonth => chrono::Duration::days(30),
+            EpisodeDateFilter::LastYear => chrono::Duration::days(365),
+        };
+        episode.published_date().is_some_and(|date| date >= now - max_age)
+    }
+}
+
+pub struct App {
+    pub should_quit: bool,
+    pub podcasts: Vec<Podcast>,
+    selected_podcast: Option<Selection<PodcastKey>>,
+    selected_episode: Option<Selection<EpisodeKey>>,
+    pub playing_episode: Option<(String, String)>, // (podcast title, episode title)
+    pub playback_progress: Option<PlaybackProgress>,
+    pub compact_player: bool, // Single-line player, leaving more rows for the panels
+    pub show_notes_fullscreen: bool, // Reading mode: show notes fill the content area
+    pub focused_panel: FocusedPanel,
+    pub show_notes_state: ScrollableParagraphState,
+    pub show_notes_search_input: Option<InputLine>, // Some while typing a '/' search
+    pub episode_search_input: Option<InputLine>,    // Some while typing a '/' filter in Episodes
+    pub episode_query: Option<String>, // Only episodes matching this are listed; per podcast
+    pub date_filter: EpisodeDateFilter,
+    pub show_hidden_episodes: bool,       // Toggled with 'H'
+    pub hide_explicit: bool,              // Toggled with 'E', defaults to the config setting
+    pub language_filter: Option<String>,  // Set with ':lang <code>', limits the Podcasts panel
+    pub category_filter: Option<String>,  // Set with ':category <name>', same idea
+    pub command_input: Option<InputLine>, // Some while typing a ':' command
+    pub status_message: Option<String>,
+    clipboard: Option<String>, // Text waiting to be sent to the terminal's clipboard
+    welcome: bool, // First run: greet with ways to add podcasts while the library is empty
+    popups: Vec<Popup>, // Bottom to top
+    pub spinner_frame: usize, // Advanced while background tasks are running
+    pub config: Config,
+    pub theme: Theme,
+    pub needs_redraw: bool, // Set by input and events, cleared after each draw
+    // Formatted show notes keyed by episode and wrap width; filled lazily while rendering
+    show_notes_cache: RefCell<ShowNotesCache>,
+    // Inner width of the Show Notes panel at the last draw, so key handlers see the same lines
+    show_notes_width: Cell<usize>,
+    // Visible rows inside the content panels at the last draw, used for paging
+    page_height: Cell<usize>,
+    help_page_height: Cell<usize>, // Bindings the help popup had room for at the last draw
+    // First visible row of the Episodes list at the last draw; kept so it doesn't jump
+    episode_list_offset: Cell<usize>,
+    fetcher: Arc<dyn FeedFetcher + Send + Sync>,
+    factory: PodcastFactory,
+    refresh_schedule: RefreshSchedule,
+    refresh_outcomes: HashMap<PodcastURL, RefreshOutcome>,
+    bulk_runs: Vec<BulkRun>, // Imports and refresh-alls still waiting for some feeds
+    tasks: TaskTracker,
+    task_handles: HashMap<TaskId, tokio::task::AbortHandle>, // For cancelling running tasks
+    downloader: Arc<dyn EpisodeDownloader>,
+    downloads: HashMap<EpisodeKey, DownloadState>,
+    // Downloads that got a 403 and wait for their feed to hand out a fresh link, with the
+    // error to report if that doesn't work out
+    enclosure_refreshes: HashMap<EpisodeKey, String>,
+    refreshed_enclosures: HashSet<EpisodeKey>, // Retried once with a fresh link already
+    download_queue: DownloadQueue,
+    episode_states: EpisodeStateStore,
+    trash: Option<Trash>, // None drops unsubscribed podcasts for good (tests)
+    backups: Option<Backups>, // None skips snapshots before bulk operations (tests)
+    instance_socket: Option<InstanceSocket>, // Where `rustero add` reaches this instance
+    read_only: bool,      // Another instance owns the data directory; nothing is written there
+    save_dir: Option<PathBuf>, // Where pipeline Save writes; None when read-only
+    search_history: InputHistory,
+    command_history: InputHistory,
+    // Background tasks report back through this channel, drained by the UI loop
+    event_tx: UnboundedSender<AppEvent>,
+    event_rx: UnboundedReceiver<AppEvent>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    pub fn new() -> App {
+        App::with_config(Config::default())
+    }
+
+    pub fn with_config(config: Config) -> App {
+        let (event_tx, event_rx) = unbounded_channel();
+        let factory =
+            PodcastFactory::new().with_tracking_prefix_stripping(config.strip_tracking_prefixes);
+        App {
+            should_quit: false,
+            podcasts: Vec::new(),
+            selected_podcast: None,
+            selected_episode: None,
+            playing_episode: None,
+            playback_progress: None,
+            compact_player: config.compact_player,
+            show_notes_fullscreen: false,
+            focused_panel: FocusedPanel::default(),
+            show_notes_state: ScrollableParagraphState::default(),
+            show_notes_search_input: None,
+            episode_search_input: None,
+            episode_query: None,
+            date_filter: EpisodeDateFilter::default(),
+            show_hidden_episodes: false,
+            hide_explicit: config.hide_explicit,
+            language_filter: None,
+            category_filter: None,
+            command_input: None,
+            popups: Vec::new(),
+            spinner_frame: 0,
+            status_message: None,
+            clipboard: None,
+            welcome: false,
+            theme: Theme::from_name(config.theme),
+            config,
+            needs_redraw: true, // Always draw the first frame
+            show_notes_cache: RefCell::new(HashMap::new()),
+            show_notes_width: Cell::new(0),
+            page_height: Cell::new(1),
+            help_page_height: Cell::new(1),
+            episode_list_offset: Cell::new(0),
+            fetcher: Arc::new(HttpFeedFetcher::new()),
+            factory,
+            refresh_schedule: RefreshSchedule::new(),
+            refresh_outcomes: HashMap::new(),
+            bulk_runs: Vec::new(),
+            tasks: TaskTracker::new(),
+            task_handles: HashMap::new(),
+            downloader: Arc::new(HttpEpisodeDownloader::new()),
+            downloads: HashMap::new(),
+            enclosure_refreshes: HashMap::new(),
+            refreshed_enclosures: HashSet::new(),
+            download_queue: DownloadQueue::new(),
+            episode_states: EpisodeStateStore::new(),
+            trash: None,
+            backups: None,
+            instance_socket: None,
+            read_only: false,
+            save_dir: None,
+            search_history: InputHistory::default(),
+            command_history: InputHistory::default(),
+            event_tx,
+            event_rx,
+        }
+    }
+
+    // Builder method, e.g. to run the UI against a FakeFetcher
+    pub fn with_fetcher(mut self, fetcher: Arc<dyn FeedFetcher + Send + Sync>) -> Self {
+        self.fetcher = fetcher;
+        self
+    }
+
+    pub fn with_downloader(mut self, downloader: Arc<dyn EpisodeDownloader>) -> Self {
+        self.downloader = downloader;
+        self
+    }
+
+    // Builder method; without it stars and other episode state live in memory only
+    pub fn with_episode_states(mut self, episode_states: EpisodeStateStore) -> Self {
+        self.episode_states = episode_states;
+        self
+    }
+
+    // Builder method: `reason` says why the data directory can't be written (another
+    // instance holds it). The caller leaves out the state file, trash and backups.
+    pub fn with_read_only(mut self, reason: Option<String>) -> Self {
+        if let Some(reason) = reason {
+            self.status_message = Some(format!("{}; changes won't be saved", reason));
+            self.read_only = true;
+        }
+        self
+    }
+
+    // Builder method: the writable data directory of the profile, for the pipeline's Save step
+    pub fn with_save_dir(mut self, save_dir: PathBuf) -> Self {
+        self.save_dir = Some(save_dir);
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    // Builder method: show the welcome screen instead of empty panels until a podcast arrives
+    pub fn with_welcome(mut self, welcome: bool) -> Self {
+        self.welcome = welcome;
+        self
+    }
+
+    // Feed refreshes are labelled with the feed URL
+    pub fn is_refreshing(&self, url: &PodcastURL) -> bool {
+        self.tasks.is_running_for(TaskKind::Refresh, url.as_str())
+    }
+
+    pub fn showing_welcome(&self) -> bool {
+        self.welcome && self.podcasts.is_empty()
+    }
+
+    pub fn with_trash(mut self, trash: Trash) -> Self {
+        self.trash = Some(trash);
+        self
+    }
+
+    pub fn with_backups(mut self, backups: Backups) -> Self {
+        self.backups = Some(backups);
+        self
+    }
+
+    // Snapshots the data directory before a bulk change. A failed snapshot cancels the
+    // operation, since the point is being able to roll it back.
+    fn backup_before(&mut self, reason: &str) -> bool {
+        let Some(backups) = &self.backups else {
+            return true;
+        };
+        match backups.snapshot(reason, Utc::now()) {
+            Ok(path) => {
+                log::info!("Backed up data before {} to {}", reason, path.display());
+                true
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Not doing {}: {}", reason, e));
+                false
+            }
+        }
+    }
+
+    pub fn episode_states(&self) -> &EpisodeStateStore {
+        &self.episode_states
+    }
+
+    pub fn event_sender(&self) -> UnboundedSender<AppEvent> {
+        self.event_tx.clone()
+    }
+
+    // Applies everything background tasks reported since the last call
+    pub fn drain_events(&mut self) {
+        while let Ok(event) = self.event_rx.try_recv() {
+            self.handle_event(event);
+        }
+    }
+
+    pub fn handle_event(&mut self, event: AppEvent) {
+        self.request_redraw();
+        match event {
+            AppEvent::PodcastSubscribed(podcast) => {
+                self.status_message = Some(format!("Subscribed to '{}'", podcast.title()));
+                self.track_new_episodes(&podcast);
+                let url = podcast.url().clone();
+                if self.podcasts.iter().any(|p| p.url() == podcast.url()) {
+                    self.replace_podcast(podcast);
+                } else {
+                    self.podcasts.push(podcast);
+                    self.apply_podcast_order();
+                }
+                self.finish_bulk_feed(&url, None);
+            }
+            AppEvent::SubscriptionFailed { url, error } => {
+                log::warn!(feed = url.as_str(); "Subscribing to {} failed: {}", url, error);
+                self.status_message = Some(format!("Could not subscribe to {}: {}", url, error));
+                self.finish_bulk_feed(&url, Some(&error));
+            }
+            AppEvent::PodcastRefreshed(podcast) => {
+                let url = podcast.url().clone();
+                let new_episodes = self.count_new_episodes(&podcast);
+                for run in &mut self.bulk_runs {
+                    run.record_new_episodes(&url, new_episodes);
+                }
+                self.track_new_episodes(&podcast);
+                self.refresh_schedule.reschedule(&podcast, Instant::now());
+                self.refresh_outcomes
+                    .insert(podcast.url().clone(), RefreshOutcome { at: Utc::now(), error: None });
+                self.replace_podcast(podcast);
+                self.finish_bulk_feed(&url, None);
+                self.finish_enclosure_refreshes(&url, true);
+            }
+            AppEvent::SubscribeRequested(url) => self.offer_subscription(url),
+            AppEvent::FeedTimed { url, timings } => {
+                log::debug!("{} took {}", url, timings);
+                for run in &mut self.bulk_runs {
+                    run.record_timings(&url, timings);
+                }
+            }
+            AppEvent::RefreshFailed { url, error } => {
+                self.finish_bulk_feed(&url, Some(&error));
+                self.finish_enclosure_refreshes(&url, false);
+                // Background refreshes stay quiet; try again after the usual interval
+                log::warn!(feed = url.as_str(); "Refreshing {} failed: {}", url, error);
+                self.refresh_outcomes
+                    .insert(url.clone(), RefreshOutcome { at: Utc::now(), error: Some(error) });
+                if let Some(podcast) = self.podcasts.iter().find(|p| p.url() == &url) {
+                    self.refresh_schedule.reschedule(podcast, Instant::now());
+                }
+                // A 429/503 with Retry-After may ask for a longer pause than that
+                if let Some(until) = self.fetcher.fresh_until(url.as_str()) {
+                    let due = self.refresh_schedule.next_due(&url);
+                    if due.is_none_or(|due| due < until) {
+                        self.refresh_schedule.postpone_until(&url, until);
+                    }
+                }
+            }
+            AppEvent::EpisodeDownloaded { episode, path } => {
+                self.refreshed_enclosures.remove(&episode);
+                self.downloads.insert(episode, DownloadState::Downloaded(path));
+                self.start_queued_downloads();
+            }
+            AppEvent::EpisodeDownloadIncomplete { episode, path, reason } => {
+                self.refreshed_enclosures.remove(&episode);
+                let title = self.episode_title(&episode).unwrap_or_default();
+                self.status_message =
+                    Some(format!("'{}' is {}; press D to download it again", title, reason));
+                self.downloads.insert(episode, DownloadState::Corrupt { path, reason });
+                self.start_queued_downloads();
+            }
+            AppEvent::EpisodeDownloadFailed { episode, error } => {
+                self.download_failed(episode, error);
+                self.start_queued_downloads();
+            }
+            AppEvent::EnclosureForbidden { episode, error } => {
+                self.refresh_expired_enclosure(episode, error);
+                self.start_queued_downloads();
+            }
+            AppEvent::TaskStarted { id, kind, label } => self.tasks.started(id, kind, label),
+            AppEvent::TaskProgress { id, done, total } => self.tasks.progress(id, done, total),
+            AppEvent::TaskFinished { id, error } => {
+                self.task_handles.remove(&id);
+                self.tasks.finished(id, error);
+            }
+        }
+    }
+
+    // Aborts a running background task. Unless it already reported back, its result is
+    // dropped and only the task's status changes.
+    pub fn cancel_task(&mut self, id: TaskId) {
+        let Some(handle) = self.task_handles.remove(&id) else {
+            return;
+        };
+        handle.abort();
+        self.tasks.cancelled(id);
+        let Some(task) = self.tasks.get(id).cloned() else {
+            return;
+        };
+        self.status_message = Some(format!("Cancelled {} {}", task.kind.verb(), task.label));
+        // Drop the partial file unless the next attempt can continue it
+        let episode = self.downloads.iter().find_map(|(episode, state)| match state {
+            DownloadState::Downloading { task, dest } if *task == id => {
+                Some((episode.clone(), dest.clone()))
+            }
+            _ => None,
+        });
+        if let Some((episode, dest)) = episode {
+            discard_partial_unless_resumable(&dest);
+            self.downloads.remove(&episode);
+            self.start_queued_downloads();
+        }
+        if matches!(task.kind, TaskKind::Subscribe | TaskKind::Refresh) {
+            self.finish_bulk_feed(&PodcastURL::new(&task.label), Some("cancelled"));
+        }
+        // A cancelled refresh waits for its next regular turn instead of restarting right away
+        if task.kind == TaskKind::Refresh
+            && let Some(podcast) = self.podcasts.iter().find(|p| p.url().as_str() == task.label)
+        {
+            self.refresh_schedule.reschedule(podcast, Instant::now());
+        }
+        self.request_redraw();
+    }
+
+    // Lets `rustero add <link>` started elsewhere hand its feed to this instance. Needs the
+    // async runtime; the socket is removed again when the app is dropped.
+    pub fn listen_for_links(&mut self, socket: &Path) {
+        match listen(socket, self.event_tx.clone()) {
+            Ok(guard) => self.instance_socket = Some(guard),
+            Err(e) => log::warn!("Not accepting links from other processes: {}", e),
+        }
+    }
+
+    // Run once at startup: deletes partial downloads that can't be resumed
+    pub fn reconcile_partial_downloads(&mut self) {
+        let cleanup = reconcile_partial_files(&self.config.download_dir());
+        if cleanup.removed > 0 {
+            log::info!("Removed {} stale partial download file(s)", cleanup.removed);
+        }
+        if cleanup.kept > 0 {
+            log::info!("{} partial download(s) can be resumed", cleanup.kept);
+        }
+    }
+
+    pub fn download_state(&self, episode: &EpisodeKey) -> Option<&DownloadState> {
+        self.downloads.get(episode)
+    }
+
+    // How many of the podcast's episodes are downloaded and how many bytes they take up.
+    // Files deleted behind our back don't count.
+    pub fn disk_usage(&self, podcast: &PodcastURL) -> (usize, u64) {
+        self.downloads
+            .iter()
+            .filter(|(key, _)| &key.podcast == podcast)
+            .filter_map(|(_, state)| match state {
+                DownloadState::Downloaded(path) => std::fs::metadata(path).ok(),
+                _ => None,
+            })
+            .fold((0, 0), |(count, bytes), file| (count + 1, bytes + file.len()))
+    }
+
+    fn episode_title(&self, key: &EpisodeKey) -> Option<String> {
+        self.episode(key).map(|(_, e)| e.title().to_string())
+    }
+
+    // Saves the selected episode's enclosure under the download directory, in the background
+    pub fn download_selected_episode(&mut self) {
+        // Not necessarily of the selected podcast, e.g. in Favorites
+        let Some((podcast, episode)) = self.selected_entry() else {
+            return;
+        };
+        let item = self.download_item(podcast, episode, DownloadPriority::User);
+        self.queue_download(item);
+    }
+
+    // Queues every episode of the selected podcast that isn't hidden or downloaded yet.
+    // They line up behind single downloads and run `max_parallel_downloads` at a time.
+    // Asks before queueing the selected podcast's NEW episodes that aren't hidden or
+    // downloaded yet, with how much that is
+    pub fn confirm_download_new_episodes(&mut self) {
+        let Some(podcast) = self.selected_podcast() else {
+            return;
+        };
+        let key = |e: &Episode| EpisodeKey::new(podcast.url(), e.id());
+        let episodes: Vec<&Episode> = podcast
+            .episodes()
+            .iter()
+            .filter(|e| self.episode_states.is_new(&key(e)))
+            .filter(|e| !self.episode_states.is_hidden(&key(e)))
+            .filter(|e| {
+                self.download_state(&key(e)).is_none_or(|s| matches!(s, DownloadState::Failed(_)))
+            })
+            .collect();
+        if episodes.is_empty() {
+            self.status_message =
+                Some(format!("No new episodes to download from '{}'", podcast.title()));
+            return;
+        }
+        let total: u64 = episodes.iter().filter_map(|e| e.size_in_bytes()).sum();
+        let unknown = episodes.iter().filter(|e| e.size_in_bytes().is_none()).count();
+        let size = match unknown {
+            0 => format_size(total),
+            n if n == episodes.len() => "size unknown".to_string(),
+            n => format!("{}, {} of unknown size", format_size(total), n),
+        };
+        let dialog = ConfirmDialog::new(
+            "Download new episodes",
+            format!(
+                "Download {} new episode(s) of '{}' ({})?",
+                episodes.len(),
+                podcast.title(),
+                size
+            ),
+        );
+        let ids = episodes.iter().map(|e| e.id().clone()).collect();
+        let action = ConfirmAction::DownloadEpisodes(podcast.url().clone(), ids);
+        self.push_popup(Popup::Confirm(action, dialog));
+    }
+
+    fn download_episodes(&mut self, url: &PodcastURL, episodes: &[EpisodeID]) {
+        let Some(podcast) = self.podcasts.iter().find(|p| p.url() == url) else {
+            return;
+        };
+        let items: Vec<QueuedDownload> = podcast
+            .episodes()
+            .iter()
+            .filter(|e| episodes.contains(e.id()))
+            .map(|e| self.download_item(podcast, e, DownloadPriority::Auto))
+            .collect();
+        let title = podcast.title().to_string();
+        let queued = items.into_iter().map(|item| self.queue_download(item)).filter(|&q| q).count();
+        self.status_message = Some(match queued {
+            0 => format!("Nothing left to download from '{}'", title),
+            count => format!("Queued {} episode(s) of '{}'", count, title),
+        });
+    }
+
+    fn download_item(
+        &self,
+        podcast: &Podcast,
+        episode: &Episode,
+        priority: DownloadPriority,
+    ) -> QueuedDownload {
+        let dir = self.config.download_dir();
+        let path = |e: &Episode| episode_path(&dir, &self.config.download_template, podcast, e);
+        let plain = path(episode);
+        // Episodes sharing a title (reruns, "Bonus") would write the same file. The oldest
+        // keeps the plain name, later ones are numbered in order of publication.
+        let key = |e: &Episode| (e.published_date(), e.id().to_string());
+        let older = |e: &&Episode| key(e) < key(episode);
+        let mut n =
+            1 + podcast.episodes().iter().filter(older).filter(|e| path(e) == plain).count();
+        let numbered = |n| if n == 1 { plain.clone() } else { numbered_path(&plain, n) };
+        let key = EpisodeKey::new(podcast.url(), episode.id());
+        // Without {podcast} in the template, other feeds can land on it too
+        while self.path_taken(&numbered(n), &key) {
+            n += 1;
+        }
+        QueuedDownload {
+            episode: key,
+            title: episode.title().to_string(),
+            url: episode.audio_url().to_string(),
+            dest: numbered(n),
+            priority,
+            expected_size: episode.size_in_bytes(),
+        }
+    }
+
+    // Whether another episode is queued, downloading or saved at `path`
+    fn path_taken(&self, path: &Path, episode: &EpisodeKey) -> bool {
+        self.download_queue.items().any(|q| &q.episode != episode && q.dest == path)
+            || self.downloads.iter().any(|(id, state)| {
+                id != episode
+                    && match state {
+                        DownloadState::Downloading { dest, .. }
+                        | DownloadState::Downloaded(dest) => dest == path,
+                        DownloadState::Corrupt { path: dest, .. } => dest == path,
+                        DownloadState::Queued | DownloadState::Failed(_) => false,
+                    }
+            })
+    }
+
+    // Adds a download to the queue unless the episode is already queued, running or saved.
+    // False if it was skipped.
+    pub fn queue_download(&mut self, item: QueuedDownload) -> bool {
+        if let Some(
+            DownloadState::Queued
+            | DownloadState::Downloading { .. }
+            | DownloadState::Downloaded(_),
+        ) = self.downloads.get(&item.episode)
+        {
+            return false;
+        }
+        self.downloads.insert(item.episode.clone(), DownloadState::Queued);
+        self.download_queue.push(item);
+        self.start_queued_downloads();
+        true
+    }
+
+    pub fn download_queue(&self) -> &DownloadQueue {
+        &self.download_queue
+    }
+
+    // Starts queued downloads until `max_parallel_downloads` are running
+    fn start_queued_downloads(&mut self) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let running = |downloads: &HashMap<EpisodeKey, DownloadState>| {
+            downloads.values().filter(|s| matches!(s, DownloadState::Downloading { .. })).count()
+        };
+        while running(&self.downloads) < self.config.max_parallel_downloads.max(1) {
+            let Some(item) = self.download_queue.pop() else {
+                break;
+            };
+            self.start_download(&runtime, item);
+        }
+    }
+
+    fn start_download(&mut self, runtime: &tokio::runtime::Handle, item: QueuedDownload) {
+        let QueuedDownload { episode: episode_id, title, url, dest, expected_size, .. } = item;
+        let tx = self.event_tx.clone();
+        let id = self.tasks.next_id();
+        let _ = tx.send(AppEvent::TaskStarted { id, kind: TaskKind::Download, label: title });
+        let downloader = self.downloader.clone();
+        let downloading = DownloadState::Downloading { task: id, dest: dest.clone() };
+        self.downloads.insert(episode_id.clone(), downloading);
+        let handle = runtime.spawn(async move {
+            // One event per percent (or per MiB without a Content-Length) is plenty for the UI
+            let last_sent = std::sync::atomic::AtomicU64::new(0);
+            let progress_tx = tx.clone();
+            let progress = move |done: u64, total: Option<u64>| {
+                let step = total.map_or(1 << 20, |total| (total / 100).max(1));
+                let last = last_sent.load(std::sync::atomic::Ordering::Relaxed);
+                if done - last >= step || Some(done) == total {
+                    last_sent.store(done, std::sync::atomic::Ordering::Relaxed);
+                    let _ = progress_tx.send(AppEvent::TaskProgress { id, done, total });
+                }
+            };
+            let result = downloader.download(&url, &dest, &progress).await;
+            let (event, error) = match result {
+                Ok(file) => match file.check_complete(expected_size) {
+                    Ok(()) => {
+                        (AppEvent::EpisodeDownloaded { episode: episode_id, path: dest }, None)
+                    }
+                    Err(reason) => (
+                        AppEvent::EpisodeDownloadIncomplete {
+                            episode: episode_id,
+                            path: dest,
+                            reason: reason.clone(),
+                        },
+                        Some(reason),
+                    ),
+                },
+                Err(e @ EpisodeDownloadError::Status(reqwest::StatusCode::FORBIDDEN)) => (
+                    AppEvent::EnclosureForbidden { episode: episode_id, error: e.to_string() },
+                    Some(e.to_string()),
+                ),
+                Err(e) => (
+                    AppEvent::EpisodeDownloadFailed { episode: episode_id, error: e.to_string() },
+                    Some(e.to_string()),
+                ),
+            };
+            let _ = tx.send(event);
+            let _ = tx.send(AppEvent::TaskFinished { id, error });
+        });
+        self.task_handles.insert(id, handle.abort_handle());
+    }
+
+    fn download_failed(&mut self, episode: EpisodeKey, error: String) {
+        self.refreshed_enclosures.remove(&episode);
+        let resumable = match self.downloads.get(&episode) {
+            Some(DownloadState::Downloading { dest, .. }) => discard_partial_unless_resumable(dest),
+            _ => false,
+        };
+        self.status_message = Some(if resumable {
+            format!("Download failed: {} (press D to resume)", error)
+        } else {
+            format!("Download failed: {}", error)
+        });
+        self.downloads.insert(episode, DownloadState::Failed(error));
+    }
+
+    // Some hosts sign enclosure links with a token that expires, so a 403 often just means
+    // the feed we have is too old. Re-fetch it for a fresh link and retry once.
+    fn refresh_expired_enclosure(&mut self, episode: EpisodeKey, error: String) {
+        let Some(title) = self.episode_title(&episode) else {
+            self.download_failed(episode, error);
+            return;
+        };
+        if self.refreshed_enclosures.contains(&episode) {
+            self.download_failed(episode, error);
+            return;
+        }
+        self.status_message = Some(format!("Fetching a fresh link for '{}'", title));
+        let feed = episode.podcast.clone();
+        self.refreshed_enclosures.insert(episode.clone());
+        self.downloads.insert(episode.clone(), DownloadState::Queued);
+        self.enclosure_refreshes.insert(episode, error);
+        self.refresh_podcasts(vec![feed]);
+    }
+
+    // Retries the downloads waiting for `feed` with the links it has now, or gives up on
+    // them if the feed couldn't be fetched or dropped the episode
+    fn finish_enclosure_refreshes(&mut self, feed: &PodcastURL, refreshed: bool) {
+        let waiting: Vec<EpisodeKey> =
+            self.enclosure_refreshes.keys().filter(|key| &key.podcast == feed).cloned().collect();
+        for episode in waiting {
+            let Some(error) = self.enclosure_refreshes.remove(&episode) else {
+                continue;
+            };
+            let item = self
+                .episode(&episode)
+                .filter(|_| refreshed)
+                .map(|(p, e)| self.download_item(p, e, DownloadPriority::User));
+            match item {
+                Some(item) => {
+                    self.downloads.remove(&episode);
+                    self.queue_download(item);
+                }
+                None => self.download_failed(episode, error),
+            }
+        }
+    }
+
+    // Cancels the selected episode's download, whether it is running or still queued
+    pub fn cancel_selected_download(&mut self) {
+        let Some(episode) = self.selected_episode_key() else {
+            return;
+        };
+        match self.downloads.get(&episode) {
+            Some(DownloadState::Downloading { task, .. }) => self.cancel_task(*task),
+            Some(DownloadState::Queued) => self.unqueue_download(&episode),
+            _ => {}
+        }
+    }
+
+    fn unqueue_download(&mut self, episode: &EpisodeKey) {
+        if let Some(item) = self.download_queue.remove(episode) {
+            self.downloads.remove(episode);
+            self.status_message = Some(format!("Removed '{}' from the download queue", item.title));
+        }
+    }
+
+    // What the task panel lists: running tasks, then queued downloads, then finished tasks
+    pub fn task_rows(&self) -> Vec<TaskRow<'_>> {
+        let (running, finished): (Vec<_>, Vec<_>) =
+            self.tasks.list().into_iter().partition(|t| t.is_running());
+        running
+            .into_iter()
+            .map(TaskRow::Task)
+            .chain(self.download_queue.items().map(TaskRow::Queued))
+            .chain(finished.into_iter().map(TaskRow::Task))
+            .collect()
+    }
+
+    pub fn tasks(&self) -> &TaskTracker {
+        &self.tasks
+    }
+
+    // Steps the activity spinner; only worth a redraw while something is running
+    pub fn advance_spinner(&mut self) {
+        if self.tasks.is_busy() {
+            self.spinner_frame = self.spinner_frame.wrapping_add(1);
+            self.request_redraw();
+        }
+    }
+
+    pub fn last_refresh(&self, url: &PodcastURL) -> Option<&RefreshOutcome> {
+        self.refresh_outcomes.get(url)
+    }
+
+    // Swaps in a refreshed copy of a feed. If the user is browsing it, the selected episode
+    // (by GUID) stays selected and on the same screen row, and the show notes keep their
+    // scroll position. Played/starred/downloaded state is keyed by GUID, so it carries over
+    // to episodes the feed retitled or re-uploaded.
+    fn replace_podcast(&mut self, podcast: Podcast) {
+        let Some(index) = self.podcasts.iter().position(|p| p.url() == podcast.url()) else {
+            return;
+        };
+        self.log_episode_changes(&self.podcasts[index], &podcast);
+        let is_selected = self.selected_podcast_index() == Some(index);
+        let old_row = self.selected_episode_index();
+        self.podcasts[index] = podcast;
+        if !is_selected {
+            return;
+        }
+        // The selection follows its episode by GUID
+        let new_row = self.selected_episode_index().or_else(|| {
+            // The episode left the feed: stay on the same row rather than jumping to the top
+            let count = self.visible_episodes().len();
+            let row = old_row.filter(|_| count > 0).map(|row| row.min(count - 1));
+            self.set_selected_episode_index(row);
+            self.show_notes_state.reset();
+            row
+        });
+        if let (Some(old_row), Some(new_row)) = (old_row, new_row) {
+            // Episodes added or removed above the selection move the list by as much
+            let offset = self.episode_list_offset.get() + new_row;
+            self.episode_list_offset.set(offset.saturating_sub(old_row));
+        }
+    }
+
+    // Logs episodes a refresh changed in place (typo fixes, re-uploaded audio) and drops
+    // their formatted show notes
+    fn log_episode_changes(&self, old: &Podcast, new: &Podcast) {
+        let old_episodes: HashMap<&EpisodeID, &Episode> =
+            old.episodes().iter().map(|e| (e.id(), e)).collect();
+        let mut cache = self.show_notes_cache.borrow_mut();
+        for episode in new.episodes() {
+            let Some(old_episode) = old_episodes.get(episode.id()) else {
+                continue;
+            };
+            let changed = old_episode.changed_fields(episode);
+            if changed.is_empty() {
+                continue;
+            }
+            log::info!(
+                "{}: '{}' changed its {}",
+                new.title(),
+                old_episode.title(),
+                changed.join(", ")
+            );
+            if changed.contains(&"enclosure")
+                && matches!(
+                    self.downloads.get(&EpisodeKey::new(new.url(), episode.id())),
+                    Some(DownloadState::Downloaded(_))
+                )
+            {
+                log::info!("The downloaded copy of '{}' is the old audio", episode.title());
+            }
+            if changed.contains(&"description") {
+                cache.retain(|(id, _), _| id != episode.id());
+            }
+        }
+    }
+
+    // Bracketed paste: a pasted feed URL opens the "Subscribe?" prompt
+    pub fn on_paste(&mut self, text: &str) {
+        if let Some(input) = self
+            .show_notes_search_input
+            .as_mut()
+            .or(self.episode_search_input.as_mut())
+            .or(self.command_input.as_mut())
+        {
+            input.insert_str(text);
+            self.request_redraw();
+            return;
+        }
+        if let Some(url) = pasted_url(text) {
+            self.offer_subscription(url);
+        }
+    }
+
+    // "Subscribe?" prompt for a feed that was pasted or sent by `rustero add`
+    pub fn offer_subscription(&mut self, url: PodcastURL) {
+        self.request_redraw();
+        if self.podcasts.iter().any(|p| p.url() == &url) {
+            self.status_message = Some(format!("Already subscribed to {}", url));
+        } else {
+            let dialog = ConfirmDialog::new("Subscribe", format!("Subscribe to {}?", url))
+                .with_default(ConfirmChoice::Yes);
+            self.push_popup(Popup::Confirm(ConfirmAction::Subscribe(url), dialog));
+        }
+    }
+
+    // Runs EvalUrl -> Download in the background; the result arrives as an AppEvent
+    pub fn subscribe(&mut self, url: PodcastURL) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            self.status_message = Some("Cannot subscribe: no async runtime".to_string());
+            return;
+        };
+        self.status_message = Some(format!("Subscribing to {}...", url));
+        self.spawn_download(&runtime, url, TaskKind::Subscribe, |url, result| match result {
+            Ok(podcast) => AppEvent::PodcastSubscribed(podcast),
+            Err(error) => AppEvent::SubscriptionFailed { url, error },
+        });
+    }
+
+    // Reads an OPML file (or plain URL list). Unless its feeds are merged right away, they are
+    // listed first so unwanted ones can be unticked.
+    pub fn import_opml(&mut self, path: &Path, duplicates: DuplicateFeeds) {
+        let feeds = match read_subscription_file(path) {
+            Ok(feeds) => feeds,
+            Err(e) => {
+                self.status_message = Some(e.to_string());
+                return;
+            }
+        };
+        if duplicates == DuplicateFeeds::Ask && feeds.len() > 1 {
+            let source = path.to_path_buf();
+            let feeds = ChecklistState::new(feeds);
+            self.push_popup(Popup::ImportSelection { source, feeds, duplicates });
+        } else {
+            self.import_feeds(path, feeds, duplicates);
+        }
+    }
+
+    // Subscribes to every feed that isn't subscribed yet, in the background. Feeds that
+    // are already subscribed can be refreshed instead.
+    fn import_feeds(&mut self, path: &Path, feeds: Vec<OpmlFeed>, duplicates: DuplicateFeeds) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            self.status_message = Some("Cannot import: no async runtime".to_string());
+            return;
+        };
+        let (existing, new_feeds): (Vec<PodcastURL>, Vec<PodcastURL>) = feeds
+            .into_iter()
+            .map(|feed| feed.url)
+            .partition(|url| self.podcasts.iter().any(|p| p.url() == url));
+        // Importing into an empty library has nothing to roll back to
+        if !self.podcasts.is_empty() && !new_feeds.is_empty() && !self.backup_before("import") {
+            return;
+        }
+        self.status_message = Some(format!(
+            "Importing {} new feed(s) from {} ({} already subscribed)...",
+            new_feeds.len(),
+            path.display(),
+            existing.len()
+        ));
+        self.start_bulk_run("import", &new_feeds);
+        for url in new_feeds {
+            self.spawn_download(&runtime, url, TaskKind::Subscribe, |url, result| match result {
+                Ok(podcast) => AppEvent::PodcastSubscribed(podcast),
+                Err(error) => AppEvent::SubscriptionFailed { url, error },
+            });
+        }
+        if existing.is_empty() {
+            return;
+        }
+        match duplicates {
+            DuplicateFeeds::Refresh => self.refresh_podcasts(existing),
+            DuplicateFeeds::Ask => {
+                let dialog = ConfirmDialog::new(
+                    "Already subscribed",
+                    format!(
+                        "{} feed(s) in the file are already subscribed. Refresh them?",
+                        existing.len()
+                    ),
+                )
+                .with_default(ConfirmChoice::Yes);
+                self.push_popup(Popup::Confirm(ConfirmAction::RefreshFeeds(existing), dialog));
+            }
+        }
+    }
+
+    // Re-downloads the given feeds now, whatever their schedule says
+    pub fn refresh_podcasts(&mut self, urls: Vec<PodcastURL>) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        self.start_bulk_run("refresh", &urls);
+        let now = Instant::now();
+        for url in urls {
+            // Pushes the regular refresh back so the tick doesn't fetch the feed a second time
+            if let Some(podcast) = self.podcasts.iter().find(|p| p.url() == &url) {
+                self.refresh_schedule.reschedule(podcast, now);
+            }
+            self.spawn_download(&runtime, url, TaskKind::Refresh, |url, result| match result {
+                Ok(podcast) => AppEvent::PodcastRefreshed(podcast),
+                Err(error) => AppEvent::RefreshFailed { url, error },
+            });
+        }
+    }
+
+    // Re-downloads every feed regardless of its schedule, except ones the server asked us
+    // to leave alone for now (Cache-Control/Retry-After)
+    pub fn refresh_all_podcasts(&mut self) {
+        let mut urls = Vec::new();
+        for podcast in &self.podcasts {
+            match self.fetcher.fresh_until(podcast.url().as_str()) {
+                Some(until) => self.refresh_schedule.postpone_until(podcast.url(), until),
+                None => urls.push(podcast.url().clone()),
+            }
+        }
+        self.refresh_podcasts(urls);
+    }
+
+    // Re-downloads every feed whose refresh interval has passed
+    pub fn refresh_due_podcasts(&mut self) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let now = Instant::now();
+        self.refresh_schedule.retain(&self.podcasts);
+        self.refresh_schedule.track_new(&self.podcasts, now);
+        for url in self.refresh_schedule.take_due(now) {
+            if let Some(until) = self.fetcher.fresh_until(url.as_str()) {
+                self.refresh_schedule.postpone_until(&url, until);
+                continue;
+            }
+            self.spawn_download(&runtime, url, TaskKind::Refresh, |url, result| match result {
+                Ok(podcast) => AppEvent::PodcastRefreshed(podcast),
+                Err(error) => AppEvent::RefreshFailed { url, error },
+            });
+        }
+    }
+
+    // A single feed needs no "slowest feeds" summary
+    fn start_bulk_run(&mut self, label: &'static str, urls: &[PodcastURL]) {
+        if urls.len() > 1 {
+            self.bulk_runs.push(BulkRun::new(label, urls));
+        }
+    }
+
+    // Once every feed of a bulk run is through, report which ones held it up. A refresh-all
+    // also gets a report of what it found.
+    fn finish_bulk_feed(&mut self, url: &PodcastURL, error: Option<&str>) {
+        for run in &mut self.bulk_runs {
+            run.finished(url, error);
+        }
+        let (done, pending): (Vec<BulkRun>, Vec<BulkRun>) =
+            std::mem::take(&mut self.bulk_runs).into_iter().partition(BulkRun::is_done);
+        self.bulk_runs = pending;
+        for run in done {
+            let name = |url: &PodcastURL| {
+                self.podcasts
+                    .iter()
+                    .find(|p| p.url() == url)
+                    .map_or_else(|| url.to_string(), |p| p.title().to_string())
+            };
+            let summary = run.summary(SLOWEST_FEEDS_REPORTED, name);
+            log::info!("{}", summary);
+            if run.label() == "refresh" {
+                let lines = run.report(name);
+                for line in &lines {
+                    log::info!("{}", line);
+                }
+                self.push_popup(Popup::Report { title: "Refresh finished".to_string(), lines });
+            }
+            self.status_message = Some(summary);
+        }
+    }
+
+    // Flags episodes the feed didn't list the last time it was loaded, in this session or
+    // an earlier one
+    fn track_new_episodes(&mut self, podcast: &Podcast) {
+        let episodes = podcast.episodes().iter().map(|e| e.id());
+        if self.episode_states.track_new_episodes(podcast.url(), episodes)
+            && let Err(e) = self.episode_states.save()
+        {
+            self.status_message = Some(e.to_string());
+        }
+    }
+
+    // Episodes in a refreshed copy of a feed that the stored copy doesn't have
+    fn count_new_episodes(&self, podcast: &Podcast) -> usize {
+        let Some(old) = self.podcasts.iter().find(|p| p.url() == podcast.url()) else {
+            return podcast.episodes().len();
+        };
+        let known: HashSet<&EpisodeID> = old.episodes().iter().map(|e| e.id()).collect();
+        podcast.episodes().iter().filter(|e| !known.contains(e.id())).count()
+    }
+
+    fn spawn_download(
+        &mut self,
+        runtime: &tokio::runtime::Handle,
+        url: PodcastURL,
+        kind: TaskKind,
+        into_event: fn(PodcastURL, Result<Podcast, String>) -> AppEvent,
+    ) {
+        let tx = self.event_tx.clone();
+        let id = self.tasks.next_id();
+        let _ = tx.send(AppEvent::TaskStarted { id, kind, label: url.to_string() });
+        let mut interpreter = PodcastPipelineInterpreter::new(self.fetcher.clone())
+            .with_factory(self.factory.clone())
+            .with_save_dir(self.save_dir.clone().filter(|_| !self.read_only));
+
+        let handle = runtime.spawn(async move {
+            let cmd = PodcastCmd::eval_url(
+                url.clone(),
+                PodcastCmd::download(url.clone(), PodcastCmd::end()),
+            );
+            let result =
+                match run_commands(&cmd, Ok(PipelineData::default()), &mut interpreter).await {
+                    Ok(PipelineData { current_podcast: Some(podcast), timings, .. }) => {
+                        let _ = tx.send(AppEvent::FeedTimed { url: url.clone(), timings });
+                        Ok(podcast)
+                    }
+                    Ok(_) => Err("pipeline finished without a podcast".to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+            let error = result.as_ref().err().cloned();
+            let event = into_event(url, result);
+            // The UI may already be gone on shutdown; nothing left to report to then
+            let _ = tx.send(event);
+            let _ = tx.send(AppEvent::TaskFinished { id, error });
+        });
+        self.task_handles.insert(id, handle.abort_handle());
+    }
+
+    // Stars or unstars the selected episode; starred episodes are collected in Favorites
+    pub fn toggle_star_selected_episode(&mut self) {
+        let Some(id) = self.selected_episode_key() else {
+            return;
+        };
+        self.episode_states.update(&id, |state| state.starred = !state.starred);
+        if let Err(e) = self.episode_states.save() {
+            self.status_message = Some(e.to_string());
+        }
+        if self.favorites_selected() {
+            // The unstarred episode drops out of the list under the cursor
+            self.clamp_episode_selection();
+        } else if self.selected_podcast_index() == Some(self.podcasts.len()) {
+            // Favorites was selected and just lost its last episode, so its row is gone
+            self.set_selected_podcast_index(self.podcasts.len().checked_sub(1));
+            self.set_selected_episode_index(None);
+        }
+    }
+
+    // Hides the selected episode, or brings it back while hidden episodes are shown
+    pub fn toggle_hide_selected_episode(&mut self) {
+        let Some((podcast, episode)) = self.selected_entry() else {
+            return;
+        };
+        let (id, title) =
+            (EpisodeKey::new(podcast.url(), episode.id()), episode.title().to_string());
+        self.episode_states.update(&id, |state| state.hidden = !state.hidden);
+        self.status_message = Some(if self.episode_states.is_hidden(&id) {
+            format!("Hid '{}' (H shows hidden episodes)", title)
+        } else {
+            format!("'{}' is visible again", title)
+        });
+        if let Err(e) = self.episode_states.save() {
+            self.status_message = Some(e.to_string());
+        }
+        self.clamp_episode_selection();
+    }
+
+    // Copies a short description of the selected episode (with the playback position, if it
+    // is the one playing) to the clipboard
+    pub fn share_selected_episode(&mut self) {
+        let Some((podcast, episode)) = self.selected_entry() else {
+            return;
+        };
+        let playing = self
+            .playing_episode
+            .as_ref()
+            .is_some_and(|(show, title)| show == podcast.title() && title == episode.title());
+        let position = self.playback_progress.filter(|_| playing).map(|p| p.position);
+        let text = share_text(podcast, episode, position);
+        self.status_message = Some(format!("Copied: {}", text.replace('\n', " ")));
+        self.clipboard = Some(text);
+    }
+
+    pub fn take_clipboard(&mut self) -> Option<String> {
+        self.clipboard.take()
+    }
+
+    // Filters leave the selection alone: it shows again once its episode is listed again
+    pub fn toggle_show_hidden_episodes(&mut self) {
+        self.show_hidden_episodes = !self.show_hidden_episodes;
+    }
+
+    pub fn toggle_hide_explicit(&mut self) {
+        self.hide_explicit = !self.hide_explicit;
+        self.status_message = Some(
+            if self.hide_explicit {
+                "Hiding explicit episodes"
+            } else {
+                "Showing explicit episodes"
+            }
+            .to_string(),
+        );
+    }
+
+    // After the selected episode dropped out of the list, selects whatever took its row
+    fn clamp_episode_selection(&mut self) {
+        if self.selected_episode_index().is_some() {
+            return;
+        }
+        let count = self.visible_episodes().len();
+        let row = self.selected_episode.as_ref().map(|s| s.row);
+        self.set_selected_episode_index(row.filter(|_| count > 0).map(|row| row.min(count - 1)));
+    }
+
+    // The Favorites row follows the podcasts while anything is starred
+    pub fn favorites_visible(&self) -> bool {
+        self.episode_states.has_starred()
+    }
+
+    pub fn favorites_selected(&self) -> bool {
+        self.favorites_visible() && self.selected_podcast_index() == Some(self.podcasts.len())
+    }
+
+    // Rows of the Podcasts panel as indices into `podcasts`, with `podcasts.len()` standing
+    // for Favorites. Pinned podcasts come first; podcasts outside the language or category
+    // filter are left out.
+    pub fn podcast_rows(&self) -> Vec<usize> {
+        let language = self.language_filter.as_deref();
+        let category = self.category_filter.as_deref();
+        let mut rows: Vec<usize> = self
+            .podcasts
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| language.is_none_or(|l| p.matches_language(l)))
+            .filter(|(_, p)| category.is_none_or(|c| p.has_category(c)))
+            .map(|(i, _)| i)
+            .collect();
+        rows.sort_by_key(|&i| !self.episode_states.is_pinned(self.podcasts[i].url()));
+        rows.extend(self.favorites_visible().then_some(self.podcasts.len()));
+        rows
+    }
+
+    pub fn toggle_pin_selected_podcast(&mut self) {
+        let Some(podcast) = self.selected_podcast() else {
+            return;
+        };
+        let (url, title) = (podcast.url().clone(), podcast.title().to_string());
+        self.episode_states.toggle_pinned(&url);
+        self.status_message = Some(if self.episode_states.is_pinned(&url) {
+            format!("Pinned '{}'", title)
+        } else {
+            format!("Unpinned '{}'", title)
+        });
+        if let Err(e) = self.episode_states.save() {
+            self.status_message = Some(e.to_string());
+        }
+    }
+
+    // Sorts podcasts into the saved custom order; ones that aren't in it keep their relative
+    // order after the rest. The selection stays on the same podcast.
+    pub fn apply_podcast_order(&mut self) {
+        let order = self.episode_states.podcast_order();
+        if order.is_empty() {
+            return;
+        }
+        let position = |p: &Podcast| order.iter().position(|url| url == p.url());
+        self.podcasts.sort_by_key(|p| position(p).unwrap_or(usize::MAX));
+    }
+
+    // Swaps the selected podcast with its neighbour in the panel (Shift+Up/Down) and saves
+    // the new order
+    pub fn move_selected_podcast(&mut self, delta: isize) {
+        self.request_redraw();
+        let rows: Vec<usize> =
+            self.podcast_rows().into_iter().filter(|&i| i < self.podcasts.len()).collect();
+        let Some(row) = self.selected_podcast_row().filter(|&row| row < rows.len()) else {
+            return;
+        };
+        let Some(&target) = row.checked_add_signed(delta).and_then(|r| rows.get(r)) else {
+            return;
+        };
+        // Pinned podcasts stay above the rest, so moves only happen within each group
+        let pinned = |i: usize| self.episode_states.is_pinned(self.podcasts[i].url());
+        if pinned(rows[row]) != pinned(target) {
+            return;
+        }
+        self.podcasts.swap(rows[row], target);
+        let order = self.podcasts.iter().map(|p| p.url().clone()).collect();
+        self.episode_states.set_podcast_order(order);
+        if let Err(e) = self.episode_states.save() {
+            self.status_message = Some(e.to_string());
+        }
+    }
+
+    // Position of the selected podcast within `podcast_rows`
+    pub fn selected_podcast_row(&self) -> Option<usize> {
+        let selected = self.selected_podcast_index()?;
+        self.podcast_rows().iter().position(|&i| i == selected)
+    }
+
+    pub fn set_language_filter(&mut self, language: Option<String>) {
+        self.language_filter = language;
+        let listed = self.apply_podcast_filters();
+        self.status_message = Some(match &self.language_filter {
+            Some(language) => format!("{} podcasts in '{}' (:lang to clear)", listed, language),
+            None => "Showing podcasts in all languages".to_string(),
+        });
+    }
+
+    pub fn set_category_filter(&mut self, category: Option<String>) {
+        self.category_filter = category;
+        let listed = self.apply_podcast_filters();
+        self.status_message = Some(match &self.category_filter {
+            Some(category) => {
+                format!("{} podcasts in '{}' (:category to clear)", listed, category)
+            }
+            None => "Showing podcasts in all categories".to_string(),
+        });
+    }
+
+    // Every category used by a subscribed podcast, sorted, for ':categories'
+    pub fn categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> =
+            self.podcasts.iter().flat_map(|p| p.categories()).map(String::as_str).collect();
+        categories.sort_by_key(|c| c.to_lowercase());
+        categories.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        categories
+    }
+
+    // Moves the selection off a podcast the filters just removed; returns how many are listed
+    fn apply_podcast_filters(&mut self) -> usize {
+        let rows = self.podcast_rows();
+        if self.selected_podcast_index().is_some_and(|i| !rows.contains(&i)) {
+            self.set_selected_podcast_index(rows.first().copied());
+            self.set_selected_episode_index(None);
+            self.show_notes_state.reset();
+        }
+        rows.iter().filter(|&&i| i < self.podcasts.len()).count()
+    }
+
+    fn select_podcast_row(&mut self, row: Option<usize>) {
+        let target = row.and_then(|row| self.podcast_rows().get(row).copied());
+        if target != self.selected_podcast_index() {
+            self.set_selected_podcast_index(target);
+            self.set_selected_episode_index(None); // Reset episode selection
+            self.episode_query = None; // The filter was for the previous podcast's archive
+            self.show_notes_state.reset();
+            self.episode_list_offset.set(0);
+        }
+    }
+
+    pub fn episode(&self, key: &EpisodeKey) -> Option<(&Podcast, &Episode)> {
+        let podcast = self.podcasts.iter().find(|p| p.url() == &key.podcast)?;
+        podcast.episodes().iter().find(|e| e.id() == &key.episode).map(|e| (podcast, e))
+    }
+
+    // Add simple navigation methods
+    pub fn select_next_podcast(&mut self) {
+        let count = self.podcast_rows().len();
+        if count == 0 {
+            return;
+        }
+        let row = match self.selected_podcast_row() {
+            Some(i) if i + 1 < count => i + 1,
+            _ => 0,
+        };
+        self.select_podcast_row(Some(row));
+    }
+
+    pub fn select_prev_podcast(&mut self) {
+        let count = self.podcast_rows().len();
+        if count == 0 {
+            return;
+        }
+        let row = match self.selected_podcast_row() {
+            Some(i) if i > 0 => i - 1,
+            _ => count - 1,
+        };
+        self.select_podcast_row(Some(row));
+    }
+
+    // Moves the podcast selection by `delta` without wrapping (PageUp/PageDown/Home/End)
+    pub fn move_podcast_selection(&mut self, delta: isize) {
+        let row = step_index(self.selected_podcast_row(), delta, self.podcast_rows().len());
+        self.select_podcast_row(row);
+    }
+
+    pub fn move_episode_selection(&mut self, delta: isize) {
+        let count = self.visible_episodes().len();
+        let target = step_index(self.selected_episode_index(), delta, count);
+        if target != self.selected_episode_index() {
+            self.set_selected_episode_index(target);
+            self.show_notes_state.reset();
+        }
+    }
+
+    pub fn select_next_episode(&mut self) {
+        let count = self.visible_episodes().len();
+        if count == 0 {
+            return;
+        }
+        self.set_selected_episode_index(Some(match self.selected_episode_index() {
+            Some(i) if i + 1 < count => i + 1,
+            _ => 0,
+        }));
+        self.show_notes_state.reset();
+    }
+
+    pub fn select_prev_episode(&mut self) {
+        let count = self.visible_episodes().len();
+        if count == 0 {
+            return;
+        }
+        self.set_selected_episode_index(Some(match self.selected_episode_index() {
+            Some(i) if i > 0 => i - 1,
+            _ => count - 1,
+        }));
+        self.show_notes_state.reset();
+    }
+
+    // Index into `podcasts`, or `podcasts.len()` for Favorites; None if nothing is selected
+    // or the selected podcast is gone
+    pub fn selected_podcast_index(&self) -> Option<usize> {
+        match &self.selected_podcast.as_ref()?.key {
+            PodcastKey::Podcast(url) => self.podcasts.iter().position(|p| p.url() == url),
+            PodcastKey::Favorites => Some(self.podcasts.len()),
+        }
+    }
+
+    pub fn set_selected_podcast_index(&mut self, index: Option<usize>) {
+        self.selected_podcast = index.and_then(|row| {
+            let key = match self.podcasts.get(row) {
+                Some(podcast) => PodcastKey::Podcast(podcast.url().clone()),
+                None if row == self.podcasts.len() => PodcastKey::Favorites,
+                None => return None,
+            };
+            Some(Selection { key, row })
+        });
+    }
+
+    // Position of the selected episode in `visible_episodes`; None if it isn't listed
+    pub fn selected_episode_index(&self) -> Option<usize> {
+        let key = &self.selected_episode.as_ref()?.key;
+        self.visible_entries()
+            .iter()
+            .position(|(p, e)| p.url() == &key.podcast && e.id() == &key.episode)
+    }
+
+    // Selecting an episode counts as having seen it, so it loses its NEW marker
+    pub fn set_selected_episode_index(&mut self, index: Option<usize>) {
+        self.selected_episode = index.and_then(|row| {
+            let (podcast, episode) = self.visible_entries().get(row).copied()?;
+            Some(Selection { key: EpisodeKey::new(podcast.url(), episode.id()), row })
+        });
+        if let Some(Selection { key: id, .. }) = &self.selected_episode
+            && self.episode_states.is_new(id)
+        {
+            self.episode_states.update(id, |state| state.new = false);
+            if let Err(e) = self.episode_states.save() {
+                self.status_message = Some(e.to_string());
+            }
+        }
+    }
+
+    // None while Favorites is selected
+    pub fn selected_podcast(&self) -> Option<&Podcast> {
+        self.selected_podcast_index().and_then(|i| self.podcasts.get(i))
+    }
+
+    // Episodes of the selected podcast (or Favorites) that pass the active filters, in display
+    // order. `selected_episode_index()` indexes into this list.
+    pub fn visible_episodes(&self) -> Vec<&Episode> {
+        self.visible_entries().into_iter().map(|(_, e)| e).collect()
+    }
+
+    // `visible_episodes` along with the podcast each episode belongs to
+    pub fn visible_entries(&self) -> Vec<(&Podcast, &Episode)> {
+        let now = Utc::now();
+        let query = self.active_episode_query().map(str::to_lowercase);
+        let shown = |(p, e): &(&Podcast, &Episode)| {
+            let key = EpisodeKey::new(p.url(), e.id());
+            self.date_filter.includes(e, now)
+                && (self.show_hidden_episodes || !self.episode_states.is_hidden(&key))
+                && !(self.hide_explicit && e.is_explicit())
+                && query
+                    .as_deref()
+                    .is_none_or(|q| episode_matches(e, self.episode_states.note(&key), q))
+        };
+        if self.favorites_selected() {
+            let mut starred: Vec<(&Podcast, &Episode)> = self
+                .podcasts
+                .iter()
+                .flat_map(|p| p.episodes().iter().map(move |e| (p, e)))
+                .filter(|(p, e)| self.episode_states.is_starred(&EpisodeKey::new(p.url(), e.id())))
+                .filter(shown)
+                .collect();
+            starred.sort_by_key(|(_, e)| std::cmp::Reverse(e.published_date()));
+            return starred;
+        }
+        self.selected_podcast()
+            .map(|p| p.episodes().iter().map(|e| (p, e)).filter(shown).collect())
+            .unwrap_or_default()
+    }
+
+    // What's being typed filters as you go; otherwise the last confirmed filter applies
+    pub fn active_episode_query(&self) -> Option<&str> {
+        match &self.episode_search_input {
+            Some(input) => Some(input.text()).filter(|t| !t.trim().is_empty()),
+            None => self.episode_query.as_deref(),
+        }
+    }
+
+    pub fn selected_episode(&self) -> Option<&Episode> {
+        self.selected_entry().map(|(_, e)| e)
+    }
+
+    pub fn selected_entry(&self) -> Option<(&Podcast, &Episode)> {
+        self.selected_episode_index().and_then(|i| self.visible_entries().get(i).copied())
+    }
+
+    pub fn selected_episode_key(&self) -> Option<EpisodeKey> {
+        self.selected_entry().map(|(p, e)| EpisodeKey::new(p.url(), e.id()))
+    }
+
+    // Switches to the next date range, keeping the selected episode if it is still visible
+    pub fn cycle_date_filter(&mut self) {
+        self.date_filter = self.date_filter.next();
+        if self.selected_episode_index().is_none() {
+            self.selected_episode = None;
+            self.show_notes_state.reset();
+        }
+    }
+
+    // Show notes of `episode` wrapped to `width`, formatted only once per episode and width
+    pub fn show_notes_lines(&self, episode: &Episode, width: usize) -> Rc<Vec<String>> {
+        let key = (episode.id().clone(), width);
+        self.show_notes_cache
+            .borrow_mut()
+            .entry(key)
+            .or_insert_with(|| {
+                let description = episode.description().unwrap_or("No show notes available.");
+                Rc::new(format_episode_description(description, width))
+            })
+            .clone()
+    }
+
+    // Called while rendering so key handlers work on the lines the user actually sees
+    pub fn set_show_notes_width(&self, width: usize) {
+        self.show_notes_width.set(width);
+    }
+
+    pub fn episode_list_offset(&self) -> usize {
+        self.episode_list_offset.get()
+    }
+
+    pub fn set_episode_list_offset(&self, offset: usize) {
+        self.episode_list_offset.set(offset);
+    }
+
+    pub fn set_help_page_height(&self, height: usize) {
+        self.help_page_height.set(height.max(1));
+    }
+
+    pub fn set_page_height(&self, height: usize) {
+        self.page_height.set(height.max(1));
+    }
+
+    pub fn page_height(&self) -> usize {
+        self.page_height.get()
+    }
+
+    // Formatted show notes of the selected episode as last drawn
+    pub fn current_show_notes_lines(&self) -> Option<Rc<Vec<String>>> {
+        let width = self.show_notes_width.get();
+        self.selected_episode().map(|episode| self.show_notes_lines(episode, width))
+    }
+
+    // Player events may arrive often; only redraw when the displayed second changes
+    pub fn set_playback_progress(&mut self, progress: Option<PlaybackProgress>) {
+        let whole_seconds =
+            |p: Option<PlaybackProgress>| p.map(|p| (p.position.as_secs(), p.duration.as_secs()));
+        if whole_seconds(progress) != whole_seconds(self.playback_progress) {
+            self.request_redraw();
+        }
+        self.playback_progress = progress;
+    }
+
+    pub fn request_redraw(&mut self) {
+        self.needs_redraw = true;
+    }
+
+    // Called every `tick_rate_ms`, independent of input polling.
+    // Periodic work (clock, playback progress) hooks in here.
+    pub fn on_tick(&mut self) {
+        if self.config.auto_refresh {
+            self.refresh_due_podcasts();
+        }
+    }
+
+    // Like `on_key`, but keeps the modifiers text input needs (Ctrl-W, Alt-Left, ...)
+    pub fn on_key_event(&mut self, key: KeyEvent) {
+        // A message stays up until the next key press, then the key hints come back
+        self.status_message = None;
+        if let Some(Popup::Note { .. }) = self.popups.last() {
+            self.request_redraw();
+            return self.on_note_key(key);
+        }
+        let typing = self.popups.is_empty()
+            && (self.show_notes_search_input.is_some()
+                || self.episode_search_input.is_some()
+                || self.command_input.is_some());
+        if !typing {
+            if self.popups.is_empty()
+                && self.focused_panel == FocusedPanel::Podcasts
+                && key.modifiers.contains(KeyModifiers::SHIFT)
+            {
+                match key.code {
+                    KeyCode::Up => return self.move_selected_podcast(-1),
+                    KeyCode::Down => return self.move_selected_podcast(1),
+                    _ => {}
+                }
+            }
+            return self.on_key(key.code);
+        }
+        self.request_redraw();
+        if self.show_notes_search_input.is_some() {
+            self.on_search_input_key(key);
+        } else if self.episode_search_input.is_some() {
+            self.on_episode_search_key(key);
+        } else {
+            self.on_command_input_key(key);
+        }
+    }
+
+    pub fn on_key(&mut self, key: KeyCode) {
+        self.request_redraw();
+        if !self.popups.is_empty() {
+            self.on_popup_key(key);
+            return;
+        }
+        if self.show_notes_search_input.is_some() {
+            self.on_search_input_key(KeyEvent::from(key));
+            return;
+        }
+        if self.episode_search_input.is_some() {
+            self.on_episode_search_key(KeyEvent::from(key));
+            return;
+        }
+        if self.command_input.is_some() {
+            self.on_command_input_key(KeyEvent::from(key));
+            return;
+        }
+        if self.showing_welcome() {
+            self.on_welcome_key(key);
+            return;
+        }
+        match key {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Char('m') => self.compact_player = !self.compact_player,
+            KeyCode::Char('d') => self.cycle_date_filter(),
+            KeyCode::Char('?') => self.push_popup(Popup::Help(ScrollableParagraphState::default())),
+            KeyCode::Char('t') => self.push_popup(Popup::Tasks { selected: 0 }),
+            KeyCode::Char(':') => self.command_input = Some(InputLine::new()),
+            KeyCode::Char('x') | KeyCode::Delete
+                if self.focused_panel == FocusedPanel::Podcasts =>
+            {
+                self.confirm_unsubscribe();
+            }
+            KeyCode::Char('D') if self.focused_panel == FocusedPanel::Episodes => {
+                self.download_selected_episode();
+            }
+            KeyCode::Char('D') if self.focused_panel == FocusedPanel::Podcasts => {
+                self.confirm_download_new_episodes();
+            }
+            KeyCode::Char('h') if self.focused_panel == FocusedPanel::Episodes => {
+                self.toggle_hide_selected_episode();
+            }
+            KeyCode::Char('H') => self.toggle_show_hidden_episodes(),
+            KeyCode::Char('E') => self.toggle_hide_explicit(),
+            KeyCode::Char('a') if self.focused_panel == FocusedPanel::Episodes => {
+                self.edit_selected_episode_note();
+            }
+            KeyCode::Char('a') if self.focused_panel == FocusedPanel::Podcasts => {
+                self.command_input = Some(InputLine::new().with_text("add "));
+            }
+            KeyCode::Char('y') if self.focused_panel == FocusedPanel::Episodes => {
+                self.share_selected_episode();
+            }
+            KeyCode::Char('s') if self.focused_panel == FocusedPanel::Episodes => {
+                self.toggle_star_selected_episode();
+            }
+            KeyCode::Char('c') if self.focused_panel == FocusedPanel::Episodes => {
+                self.cancel_selected_download();
+            }
+            KeyCode::Char('p') if self.focused_panel == FocusedPanel::Podcasts => {
+                self.toggle_pin_selected_podcast()
+            }
+            KeyCode::Char('i') if self.focused_panel == FocusedPanel::Podcasts => {
+                if let Some(url) = self.selected_podcast().map(|p| p.url().clone()) {
+                    self.push_popup(Popup::PodcastInfo(url));
+                }
+            }
+            KeyCode::Char('f') => {
+                self.show_notes_fullscreen = !self.show_notes_fullscreen;
+                if self.show_notes_fullscreen {
+                    self.focused_panel = FocusedPanel::ShowNotes;
+                }
+            }
+            KeyCode::Esc if self.show_notes_state.query().is_some() => {
+                self.show_notes_state.clear_search()
+            }
+            KeyCode::Esc => self.show_notes_fullscreen = false,
+            KeyCode::Tab => self.focused_panel = self.focused_panel.next(),
+            KeyCode::BackTab => self.focused_panel = self.focused_panel.prev(),
+            KeyCode::Down => match self.focused_panel {
+                FocusedPanel::Podcasts => self.select_next_podcast(),
+                FocusedPanel::Episodes => self.select_next_episode(),
+                FocusedPanel::ShowNotes => {
+                    let line_count = self.current_show_notes_lines().map_or(0, |l| l.len());
+                    self.show_notes_state.scroll_down(1, line_count);
+                }
+            },
+            KeyCode::Up => match self.focused_panel {
+                FocusedPanel::Podcasts => self.select_prev_podcast(),
+                FocusedPanel::Episodes => self.select_prev_episode(),
+                FocusedPanel::ShowNotes => self.show_notes_state.scroll_up(1),
+            },
+            KeyCode::PageDown => self.move_focused_selection(self.page_height() as isize),
+            KeyCode::PageUp => self.move_focused_selection(-(self.page_height() as isize)),
+            KeyCode::Home => self.move_focused_selection(isize::MIN),
+            KeyCode::End => self.move_focused_selection(isize::MAX),
+            KeyCode::Char('/') if self.focused_panel == FocusedPanel::ShowNotes => {
+                self.show_notes_search_input = Some(InputLine::new());
+            }
+            // Starts from the current filter so it can be refined
+            KeyCode::Char('/') if self.focused_panel == FocusedPanel::Episodes => {
+                let current = self.episode_query.as_deref().unwrap_or("");
+                self.episode_search_input = Some(InputLine::new().with_text(current));
+            }
+            KeyCode::Char('n') if self.focused_panel == FocusedPanel::ShowNotes => {
+                if let Some(lines) = self.current_show_notes_lines() {
+                    self.show_notes_state.next_match(&lines);
+                }
+            }
+            KeyCode::Char('N') if self.focused_panel == FocusedPanel::ShowNotes => {
+                if let Some(lines) = self.current_show_notes_lines() {
+                    self.show_notes_state.prev_match(&lines);
+                }
+            }
+            // Add more key handlers as needed
+            _ => {}
+        }
+    }
+
+    fn confirm_unsubscribe(&mut self) {
+        let Some(podcast) = self.selected_podcast() else {
+            return;
+        };
+        let dialog = ConfirmDialog::new(
+            "Unsubscribe",
+            format!(
+                "Unsubscribe from '{}'? Its episodes will disappear from the list.",
+                podcast.title()
+            ),
+        );
+        let action = ConfirmAction::Unsubscribe(podcast.url().clone());
+        self.push_popup(Popup::Confirm(action, dialog));
+    }
+
+    pub fn popups(&self) -> &[Popup] {
+        &self.popups
+    }
+
+    pub fn top_popup(&self) -> Option<&Popup> {
+        self.popups.last()
+    }
+
+    pub fn push_popup(&mut self, popup: Popup) {
+        self.popups.push(popup);
+        self.request_redraw();
+    }
+
+    pub fn close_popup(&mut self) -> Option<Popup> {
+        self.request_redraw();
+        self.popups.pop()
+    }
+
+    fn on_popup_key(&mut self, key: KeyCode) {
+        let Some(popup) = self.popups.last_mut() else {
+            return;
+        };
+        match popup {
+            Popup::Confirm(action, dialog) => {
+                // Esc is "No" here, which also closes the dialog
+                if let Some(choice) = dialog.handle_key(key) {
+                    let action = action.clone();
+                    self.close_popup();
+                    if choice == ConfirmChoice::Yes {
+                        self.run_confirmed(action);
+                    }
+                }
+            }
+            Popup::FilePicker(purpose, picker) => match key {
+                KeyCode::Down => picker.select_next(),
+                KeyCode::Up => picker.select_prev(),
+                KeyCode::Home => picker.select_first(),
+                KeyCode::End => picker.select_last(),
+                KeyCode::Char('.') => picker.toggle_hidden_files(),
+                KeyCode::Backspace | KeyCode::Left => picker.go_up(),
+                KeyCode::Enter | KeyCode::Right => {
+                    if let Some(path) = picker.enter() {
+                        let purpose = *purpose;
+                        self.close_popup();
+                        self.on_file_picked(purpose, &path);
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.close_popup();
+                }
+                _ => {}
+            },
+            Popup::ImportSelection { feeds, .. } => match key {
+                KeyCode::Down => feeds.select_next(),
+                KeyCode::Up => feeds.select_prev(),
+                KeyCode::Home => feeds.select_first(),
+                KeyCode::End => feeds.select_last(),
+                KeyCode::Char(' ') => feeds.toggle_selected(),
+                KeyCode::Char('a') => feeds.toggle_all(),
+                KeyCode::Enter => {
+                    if let Some(Popup::ImportSelection { source, feeds, duplicates }) =
+                        self.close_popup()
+                    {
+                        let feeds = feeds.into_checked();
+                        if feeds.is_empty() {
+                            self.status_message = Some("Nothing imported".to_string());
+                        } else {
+                            self.import_feeds(&source, feeds, duplicates);
+                        }
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.close_popup();
+                }
+                _ => {}
+            },
+            Popup::Tasks { selected } => {
+                let current = *selected;
+                if let Some(index) = self.on_task_panel_key(key, current)
+                    && let Some(Popup::Tasks { selected }) = self.popups.last_mut()
+                {
+                    *selected = index;
+                }
+            }
+            // Text input needs the modifiers; `on_key_event` routes keys here
+            Popup::Note { .. } => {
+                let event = KeyEvent::new(key, crossterm::event::KeyModifiers::NONE);
+                self.on_note_key(event);
+            }
+            Popup::Help(_) => self.on_help_key(key),
+            // Read-only popups: any key closes them
+            Popup::PodcastInfo(_) | Popup::Report { .. } => {
+                self.close_popup();
+            }
+        }
+    }
+
+    // The help popup scrolls when the screen is too short for all bindings
+    fn on_help_key(&mut self, key: KeyCode) {
+        let page = self.help_page_height.get();
+        let last_page = help_bindings().count().saturating_sub(page) + 1;
+        let Some(Popup::Help(scroll)) = self.popups.last_mut() else {
+            return;
+        };
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => scroll.scroll_up(1),
+            KeyCode::Down | KeyCode::Char('j') => scroll.scroll_down(1, last_page),
+            KeyCode::PageUp => scroll.scroll_up(page),
+            KeyCode::PageDown => scroll.scroll_down(page, last_page),
+            KeyCode::Home => scroll.reset(),
+            KeyCode::End => scroll.scroll_down(usize::MAX / 2, last_page),
+            _ => {
+                self.close_popup();
+            }
+        }
+    }
+
+    // Returns the new selection, or None once the panel is closed
+    fn on_task_panel_key(&mut self, key: KeyCode, selected: usize) -> Option<usize> {
+        let row_count = self.task_rows().len();
+        let index = selected.min(row_count.saturating_sub(1));
+        // Queued downloads come right after the running tasks
+        let first_queued = self.tasks.list().iter().filter(|t| t.is_running()).count();
+        let queue_index =
+            index.checked_sub(first_queued).filter(|i| *i < self.download_queue.len());
+        match key {
+            KeyCode::Down => Some((index + 1).min(row_count.saturating_sub(1))),
+            KeyCode::Up => Some(index.saturating_sub(1)),
+            KeyCode::Char('+') if let Some(i) = queue_index => {
+                Some(first_queued + self.download_queue.move_up(i))
+            }
+            KeyCode::Char('-') if let Some(i) = queue_index => {
+                Some(first_queued + self.download_queue.move_down(i))
+            }
+            KeyCode::Char('c') | KeyCode::Char('x') | KeyCode::Delete => {
+                let target = match self.task_rows().get(index) {
+                    Some(TaskRow::Queued(item)) => Err(item.episode.clone()),
+                    Some(TaskRow::Task(task)) => Ok(task.id),
+                    None => return Some(index),
+                };
+                match target {
+                    Ok(id) => self.cancel_task(id),
+                    Err(episode) => self.unqueue_download(&episode),
+                }
+                Some(index)
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('t') => {
+                self.close_popup();
+                None
+            }
+            _ => Some(index),
+        }
+    }
+
+    fn run_confirmed(&mut self, action: ConfirmAction) {
+        match action {
+            ConfirmAction::Subscribe(url) => self.subscribe(url),
+            ConfirmAction::Unsubscribe(url) => self.unsubscribe(&url),
+            ConfirmAction::RefreshFeeds(urls) => self.refresh_podcasts(urls),
+            ConfirmAction::DownloadEpisodes(url, episodes) => {
+                self.download_episodes(&url, &episodes)
+            }
+        }
+    }
+
+    pub fn unsubscribe(&mut self, url: &PodcastURL) {
+        let Some(index) = self.podcasts.iter().position(|p| p.url() == url) else {
+            return;
+        };
+        let was_selected = self.selected_podcast_index() == Some(index);
+        let podcast = self.podcasts.remove(index);
+        self.refresh_outcomes.remove(url);
+        self.refresh_schedule.retain(&self.podcasts);
+        self.status_message =
+            Some(match self.trash.as_ref().map(|t| t.put(&podcast, Utc::now())) {
+                Some(Ok(_)) => {
+                    format!("Unsubscribed from '{}' (:restore to undo)", podcast.title())
+                }
+                Some(Err(e)) => format!("Unsubscribed from '{}', but {}", podcast.title(), e),
+                None => format!("Unsubscribed from '{}'", podcast.title()),
+            });
 
-pub struct App {
-    pub should_quit: bool,
-    pub podcasts: Vec<Podcast>,
-    pub selected_podcast_index: Option<usize>,
-    pub selected_episode_index: Option<usize>,
-    pub playing_episode: Option<(String, String)>, // (podcast title, episode title)
-}
+        // Any other selection follows its podcast; this one moves to whatever took the
+        // removed podcast's place, or the new last entry
+        if was_selected {
+            let last = self.podcasts.len().checked_sub(1);
+            self.set_selected_podcast_index(last.map(|last| index.min(last)));
+            self.set_selected_episode_index(None);
+            self.show_notes_state.reset();
+        }
+    }
 
-impl App {
-    pub fn new() -> App {
-        App {
-            should_quit: false,
-            podcasts: Vec::new(),
-            selected_podcast_index: None,
-            selected_episode_index: None,
-            playing_episode: None,
+    // Paging in whichever panel has focus; isize::MIN/MAX jump to the start/end
+    fn move_focused_selection(&mut self, delta: isize) {
+        match self.focused_panel {
+            FocusedPanel::Podcasts => self.move_podcast_selection(delta),
+            FocusedPanel::Episodes => self.move_episode_selection(delta),
+            FocusedPanel::ShowNotes => {
+                let line_count = self.current_show_notes_lines().map_or(0, |l| l.len());
+                if delta < 0 {
+                    self.show_notes_state.scroll_up(delta.unsigned_abs());
+                } else {
+                    // Stop once the last page is visible
+                    let last_page = line_count.saturating_sub(self.page_height()) + 1;
+                    self.show_notes_state.scroll_down(delta as usize, last_page);
+                }
+            }
         }
     }
 
-    // Add simple navigation methods
-    pub fn select_next_podcast(&mut self) {
-        if self.podcasts.is_empty() {
+    fn on_search_input_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.show_notes_search_input.as_mut() else {
             return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let query = self.show_notes_search_input.take().unwrap_or_default();
+                self.search_history.push(query.text());
+                let lines = self.current_show_notes_lines().unwrap_or_default();
+                self.show_notes_state.search(query.text(), &lines);
+            }
+            KeyCode::Esc => self.show_notes_search_input = None,
+            _ => {
+                input.handle_key(key, &self.search_history);
+            }
         }
-        self.selected_podcast_index = Some(match self.selected_podcast_index {
-            Some(i) if i + 1 < self.podcasts.len() => i + 1,
-            _ => 0,
-        });
-        self.selected_episode_index = None; // Reset episode selection
     }
 
-    pub fn select_prev_podcast(&mut self) {
-        if self.podcasts.is_empty() {
+    // Enter keeps the filter, Esc drops it
+    fn on_episode_search_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.episode_search_input.as_mut() else {
             return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let query = self.episode_search_input.take().unwrap_or_default();
+                self.search_history.push(query.text());
+                self.episode_query =
+                    Some(query.text().trim().to_string()).filter(|q| !q.is_empty());
+            }
+            KeyCode::Esc => {
+                self.episode_search_input = None;
+                self.episode_query = None;
+            }
+            _ => {
+                input.handle_key(key, &self.search_history);
+            }
         }
-        self.selected_podcast_index = Some(match self.selected_podcast_index {
-            Some(i) if i > 0 => i - 1,
-            _ => self.podcasts.len() - 1,
-        });
-        self.selected_episode_index = None; // Reset episode selection
+        // The list changed under the selection; start over at the first match
+        self.set_selected_episode_index((!self.visible_episodes().is_empty()).then_some(0));
+        self.show_notes_state.reset();
     }
 
-    pub fn selected_podcast(&self) -> Option<&Podcast> {
-        self.selected_podcast_index.map(|i| &self.podcasts[i])
+    fn on_command_input_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.command_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let command = self.command_input.take().unwrap_or_default();
+                self.command_history.push(command.text());
+                self.run_command(command.text());
+            }
+            KeyCode::Esc => self.command_input = None,
+            _ => {
+                input.handle_key(key, &self.command_history);
+            }
+        }
     }
 
-    pub fn selected_episode(&self) -> Option<&Episode> {
-        self.selected_podcast().and_then(|p| self.selected_episode_index.map(|i| &p.episodes()[i]))
+    // Opens the note editor for the selected episode, prefilled with its current note
+    pub fn edit_selected_episode_note(&mut self) {
+        let Some((podcast, episode)) = self.selected_entry() else {
+            return;
+        };
+        let key = EpisodeKey::new(podcast.url(), episode.id());
+        let note = self.episode_states.note(&key).unwrap_or_default();
+        let popup = Popup::Note {
+            episode: key,
+            title: episode.title().to_string(),
+            input: InputLine::new().with_text(note),
+        };
+        self.push_popup(popup);
     }
 
-    pub fn on_key(&mut self, key: KeyCode) {
+    fn on_note_key(&mut self, key: KeyEvent) {
+        let Some(Popup::Note { episode, input, .. }) = self.popups.last_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                // Saving an empty note removes it
+                let text = input.text().trim().to_string();
+                let note = Some(text).filter(|t| !t.is_empty());
+                let episode = episode.clone();
+                self.close_popup();
+                self.episode_states.update(&episode, |state| state.note = note);
+                if let Err(e) = self.episode_states.save() {
+                    self.status_message = Some(e.to_string());
+                }
+            }
+            KeyCode::Esc => {
+                self.close_popup();
+            }
+            _ => {
+                input.handle_key(key, &InputHistory::default());
+            }
+        }
+    }
+
+    fn on_welcome_key(&mut self, key: KeyCode) {
         match key {
+            KeyCode::Char('i') => self.open_file_picker(FilePickerPurpose::ImportOpml),
+            KeyCode::Char('a') => self.command_input = Some(InputLine::new().with_text("add ")),
             KeyCode::Char('q') => self.should_quit = true,
-            KeyCode::Down => self.select_next_podcast(),
-            KeyCode::Up => self.select_prev_podcast(),
-            // Add more key handlers as needed
+            KeyCode::Esc => self.welcome = false,
             _ => {}
         }
     }
 
+    // Commands typed after ':'
+    pub fn run_command(&mut self, command: &str) {
+        let command = command.trim();
+        let (name, argument) = command.split_once(' ').unwrap_or((command, ""));
+        match (name, argument.trim()) {
+            ("", _) => {}
+            ("q" | "quit", _) => self.should_quit = true,
+            ("import", "") => {
+                self.open_file_picker(FilePickerPurpose::ImportOpml);
+            }
+            ("import", path) => self.import_opml(Path::new(path), DuplicateFeeds::Ask),
+            ("add", text) => match pasted_url(text) {
+                Some(url) if self.podcasts.iter().any(|p| p.url() == &url) => {
+                    self.status_message = Some(format!("Already subscribed to {}", url));
+                }
+                Some(url) => self.subscribe(url),
+                None => self.status_message = Some("Usage: :add <feed URL>".to_string()),
+            },
+            ("lang", "") => self.set_language_filter(None),
+            ("lang", language) => self.set_language_filter(Some(language.to_string())),
+            ("category", "") => self.set_category_filter(None),
+            ("category", category) => self.set_category_filter(Some(category.to_string())),
+            ("trash", _) => self.list_trash(),
+            ("backup", _) => {
+                if self.backups.is_some() && self.backup_before("manual") {
+                    self.status_message = Some("Backed up the data directory".to_string());
+                }
+            }
+            ("restore", title) => self.restore_from_trash(title),
+            ("categories", _) => {
+                let categories = self.categories();
+                self.status_message = Some(if categories.is_empty() {
+                    "No categories in your feeds".to_string()
+                } else {
+                    format!("Categories: {}", categories.join(", "))
+                });
+            }
+            _ => self.status_message = Some(format!("Unknown command: {}", name)),
+        }
+    }
+
+    fn list_trash(&mut self) {
+        let Some(trash) = &self.trash else {
+            return;
+        };
+        self.status_message = Some(match trash.entries() {
+            Ok(entries) if entries.is_empty() => "The trash is empty".to_string(),
+            Ok(entries) => {
+                let titles: Vec<&str> = entries.iter().map(|e| e.title.as_str()).collect();
+                format!("In the trash: {}", titles.join(", "))
+            }
+            Err(e) => e.to_string(),
+        });
+    }
+
+    // Brings back the most recently unsubscribed podcast whose title contains `title`
+    // (any podcast when empty)
+    pub fn restore_from_trash(&mut self, title: &str) {
+        let Some(trash) = &self.trash else {
+            return;
+        };
+        let needle = title.to_lowercase();
+        let found = trash.entries().and_then(|entries| {
+            let entry = entries.into_iter().find(|e| e.title.to_lowercase().contains(&needle));
+            entry.map(|entry| trash.read(&entry).map(|podcast| (entry, podcast))).transpose()
+        });
+        // Subscribed again in the meantime: keep the trashed copy, it may be the one wanted
+        let restored = match found {
+            Ok(Some((_, podcast))) if self.podcasts.iter().any(|p| p.url() == podcast.url()) => {
+                self.status_message = Some(format!(
+                    "'{}' is already subscribed; left it in the trash",
+                    podcast.title()
+                ));
+                return;
+            }
+            Ok(Some((entry, podcast))) => trash.remove(&entry).map(|_| Some(podcast)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        };
+        match restored {
+            Ok(Some(podcast)) => {
+                self.status_message = Some(format!("Restored '{}'", podcast.title()));
+                self.podcasts.push(podcast);
+                self.apply_podcast_order();
+            }
+            Ok(None) => {
+                self.status_message = Some(format!("Nothing matching '{}' in the trash", title))
+            }
+            Err(e) => self.status_message = Some(e.to_string()),
+        }
+    }
+
+    // Starts in the working directory, where exported files usually end up
+    pub fn open_file_picker(&mut self, purpose: FilePickerPurpose) {
+        let start_dir = std::env::current_dir()
+            .ok()
+            .or_else(|| std::env::var_os("HOME").map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from("/"));
+        let picker = FilePickerState::new(start_dir).with_extensions(purpose.extensions());
+        self.push_popup(Popup::FilePicker(purpose, picker));
+    }
+
+    fn on_file_picked(&mut self, purpose: FilePickerPurpose, path: &Path) {
+        match purpose {
+            FilePickerPurpose::ImportOpml => self.import_opml(path, DuplicateFeeds::Ask),
+        }
+    }
+
     pub fn load_test_podcast(&mut self) {
         // Create a test podcast with some episodes
         let test_podcast = Podcast::new(
@@ -83,22 +2326,61 @@ impl App {
     }
 }
 
+// Title, description or the user's note; case-insensitive, `query` is already lowercase
+fn episode_matches(episode: &Episode, note: Option<&str>, query: &str) -> bool {
+    episode.title().to_lowercase().contains(query)
+        || episode.description().is_some_and(|d| d.to_lowercase().contains(query))
+        || note.is_some_and(|n| n.to_lowercase().contains(query))
+}
+
+// Pasted text that is a single http(s) URL, e.g. copied from a podcast's website
+fn pasted_url(text: &str) -> Option<PodcastURL> {
+    let text = text.trim();
+    if text.contains(char::is_whitespace) {
+        return None;
+    }
+    let url = reqwest::Url::parse(text).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| PodcastURL::new(text))
+}
+
+// Moves `current` by `delta` within 0..len, clamping at both ends; selects the first
+// item when nothing is selected yet
+fn step_index(current: Option<usize>, delta: isize, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    let Some(current) = current else {
+        return Some(if delta < 0 { len - 1 } else { 0 });
+    };
+    Some(current.saturating_add_signed(delta).min(len - 1))
+}
+
 pub fn start_ui(initial_app: Option<App>) -> Result<()> {
     // Set up the terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)?;
     let backend = ratatui::backend::CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Use provided app or create a new empty one
-    let mut app = initial_app.unwrap_or_else(App::new);
+    let mut app = initial_app.unwrap_or_default();
+    app.apply_podcast_order();
+    app.reconcile_partial_downloads();
+    if app.config.refresh_on_startup {
+        app.refresh_all_podcasts();
+    }
 
     let res = run_app(&mut terminal, &mut app);
 
     // Restore the terminal
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste
+    )?;
     terminal.show_cursor()?;
 
     if let Err(e) = res {
@@ -109,13 +2391,1080 @@ pub fn start_ui(initial_app: Option<App>) -> Result<()> {
 }
 
 pub fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+    let poll_interval = app.config.poll_interval();
+    let tick_rate = app.config.tick_rate();
+    let mut last_tick = Instant::now();
+    let mut last_spin = Instant::now();
+
     while !app.should_quit {
-        terminal.draw(|f| crate::ui::ui::<B>(f, app))?;
+        app.drain_events();
+        if app.needs_redraw {
+            terminal.draw(|f| crate::ui::ui::<B>(f, app))?;
+            app.needs_redraw = false;
+        }
+        if let Some(text) = app.take_clipboard() {
+            execute!(io::stdout(), crossterm::style::Print(osc52_copy(&text)))?;
+        }
 
-        if let Event::Key(key) = event::read()? {
-            app.on_key(key.code);
+        // Never block past the next tick, so periodic work isn't delayed by a quiet keyboard
+        let timeout = poll_interval.min(tick_rate.saturating_sub(last_tick.elapsed()));
+        if event::poll(timeout)? {
+            match event::read()? {
+                Event::Key(key) => app.on_key_event(key),
+                Event::Paste(text) => app.on_paste(&text),
+                Event::Resize(_, _) => app.request_redraw(),
+                _ => {}
+            }
+        }
+
+        if last_spin.elapsed() >= SPINNER_INTERVAL {
+            app.advance_spinner();
+            last_spin = Instant::now();
+        }
+        if last_tick.elapsed() >= tick_rate {
+            app.on_tick();
+            last_tick = Instant::now();
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An episode of the podcast in `app_with_episodes`
+    fn episode_key(id: &str) -> EpisodeKey {
+        EpisodeKey::new(&PodcastURL::new("http://example.com/feed"), &EpisodeID::new(id))
+    }
+
+    fn app_with_episodes(count: usize) -> App {
+        let episodes = (0..count)
+            .map(|i| {
+                Episode::new(
+                    EpisodeID::new(&format!("ep{}", i)),
+                    format!("Episode {}", i),
+                    None,
+                    Utc::now(),
+                    None,
+                    format!("http://example.com/ep{}.mp3", i),
+                    None,
+                )
+            })
+            .collect();
+        let mut app = App::new();
+        app.podcasts.push(Podcast::new(
+            PodcastURL::new("http://example.com/feed"),
+            "Test Podcast".to_string(),
+            None,
+            None,
+            None,
+            episodes,
+        ));
+        app.set_selected_podcast_index(Some(0));
+        app
+    }
+
+    #[test]
+    fn test_paging_moves_episode_selection_by_page_height() {
+        let mut app = app_with_episodes(25);
+        app.focused_panel = FocusedPanel::Episodes;
+        app.set_page_height(10);
+
+        app.on_key(KeyCode::PageDown);
+        assert_eq!(app.selected_episode_index(), Some(0)); // First press selects the top
+        app.on_key(KeyCode::PageDown);
+        assert_eq!(app.selected_episode_index(), Some(10));
+        app.on_key(KeyCode::End);
+        assert_eq!(app.selected_episode_index(), Some(24));
+        app.on_key(KeyCode::PageDown);
+        assert_eq!(app.selected_episode_index(), Some(24)); // No wrapping when paging
+        app.on_key(KeyCode::PageUp);
+        assert_eq!(app.selected_episode_index(), Some(14));
+        app.on_key(KeyCode::Home);
+        assert_eq!(app.selected_episode_index(), Some(0));
+    }
+
+    #[test]
+    fn test_step_index() {
+        assert_eq!(step_index(None, 5, 0), None);
+        assert_eq!(step_index(None, -5, 3), Some(2));
+        assert_eq!(step_index(Some(1), isize::MIN, 3), Some(0));
+        assert_eq!(step_index(Some(1), isize::MAX, 3), Some(2));
+    }
+
+    #[test]
+    fn test_date_filter_hides_old_episodes_and_keeps_selection() {
+        let mut app = app_with_episodes(0);
+        for (id, days_old) in [("new", 2), ("recent", 20), ("old", 200), ("ancient", 2000)] {
+            app.podcasts[0].add_episode(Episode::new(
+                EpisodeID::new(id),
+                id.to_string(),
+                None,
+                Utc::now() - chrono::Duration::days(days_old),
+                None,
+                format!("http://example.com/{}.mp3", id),
+                None,
+            ));
+        }
+        app.set_selected_episode_index(Some(1)); // "recent"
+
+        let visible = |app: &App| app.visible_episodes().len();
+        app.cycle_date_filter();
+        assert_eq!((app.date_filter, visible(&app)), (EpisodeDateFilter::LastWeek, 1));
+        assert_eq!(app.selected_episode_index(), None); // "recent" is filtered out
+
+        app.set_selected_episode_index(Some(0)); // "new"
+        app.cycle_date_filter();
+        assert_eq!(visible(&app), 2);
+        assert_eq!(app.selected_episode().map(|e| e.title()), Some("new"));
+        app.cycle_date_filter();
+        assert_eq!(visible(&app), 3);
+        app.cycle_date_filter();
+        assert_eq!(visible(&app), 4);
+    }
+
+    #[test]
+    fn test_pasted_url() {
+        assert_eq!(
+            pasted_url(" https://example.com/feed.xml\n"),
+            Some(PodcastURL::new("https://example.com/feed.xml"))
+        );
+        assert_eq!(pasted_url("ftp://example.com/feed.xml"), None);
+        assert_eq!(pasted_url("some text https://example.com"), None);
+        assert_eq!(pasted_url("not a url"), None);
+    }
+
+    #[tokio::test]
+    async fn test_paste_and_confirm_subscribes_in_background() {
+        let feed = r#"<?xml version="1.0"?>
+            <rss version="2.0"><channel>
+                <title>Pasted Podcast</title><link>http://example.com</link><description>d</description>
+            </channel></rss>"#;
+        let fetcher = Arc::new(crate::podcast_download::FakeFetcher::new(feed));
+        let mut app = App::new().with_fetcher(fetcher);
+
+        app.on_paste("http://example.com/feed.xml");
+        assert!(matches!(
+            app.top_popup(),
+            Some(Popup::Confirm(ConfirmAction::Subscribe(url), _))
+                if url == &PodcastURL::new("http://example.com/feed.xml")
+        ));
+        app.on_key(KeyCode::Char('y'));
+        assert!(app.top_popup().is_none());
+
+        for _ in 0..100 {
+            app.drain_events();
+            if !app.podcasts.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(app.podcasts.len(), 1);
+        assert_eq!(app.podcasts[0].title(), "Pasted Podcast");
+
+        // Pasting it again doesn't prompt a second time
+        app.on_paste("http://example.com/feed.xml/");
+        assert!(app.top_popup().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_import_opml_subscribes_to_new_feeds() {
+        let feed = |title: &str| {
+            format!(
+                r#"<rss version="2.0"><channel><title>{}</title><link>http://example.com</link>
+                    <description>d</description></channel></rss>"#,
+                title
+            )
+        };
+        let fetcher = crate::podcast_download::FakeFetcher::new("")
+            .with_response("http://example.com/a.xml", feed("A"))
+            .with_response("http://example.com/b.xml", feed("B"));
+        let mut app = App::new().with_fetcher(Arc::new(fetcher));
+        app.podcasts.push(Podcast::new(
+            PodcastURL::new("http://example.com/a.xml"),
+            "A".to_string(),
+            None,
+            None,
+            None,
+            vec![],
+        ));
+
+        let path = std::env::temp_dir().join("rustero-import-test.opml");
+        std::fs::write(
+            &path,
+            r#"<opml version="2.0"><body>
+                <outline text="A" xmlUrl="http://example.com/a.xml"/>
+                <outline text="B" xmlUrl="http://example.com/b.xml"/>
+                <outline text="C" xmlUrl="http://example.com/c.xml"/>
+            </body></opml>"#,
+        )
+        .unwrap();
+        app.run_command(&format!("import {}", path.display()));
+        // The feeds are listed first; C gets unticked
+        assert!(
+            matches!(app.top_popup(), Some(Popup::ImportSelection { feeds, .. }) if feeds.len() == 3)
+        );
+        app.on_key(KeyCode::End);
+        app.on_key(KeyCode::Char(' '));
+        app.on_key(KeyCode::Enter);
+        // A is already subscribed, so its refresh is offered instead
+        assert!(matches!(
+            app.top_popup(),
+            Some(Popup::Confirm(ConfirmAction::RefreshFeeds(urls), _))
+                if urls == &vec![PodcastURL::new("http://example.com/a.xml")]
+        ));
+        app.on_key(KeyCode::Enter);
+        assert!(app.top_popup().is_none());
+
+        for _ in 0..100 {
+            app.drain_events();
+            if app.podcasts.len() == 2 && app.last_refresh(app.podcasts[0].url()).is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let titles: Vec<&str> = app.podcasts.iter().map(|p| p.title()).collect();
+        assert_eq!(titles, vec!["A", "B"]);
+        assert!(app.last_refresh(app.podcasts[0].url()).is_some_and(|o| o.error.is_none()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_all_updates_every_podcast() {
+        let feed = |title: &str| {
+            format!(
+                r#"<rss version="2.0"><channel><title>{} (new)</title><link>http://example.com</link>
+                    <description>d</description></channel></rss>"#,
+                title
+            )
+        };
+        let fetcher = crate::podcast_download::FakeFetcher::new("")
+            .with_response("http://example.com/a.xml", feed("A"))
+            .with_response("http://example.com/b.xml", feed("B"));
+        let mut app = App::new().with_fetcher(Arc::new(fetcher));
+        for name in ["a", "b"] {
+            app.podcasts.push(Podcast::new(
+                PodcastURL::new(&format!("http://example.com/{}.xml", name)),
+                name.to_uppercase(),
+                None,
+                None,
+                None,
+                vec![],
+            ));
+        }
+
+        app.refresh_all_podcasts();
+        for _ in 0..100 {
+            app.drain_events();
+            if app.podcasts.iter().all(|p| p.title().ends_with("(new)")) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let titles: Vec<&str> = app.podcasts.iter().map(|p| p.title()).collect();
+        assert_eq!(titles, vec!["A (new)", "B (new)"]);
+        // Both feeds are in, so the timing summary is up
+        let summary = app.status_message.clone().unwrap_or_default();
+        assert!(summary.starts_with("Refresh of 2 feed(s) took "), "{}", summary);
+        assert!(summary.contains("slowest: ") && summary.contains("A (new) "), "{}", summary);
+        // ...and so is the report of what it found
+        assert!(matches!(
+            app.top_popup(),
+            Some(Popup::Report { lines, .. }) if lines[1..] == ["No new episodes"]
+        ));
+    }
+
+    #[test]
+    fn test_refresh_keeps_the_browsing_position() {
+        let podcast = |ids: &[&str]| {
+            let episodes = ids
+                .iter()
+                .map(|id| {
+                    Episode::new(
+                        EpisodeID::new(id),
+                        id.to_string(),
+                        Some("notes".to_string()),
+                        Utc::now(),
+                        None,
+                        format!("http://example.com/{}.mp3", id),
+                        None,
+                    )
+                })
+                .collect();
+            let url = PodcastURL::new("http://example.com/feed");
+            Podcast::new(url, "P".to_string(), None, None, None, episodes)
+        };
+        let mut app = App::new();
+        app.podcasts.push(podcast(&["e3", "e2", "e1"]));
+        app.set_selected_podcast_index(Some(0));
+        app.set_selected_episode_index(Some(1));
+        app.set_episode_list_offset(1);
+        app.show_notes_state.scroll_down(3, 100);
+
+        // Two new episodes on top: still on e2, on the same screen row, notes where they were
+        app.handle_event(AppEvent::PodcastRefreshed(podcast(&["e5", "e4", "e3", "e2", "e1"])));
+        assert_eq!(app.selected_episode().map(|e| e.title()), Some("e2"));
+        assert_eq!(app.episode_list_offset(), 3);
+        assert_eq!(app.show_notes_state.offset(), 3);
+
+        // e2 was pulled from the feed: the row stays, the notes start over
+        app.handle_event(AppEvent::PodcastRefreshed(podcast(&["e5", "e4", "e3", "e1"])));
+        assert_eq!(app.selected_episode().map(|e| e.title()), Some("e1"));
+        assert_eq!(app.show_notes_state.offset(), 0);
+    }
+
+    #[test]
+    fn test_new_episodes_are_marked_until_selected() {
+        let mut app = app_with_episodes(1);
+        let mut podcast = app.podcasts[0].clone();
+        app.handle_event(AppEvent::PodcastRefreshed(podcast.clone()));
+        podcast.add_episode(Episode::new(
+            EpisodeID::new("ep1"),
+            "Episode 1".to_string(),
+            None,
+            Utc::now(),
+            None,
+            "http://example.com/ep1.mp3".to_string(),
+            None,
+        ));
+        app.handle_event(AppEvent::PodcastRefreshed(podcast));
+        let new = episode_key("ep1");
+        assert!(app.episode_states().is_new(&new));
+        assert!(!app.episode_states().is_new(&episode_key("ep0")));
+
+        app.set_selected_podcast_index(Some(0));
+        let row = app.visible_episodes().iter().position(|e| e.id() == &new.episode);
+        app.set_selected_episode_index(row);
+        assert!(!app.episode_states().is_new(&new));
+    }
+
+    #[test]
+    fn test_refresh_updates_changed_episodes_and_keeps_their_state() {
+        let podcast = |title: &str, notes: &str| {
+            let episode = Episode::new(
+                EpisodeID::new("e1"),
+                title.to_string(),
+                Some(notes.to_string()),
+                Utc::now(),
+                None,
+                "http://example.com/e1.mp3".to_string(),
+                None,
+            );
+            let url = PodcastURL::new("http://example.com/feed");
+            Podcast::new(url, "P".to_string(), None, None, None, vec![episode])
+        };
+        let mut app = App::new();
+        app.podcasts.push(podcast("Epsiode 1", "Old notes"));
+        app.set_selected_podcast_index(Some(0));
+        app.set_selected_episode_index(Some(0));
+        app.toggle_star_selected_episode();
+        let episode = app.selected_episode().unwrap().clone();
+        assert_eq!(app.show_notes_lines(&episode, 40).as_slice(), ["Old notes"]);
+
+        app.handle_event(AppEvent::PodcastRefreshed(podcast("Episode 1", "New notes")));
+        let episode = app.selected_episode().unwrap().clone();
+        assert_eq!(episode.title(), "Episode 1");
+        assert_eq!(app.show_notes_lines(&episode, 40).as_slice(), ["New notes"]);
+        assert!(app.episode_states().get(&episode_key("e1")).starred);
+    }
+
+    #[test]
+    fn test_episode_filter_is_scoped_to_the_podcast() {
+        let episode = |id: &str, title: &str, description: &str| {
+            Episode::new(
+                EpisodeID::new(id),
+                title.to_string(),
+                Some(description.to_string()),
+                Utc::now(),
+                None,
+                format!("http://example.com/{}.mp3", id),
+                None,
+            )
+        };
+        let mut app = App::new();
+        for (name, episodes) in [
+            (
+                "A",
+                vec![
+                    episode("a1", "Async Rust", "Futures explained"),
+                    episode("a2", "Lifetimes", "All about borrowing"),
+                    episode("a3", "Traits", "Generics and ASYNC traits"),
+                ],
+            ),
+            ("B", vec![episode("b1", "Async in B", "")]),
+        ] {
+            app.podcasts.push(Podcast::new(
+                PodcastURL::new(&format!("http://example.com/{}", name)),
+                name.to_string(),
+                None,
+                None,
+                None,
+                episodes,
+            ));
+        }
+        app.select_next_podcast();
+        app.focused_panel = FocusedPanel::Episodes;
+
+        app.on_key(KeyCode::Char('/'));
+        for c in "async".chars() {
+            app.on_key(KeyCode::Char(c));
+        }
+        fn titles(app: &App) -> Vec<&str> {
+            app.visible_episodes().iter().map(|e| e.title()).collect()
+        }
+        assert_eq!(titles(&app), vec!["Async Rust", "Traits"]); // Filters while typing
+        app.on_key(KeyCode::Enter);
+        assert_eq!(app.episode_query.as_deref(), Some("async"));
+        assert_eq!(titles(&app), vec!["Async Rust", "Traits"]);
+        assert_eq!(app.selected_episode_index(), Some(0));
+
+        // Switching podcasts drops the filter
+        app.select_next_podcast();
+        assert_eq!(app.episode_query, None);
+        assert_eq!(titles(&app), vec!["Async in B"]);
+
+        app.on_key(KeyCode::Char('/'));
+        app.on_key(KeyCode::Char('x'));
+        assert!(app.visible_episodes().is_empty());
+        app.on_key(KeyCode::Esc);
+        assert_eq!(titles(&app), vec!["Async in B"]);
+    }
+
+    #[test]
+    fn test_episode_filter_finds_notes() {
+        let mut app = app_with_episodes(3);
+        app.set_selected_podcast_index(Some(0));
+        let note = "Great SPONSOR read at the end".to_string();
+        app.episode_states.update(&episode_key("ep1"), |s| s.note = Some(note));
+
+        app.episode_query = Some("sponsor".to_string());
+        let titles: Vec<&str> = app.visible_episodes().iter().map(|e| e.title()).collect();
+        assert_eq!(titles, vec!["Episode 1"]);
+    }
+
+    // Never answers, so tasks stay running until cancelled
+    struct HangingFetcher;
+
+    #[async_trait::async_trait]
+    impl FeedFetcher for HangingFetcher {
+        async fn fetch(&self, _url: &str) -> Result<String, crate::errors::DownloaderError> {
+            std::future::pending().await
+        }
+        async fn fetch_headers(
+            &self,
+            _url: &str,
+        ) -> Result<HashMap<String, String>, crate::errors::DownloaderError> {
+            std::future::pending().await
+        }
+        async fn fetch_partial_content(
+            &self,
+            _url: &str,
+            _byte_range: (u64, u64),
+        ) -> Result<String, crate::errors::DownloaderError> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_task_from_task_panel() {
+        let mut app = App::new().with_fetcher(Arc::new(HangingFetcher));
+        app.subscribe(PodcastURL::new("http://example.com/slow.xml"));
+        app.drain_events();
+        assert!(app.tasks().is_busy());
+
+        app.on_key(KeyCode::Char('t'));
+        assert!(matches!(app.top_popup(), Some(Popup::Tasks { selected: 0 })));
+        app.on_key(KeyCode::Char('c'));
+        assert!(!app.tasks().is_busy());
+        assert_eq!(app.tasks().list()[0].status, crate::tasks::TaskStatus::Cancelled);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Cancelled subscribing http://example.com/slow.xml")
+        );
+
+        app.on_key(KeyCode::Esc);
+        assert!(app.top_popup().is_none());
+    }
+
+    // Writes a partial file, reports half the bytes and then stalls
+    struct StallingDownloader;
+
+    #[async_trait::async_trait]
+    impl EpisodeDownloader for StallingDownloader {
+        async fn download(
+            &self,
+            _url: &str,
+            dest: &Path,
+            progress: &crate::episode_download::ProgressFn,
+        ) -> Result<crate::episode_download::DownloadedFile, crate::errors::EpisodeDownloadError>
+        {
+            let part = crate::episode_download::partial_path(dest);
+            std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+            std::fs::write(&part, b"half").unwrap();
+            progress(4, Some(8));
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_download_removes_partial_file() {
+        let dir = std::env::temp_dir().join(format!("rustero-cancel-{}", std::process::id()));
+        let config = Config { download_dir: Some(dir.clone()), ..Config::default() };
+        let mut app = App::with_config(config).with_downloader(Arc::new(StallingDownloader));
+        app.podcasts = app_with_episodes(2).podcasts;
+        app.set_selected_podcast_index(Some(0));
+        app.set_selected_episode_index(Some(0));
+        app.focused_panel = FocusedPanel::Episodes;
+
+        app.on_key(KeyCode::Char('D'));
+        let part = dir.join("Test Podcast").join("Episode 0.mp3.part");
+        for _ in 0..100 {
+            app.drain_events();
+            if app.tasks().list().first().is_some_and(|t| t.progress.is_some()) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(app.tasks().list()[0].ratio(), Some(0.5));
+        assert!(part.exists());
+
+        app.on_key(KeyCode::Char('c'));
+        assert!(!part.exists());
+        assert_eq!(app.download_state(&episode_key("ep0")), None);
+        assert_eq!(app.tasks().list()[0].status, crate::tasks::TaskStatus::Cancelled);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // Finishes immediately, reporting the given sizes
+    struct ShortDownloader(crate::episode_download::DownloadedFile);
+
+    #[async_trait::async_trait]
+    impl EpisodeDownloader for ShortDownloader {
+        async fn download(
+            &self,
+            _url: &str,
+            _dest: &Path,
+            _progress: &crate::episode_download::ProgressFn,
+        ) -> Result<crate::episode_download::DownloadedFile, crate::errors::EpisodeDownloadError>
+        {
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_short_download_is_marked_incomplete_and_can_be_retried() {
+        let file = crate::episode_download::DownloadedFile { bytes: 10, content_length: Some(20) };
+        let mut app = App::new().with_downloader(Arc::new(ShortDownloader(file)));
+        app.podcasts = app_with_episodes(1).podcasts;
+        app.set_selected_podcast_index(Some(0));
+        app.set_selected_episode_index(Some(0));
+        app.focused_panel = FocusedPanel::Episodes;
+        let ep0 = episode_key("ep0");
+
+        app.on_key(KeyCode::Char('D'));
+        for _ in 0..100 {
+            app.drain_events();
+            if matches!(app.download_state(&ep0), Some(DownloadState::Corrupt { .. })) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(matches!(
+            app.download_state(&ep0),
+            Some(DownloadState::Corrupt { reason, .. }) if reason == "incomplete: 10 B of 20 B"
+        ));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("'Episode 0' is incomplete: 10 B of 20 B; press D to download it again")
+        );
+
+        app.on_key(KeyCode::Char('D'));
+        assert!(matches!(app.download_state(&ep0), Some(DownloadState::Downloading { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_downloads_wait_in_reorderable_queue() {
+        let dir = std::env::temp_dir().join(format!("rustero-queue-{}", std::process::id()));
+        let config = Config {
+            download_dir: Some(dir.clone()),
+            max_parallel_downloads: 1,
+            ..Config::default()
+        };
+        let mut app = App::with_config(config).with_downloader(Arc::new(StallingDownloader));
+        app.podcasts = app_with_episodes(3).podcasts;
+        app.set_selected_podcast_index(Some(0));
+        app.focused_panel = FocusedPanel::Episodes;
+        for i in 0..3 {
+            app.set_selected_episode_index(Some(i));
+            app.on_key(KeyCode::Char('D'));
+        }
+        app.drain_events();
+        let queued =
+            |app: &App| app.download_queue().items().map(|q| q.title.clone()).collect::<Vec<_>>();
+        assert_eq!(queued(&app), vec!["Episode 1", "Episode 2"]);
+        assert_eq!(app.download_state(&episode_key("ep1")), Some(&DownloadState::Queued));
+
+        // Rows: running Episode 0, then the queue. Move Episode 2 to the front.
+        app.on_key(KeyCode::Char('t'));
+        app.on_key(KeyCode::Down);
+        app.on_key(KeyCode::Down);
+        app.on_key(KeyCode::Char('+'));
+        assert_eq!(queued(&app), vec!["Episode 2", "Episode 1"]);
+        assert!(matches!(app.top_popup(), Some(Popup::Tasks { selected: 1 })));
+
+        // Cancelling the running download frees the slot for the next in line
+        app.on_key(KeyCode::Up);
+        app.on_key(KeyCode::Char('c'));
+        assert_eq!(queued(&app), vec!["Episode 1"]);
+        assert!(matches!(
+            app.download_state(&episode_key("ep2")),
+            Some(DownloadState::Downloading { .. })
+        ));
+
+        // Cancelling a queued download just drops it from the queue
+        app.set_selected_episode_index(Some(1));
+        app.on_key(KeyCode::Esc);
+        app.on_key(KeyCode::Char('c'));
+        assert!(app.download_queue().is_empty());
+        assert_eq!(app.download_state(&episode_key("ep1")), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_starred_episodes_show_up_in_favorites() {
+        let mut app = app_with_episodes(3);
+        app.podcasts.push(Podcast::new(
+            PodcastURL::new("http://example.com/other"),
+            "Other".to_string(),
+            None,
+            None,
+            None,
+            vec![Episode::new(
+                EpisodeID::new("other1"),
+                "Other 1".to_string(),
+                None,
+                Utc::now() - chrono::Duration::days(1),
+                None,
+                "http://example.com/other1.mp3".to_string(),
+                None,
+            )],
+        ));
+        app.focused_panel = FocusedPanel::Episodes;
+        assert!(!app.favorites_visible());
+
+        app.set_selected_episode_index(Some(1));
+        app.on_key(KeyCode::Char('s'));
+        app.set_selected_podcast_index(Some(1));
+        app.set_selected_episode_index(Some(0));
+        app.on_key(KeyCode::Char('s'));
+        assert!(app.episode_states().is_starred(&episode_key("ep1")));
+
+        // Favorites is an extra row after the podcasts, newest episode first
+        app.select_next_podcast();
+        assert!(app.favorites_selected());
+        assert!(app.selected_podcast().is_none());
+        let titles: Vec<&str> = app.visible_episodes().iter().map(|e| e.title()).collect();
+        assert_eq!(titles, vec!["Episode 1", "Other 1"]);
+
+        // Unstarring the last favorites removes the row again
+        app.set_selected_episode_index(Some(1));
+        app.on_key(KeyCode::Char('s'));
+        assert_eq!(app.selected_episode_index(), Some(0));
+        app.on_key(KeyCode::Char('s'));
+        assert!(!app.favorites_visible());
+        assert_eq!(app.selected_podcast_index(), Some(1));
+    }
+
+    #[test]
+    fn test_hidden_episodes_leave_the_list_until_shown() {
+        let mut app = app_with_episodes(3);
+        app.focused_panel = FocusedPanel::Episodes;
+        app.set_selected_episode_index(Some(2));
+        app.on_key(KeyCode::Char('h'));
+        let titles = |app: &App| {
+            app.visible_episodes().iter().map(|e| e.title().to_string()).collect::<Vec<_>>()
+        };
+        assert_eq!(titles(&app), vec!["Episode 0", "Episode 1"]);
+        assert_eq!(app.selected_episode_index(), Some(1));
+
+        app.on_key(KeyCode::Char('H'));
+        assert_eq!(titles(&app).len(), 3);
+        assert_eq!(app.selected_episode().map(|e| e.title()), Some("Episode 1"));
+        // Unhiding works from the full list
+        app.set_selected_episode_index(Some(2));
+        app.on_key(KeyCode::Char('h'));
+        app.on_key(KeyCode::Char('H'));
+        assert_eq!(titles(&app).len(), 3);
+    }
+
+    #[test]
+    fn test_explicit_filter_hides_explicit_episodes() {
+        let mut app = app_with_episodes(2);
+        let explicit = app.podcasts[0].episodes()[0].clone().with_explicit(true);
+        app.podcasts[0] = Podcast::new(
+            PodcastURL::new("http://example.com/feed"),
+            "Test".to_string(),
+            None,
+            None,
+            None,
+            vec![explicit, app.podcasts[0].episodes()[1].clone()],
+        );
+        app.set_selected_episode_index(Some(1));
+
+        app.on_key(KeyCode::Char('E'));
+        let titles: Vec<&str> = app.visible_episodes().iter().map(|e| e.title()).collect();
+        assert_eq!(titles, vec!["Episode 1"]);
+        assert_eq!(app.selected_episode_index(), Some(0));
+        app.on_key(KeyCode::Char('E'));
+        assert_eq!(app.visible_episodes().len(), 2);
+    }
+
+    #[test]
+    fn test_language_filter_limits_podcast_rows() {
+        let mut app = App::new();
+        for (title, language) in [("A", "en-US"), ("B", "de"), ("C", "en")] {
+            let url = PodcastURL::new(&format!("http://example.com/{}", title));
+            let podcast = Podcast::new(url, title.to_string(), None, None, None, vec![])
+                .with_language(Some(language.to_string()));
+            app.podcasts.push(podcast);
+        }
+        app.set_selected_podcast_index(Some(1));
+
+        app.run_command("lang en");
+        assert_eq!(app.podcast_rows(), vec![0, 2]);
+        assert_eq!(app.selected_podcast_index(), Some(0));
+        app.select_next_podcast();
+        assert_eq!(app.selected_podcast_index(), Some(2));
+        app.select_next_podcast();
+        assert_eq!(app.selected_podcast_index(), Some(0));
+
+        app.run_command("lang");
+        assert_eq!(app.podcast_rows(), vec![0, 1, 2]);
+        assert_eq!(app.selected_podcast_index(), Some(0));
+    }
+
+    #[test]
+    fn test_podcasts_are_reordered_with_shift_arrows() {
+        let shift = |code| KeyEvent::new(code, KeyModifiers::SHIFT);
+        let titles =
+            |app: &App| app.podcasts.iter().map(|p| p.title().to_string()).collect::<Vec<_>>();
+        let mut app = App::new();
+        for title in ["A", "B", "C"] {
+            let url = PodcastURL::new(&format!("http://example.com/{}", title));
+            app.podcasts.push(Podcast::new(url, title.to_string(), None, None, None, vec![]));
+        }
+        app.set_selected_podcast_index(Some(0));
+
+        app.on_key_event(shift(KeyCode::Down));
+        app.on_key_event(shift(KeyCode::Down));
+        app.on_key_event(shift(KeyCode::Down)); // Already last
+        assert_eq!(titles(&app), vec!["B", "C", "A"]);
+        assert_eq!(app.selected_podcast_index(), Some(2));
+        app.on_key_event(shift(KeyCode::Up));
+        assert_eq!(titles(&app), vec!["B", "A", "C"]);
+
+        // A fresh list (e.g. the next start) is put back into the saved order
+        let order = app.episode_states().podcast_order().to_vec();
+        app.podcasts.sort_by(|a, b| a.title().cmp(b.title()));
+        app.episode_states.set_podcast_order(order);
+        app.apply_podcast_order();
+        assert_eq!(titles(&app), vec!["B", "A", "C"]);
+    }
+
+    #[test]
+    fn test_pinned_podcasts_are_listed_first() {
+        let mut app = App::new();
+        for title in ["A", "B", "C"] {
+            let url = PodcastURL::new(&format!("http://example.com/{}", title));
+            app.podcasts.push(Podcast::new(url, title.to_string(), None, None, None, vec![]));
+        }
+        app.set_selected_podcast_index(Some(2));
+        app.on_key(KeyCode::Char('p'));
+        assert_eq!(app.podcast_rows(), vec![2, 0, 1]);
+        assert_eq!(app.selected_podcast_row(), Some(0));
+
+        // Moving a pinned podcast below the unpinned ones does nothing
+        app.move_selected_podcast(1);
+        assert_eq!(app.podcast_rows(), vec![2, 0, 1]);
+        app.select_next_podcast();
+        assert_eq!(app.selected_podcast_index(), Some(0));
+
+        app.select_prev_podcast();
+        app.on_key(KeyCode::Char('p'));
+        assert_eq!(app.podcast_rows(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_selection_follows_its_podcast_and_episode() {
+        let mut app = app_with_episodes(3);
+        app.set_selected_podcast_index(Some(0));
+        app.set_selected_episode_index(Some(1));
+
+        // A podcast sorting in above the selected one
+        let other = PodcastURL::new("http://example.com/other");
+        app.podcasts
+            .insert(0, Podcast::new(other.clone(), "Other".into(), None, None, None, vec![]));
+        assert_eq!(app.selected_podcast_index(), Some(1));
+        assert_eq!(app.selected_podcast().map(|p| p.title()), Some("Test Podcast"));
+        assert_eq!(app.selected_episode().map(|e| e.id().to_string()), Some("ep1".to_string()));
+
+        app.unsubscribe(&other);
+        assert_eq!(app.selected_podcast_index(), Some(0));
+        assert_eq!(app.selected_episode().map(|e| e.id().to_string()), Some("ep1".to_string()));
+    }
+
+    #[test]
+    fn test_download_new_asks_first_and_skips_hidden_and_queued_episodes() {
+        let mut app = app_with_episodes(5);
+        let url = PodcastURL::new("http://example.com/feed");
+        let ids: Vec<EpisodeID> = (0..5).map(|i| EpisodeID::new(&format!("ep{}", i))).collect();
+        // ep4 was there last time, the others are new
+        app.episode_states.track_new_episodes(&url, &ids[4..]);
+        app.episode_states.track_new_episodes(&url, &ids);
+
+        app.set_selected_podcast_index(Some(0));
+        app.set_selected_episode_index(Some(0));
+        app.toggle_hide_selected_episode();
+        app.set_selected_episode_index(Some(0));
+        app.download_selected_episode();
+
+        app.focused_panel = FocusedPanel::Podcasts;
+        app.on_key(KeyCode::Char('D'));
+        let Some(Popup::Confirm(ConfirmAction::DownloadEpisodes(_, episodes), dialog)) =
+            app.top_popup()
+        else {
+            panic!("expected a confirmation");
+        };
+        assert_eq!(episodes.len(), 2);
+        assert_eq!(dialog.message(), "Download 2 new episode(s) of 'Test Podcast' (size unknown)?");
+        assert_eq!(app.download_queue().len(), 1);
+
+        app.on_key(KeyCode::Char('y'));
+        assert_eq!(app.status_message.as_deref(), Some("Queued 2 episode(s) of 'Test Podcast'"));
+        assert_eq!(app.download_queue().len(), 3);
+        assert!(app.download_state(&episode_key("ep0")).is_none());
+        assert!(app.download_state(&episode_key("ep4")).is_none());
+
+        app.on_key(KeyCode::Char('D'));
+        assert!(app.top_popup().is_none());
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("No new episodes to download from 'Test Podcast'")
+        );
+    }
+
+    #[test]
+    fn test_same_guid_in_two_feeds_has_its_own_state_and_download() {
+        let mut app = app_with_episodes(1);
+        let other = PodcastURL::new("http://example.com/other");
+        let episodes = app.podcasts[0].episodes().to_vec();
+        app.podcasts.push(Podcast::new(other.clone(), "Other".into(), None, None, None, episodes));
+        let other_ep0 = EpisodeKey::new(&other, &EpisodeID::new("ep0"));
+
+        app.set_selected_podcast_index(Some(1));
+        app.set_selected_episode_index(Some(0));
+        app.toggle_star_selected_episode();
+        app.download_selected_episode();
+        assert!(app.episode_states().is_starred(&other_ep0));
+        assert!(!app.episode_states().is_starred(&episode_key("ep0")));
+        assert_eq!(app.download_state(&other_ep0), Some(&DownloadState::Queued));
+        assert_eq!(app.download_state(&episode_key("ep0")), None);
+
+        // Favorites knows which podcast its episode came from
+        app.set_selected_podcast_index(Some(2));
+        app.set_selected_episode_index(Some(0));
+        assert_eq!(app.selected_entry().map(|(p, _)| p.title()), Some("Other"));
+    }
+
+    #[test]
+    fn test_disk_usage_sums_the_podcasts_downloaded_files() {
+        let dir = std::env::temp_dir().join(format!("rustero-usage-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut app = app_with_episodes(3);
+        for (id, bytes) in [("ep0", 1_500), ("ep1", 2_500)] {
+            let path = dir.join(id);
+            std::fs::write(&path, vec![0u8; bytes]).unwrap();
+            app.downloads.insert(episode_key(id), DownloadState::Downloaded(path));
+        }
+        app.downloads.insert(episode_key("ep2"), DownloadState::Downloaded(dir.join("gone")));
+        let other =
+            EpisodeKey::new(&PodcastURL::new("http://example.com/other"), &EpisodeID::new("ep0"));
+        app.downloads.insert(other, DownloadState::Downloaded(dir.join("ep0")));
+
+        assert_eq!(app.disk_usage(&PodcastURL::new("http://example.com/feed")), (2, 4_000));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_episodes_with_the_same_title_get_their_own_files() {
+        let bonus = |id: &str, days_ago| {
+            Episode::new(
+                EpisodeID::new(id),
+                "Bonus".to_string(),
+                None,
+                Utc::now() - chrono::Duration::days(days_ago),
+                None,
+                format!("http://example.com/{}.mp3", id),
+                None,
+            )
+        };
+        let podcast = Podcast::new(
+            PodcastURL::new("http://example.com/feed"),
+            "Show".to_string(),
+            None,
+            None,
+            None,
+            vec![bonus("new", 1), bonus("old", 30)],
+        );
+        let mut app = App::new();
+        let item = |app: &App, i: usize| {
+            app.download_item(&podcast, &podcast.episodes()[i], DownloadPriority::User)
+        };
+        let (newer, older) = (item(&app, 0), item(&app, 1));
+        assert!(older.dest.ends_with("Show/Bonus.mp3"));
+        assert!(newer.dest.ends_with("Show/Bonus (2).mp3"));
+
+        // A download that already sits at a path pushes the next one further
+        app.downloads.insert(episode_key("elsewhere"), DownloadState::Downloaded(newer.dest));
+        assert!(item(&app, 0).dest.ends_with("Show/Bonus (3).mp3"));
+    }
+
+    #[test]
+    fn test_forbidden_download_is_retried_once_with_a_fresh_link() {
+        let mut app = app_with_episodes(1);
+        let episode = episode_key("ep0");
+        let forbidden = || AppEvent::EnclosureForbidden {
+            episode: episode_key("ep0"),
+            error: "Server answered 403 Forbidden".to_string(),
+        };
+        app.handle_event(forbidden());
+        assert_eq!(app.status_message.as_deref(), Some("Fetching a fresh link for 'Episode 0'"));
+        assert!(matches!(app.download_state(&episode), Some(DownloadState::Queued)));
+
+        let fresh = Episode::new(
+            episode.episode.clone(),
+            "Episode 0".to_string(),
+            None,
+            Utc::now(),
+            None,
+            "http://example.com/ep0.mp3?token=new".to_string(),
+            None,
+        );
+        let url = app.podcasts[0].url().clone();
+        let refreshed =
+            Podcast::new(url, "Test Podcast".to_string(), None, None, None, vec![fresh]);
+        app.handle_event(AppEvent::PodcastRefreshed(refreshed));
+        let urls: Vec<&str> = app.download_queue().items().map(|i| i.url.as_str()).collect();
+        assert_eq!(urls, ["http://example.com/ep0.mp3?token=new"]);
+
+        // The fresh link is refused too: that's a real 403
+        app.download_queue.remove(&episode);
+        app.handle_event(forbidden());
+        assert!(matches!(app.download_state(&episode), Some(DownloadState::Failed(_))));
+    }
+
+    #[test]
+    fn test_unsubscribed_podcast_can_be_restored_from_trash() {
+        let dir = std::env::temp_dir().join(format!("rustero-app-trash-{}", std::process::id()));
+        let mut app = app_with_episodes(2).with_trash(Trash::new(dir.clone()));
+        let url = app.podcasts[0].url().clone();
+
+        app.unsubscribe(&url);
+        assert!(app.podcasts.is_empty());
+        app.run_command("restore nothing-like-it");
+        assert!(app.podcasts.is_empty());
+        app.run_command("restore test");
+        assert_eq!(app.podcasts.len(), 1);
+        assert_eq!(app.podcasts[0].episodes().len(), 2);
+        assert_eq!(app.status_message.as_deref(), Some("Restored 'Test Podcast'"));
+
+        // Resubscribed before restoring: the trashed copy stays where it is
+        app.unsubscribe(&url);
+        app.podcasts = app_with_episodes(1).podcasts;
+        app.run_command("restore test");
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("'Test Podcast' is already subscribed; left it in the trash")
+        );
+        assert_eq!(app.podcasts.len(), 1);
+        assert_eq!(app.trash.as_ref().unwrap().entries().unwrap().len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_category_filter_limits_podcast_rows() {
+        let mut app = App::new();
+        for (title, categories) in [("A", vec!["News"]), ("B", vec!["Technology", "News"])] {
+            let url = PodcastURL::new(&format!("http://example.com/{}", title));
+            let podcast = Podcast::new(url, title.to_string(), None, None, None, vec![])
+                .with_categories(categories.into_iter().map(String::from).collect());
+            app.podcasts.push(podcast);
+        }
+        assert_eq!(app.categories(), vec!["News", "Technology"]);
+
+        app.run_command("category technology");
+        assert_eq!(app.podcast_rows(), vec![1]);
+        app.run_command("lang de");
+        assert!(app.podcast_rows().is_empty());
+        assert_eq!(app.selected_podcast_index(), None);
+        app.run_command("lang");
+        app.run_command("category");
+        assert_eq!(app.podcast_rows(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_episode_note_is_edited_in_a_popup() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), crossterm::event::KeyModifiers::NONE);
+        let mut app = app_with_episodes(1);
+        app.focused_panel = FocusedPanel::Episodes;
+        app.set_selected_episode_index(Some(0));
+        let ep0 = episode_key("ep0");
+
+        app.on_key_event(key('a'));
+        for c in "rerun".chars() {
+            app.on_key_event(key(c)); // 'q' or 's' here must not quit or star
+        }
+        app.on_key_event(KeyEvent::from(KeyCode::Enter));
+        assert!(app.top_popup().is_none());
+        assert_eq!(app.episode_states().note(&ep0), Some("rerun"));
+
+        // Reopening starts from the saved text; Esc keeps it unchanged
+        app.on_key_event(key('a'));
+        app.on_key_event(key('!'));
+        app.on_key_event(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(app.episode_states().note(&ep0), Some("rerun"));
+
+        // Clearing the text removes the note
+        app.on_key_event(key('a'));
+        app.on_key_event(KeyEvent::new(
+            KeyCode::Char('u'),
+            crossterm::event::KeyModifiers::CONTROL,
+        ));
+        app.on_key_event(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.episode_states().note(&ep0), None);
+    }
+
+    #[test]
+    fn test_unsubscribe_asks_for_confirmation() {
+        let mut app = App::new();
+        for title in ["A", "B"] {
+            let url = PodcastURL::new(&format!("http://example.com/{}", title));
+            app.podcasts.push(Podcast::new(url, title.to_string(), None, None, None, vec![]));
+        }
+        app.set_selected_podcast_index(Some(1));
+
+        app.on_key(KeyCode::Char('x'));
+        assert!(matches!(app.top_popup(), Some(Popup::Confirm(ConfirmAction::Unsubscribe(_), _))));
+        app.on_key(KeyCode::Enter); // Focus starts on "No"
+        assert!(app.top_popup().is_none());
+        assert_eq!(app.podcasts.len(), 2);
+
+        app.on_key(KeyCode::Char('x'));
+        app.on_key(KeyCode::Char('y'));
+        assert_eq!(app.podcasts.len(), 1);
+        assert_eq!(app.podcasts[0].title(), "A");
+        assert_eq!(app.selected_podcast_index(), Some(0));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::errors::BackupError;
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+
+// Snapshots kept before the oldest ones are deleted
+const MAX_BACKUPS: usize = 10;
+
+// Copies of the data directory taken before bulk operations, so a bad import can be
+// rolled back by copying a snapshot over the data directory. Downloaded episodes are
+// left out; they are large and can be fetched again.
+#[derive(Debug, Clone)]
+pub struct Backups {
+    source: PathBuf,
+    dir: PathBuf,
+    excluded: Vec<PathBuf>,
+}
+
+impl Backups {
+    // `dir` may live inside `source`; it is never copied into itself
+    pub fn new(source: PathBuf, dir: PathBuf) -> Self {
+        Self { excluded: vec![dir.clone()], source, dir }
+    }
+
+    // Builder method, for the download directory and similar
+    pub fn with_excluded(mut self, path: PathBuf) -> Self {
+        self.excluded.push(path);
+        self
+    }
+
+    // Copies the data directory to "<dir>/<timestamp>-<reason>" and prunes old snapshots
+    pub fn snapshot(&self, reason: &str, now: DateTime<Utc>) -> Result<PathBuf, BackupError> {
+        let target = self.dir.join(format!("{}-{}", now.format("%Y%m%d-%H%M%S"), reason));
+        copy_dir(&self.source, &target, &self.excluded)?;
+        self.prune(MAX_BACKUPS)?;
+        Ok(target)
+    }
+
+    // Snapshot directories, newest first (the timestamp prefix sorts chronologically)
+    pub fn list(&self) -> Result<Vec<PathBuf>, BackupError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => return Err(BackupError::Io { path: self.dir.clone(), source }),
+        };
+        let mut snapshots: Vec<PathBuf> =
+            entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect();
+        snapshots.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+        Ok(snapshots)
+    }
+
+    fn prune(&self, keep: usize) -> Result<(), BackupError> {
+        for old in self.list()?.into_iter().skip(keep) {
+            std::fs::remove_dir_all(&old)
+                .map_err(|source| BackupError::Io { path: old, source })?;
+        }
+        Ok(())
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path, excluded: &[PathBuf]) -> Result<(), BackupError> {
+    let io_error = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| BackupError::Io { path, source }
+    };
+    std::fs::create_dir_all(to).map_err(io_error(to))?;
+    let entries = match std::fs::read_dir(from) {
+        Ok(entries) => entries,
+        // Nothing saved yet, so the snapshot is empty
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(source) => return Err(BackupError::Io { path: from.to_path_buf(), source }),
+    };
+    for entry in entries {
+        let path = entry.map_err(io_error(from))?.path();
+        if excluded.contains(&path) {
+            continue;
+        }
+        let target = to.join(path.file_name().unwrap_or_default());
+        if path.is_dir() {
+            copy_dir(&path, &target, excluded)?;
+        } else {
+            std::fs::copy(&path, &target).map_err(io_error(&path))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_skips_excluded_dirs_and_prunes() {
+        let data = std::env::temp_dir().join(format!("rustero-backup-{}", std::process::id()));
+        std::fs::create_dir_all(data.join("trash")).unwrap();
+        std::fs::create_dir_all(data.join("downloads")).unwrap();
+        std::fs::write(data.join("state.json"), "{}").unwrap();
+        std::fs::write(data.join("trash").join("1-a.json"), "{}").unwrap();
+        std::fs::write(data.join("downloads").join("ep.mp3"), "audio").unwrap();
+        let backups =
+            Backups::new(data.clone(), data.join("backups")).with_excluded(data.join("downloads"));
+
+        let start = Utc::now();
+        let first = backups.snapshot("import", start).unwrap();
+        assert!(first.join("state.json").exists());
+        assert!(first.join("trash").join("1-a.json").exists());
+        assert!(!first.join("downloads").exists());
+        assert!(!first.join("backups").exists());
+
+        for i in 1..=MAX_BACKUPS as i64 {
+            backups.snapshot("import", start + chrono::Duration::seconds(i)).unwrap();
+        }
+        let snapshots = backups.list().unwrap();
+        assert_eq!(snapshots.len(), MAX_BACKUPS);
+        assert!(!snapshots.contains(&first));
+        let _ = std::fs::remove_dir_all(&data);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::config::validate_profile_name;
+use crate::errors::ConfigError;
+use crate::logging::LogFormat;
+use log::LevelFilter;
+use std::path::PathBuf;
+
+// Things rustero can do instead of starting the TUI
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Validate(String), // rustero validate <url>
+    // rustero import [--urls] [--merge] <file>: subscribe, then start the TUI. With --merge,
+    // feeds that are already subscribed are refreshed instead of asking.
+    Import { path: PathBuf, merge: bool },
+    // rustero add <url>, or just rustero <podcast://... link>: subscribe in the running
+    // instance if there is one, else start the TUI and offer the subscription there
+    Add(String),
+}
+
+// Command line flags. They override the matching config settings.
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    pub command: Option<CliCommand>,
+    pub profile: Option<String>, // --profile <name>; "default" is the same as leaving it out
+    pub log_file: Option<PathBuf>, // --log-file <path>
+    pub log_level: Option<String>, // --log-level <spec>, e.g. "info,rustero::refresh=debug"
+    pub log_format: Option<LogFormat>, // --log-format text|json
+    pub verbosity: u8,           // -v / --verbose: debug, -vv: trace
+}
+
+impl CliArgs {
+    // Takes the arguments without the program name. Both "--flag value" and "--flag=value"
+    // work; unknown arguments are ignored.
+    pub fn parse<I>(args: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        let mut cli = CliArgs::default();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = |name: &'static str| {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or(ConfigError::MissingArgument(name))
+            };
+            match flag.as_str() {
+                "--profile" => cli.profile = Some(value("--profile")?),
+                "--log-file" => cli.log_file = Some(PathBuf::from(value("--log-file")?)),
+                "--log-level" => cli.log_level = Some(value("--log-level")?),
+                "--log-format" => {
+                    let format = value("--log-format")?;
+                    cli.log_format = Some(match format.as_str() {
+                        "text" => LogFormat::Text,
+                        "json" => LogFormat::Json,
+                        _ => return Err(ConfigError::InvalidArgument("--log-format", format)),
+                    });
+                }
+                "-v" | "--verbose" => cli.verbosity += 1,
+                "-vv" => cli.verbosity += 2,
+                "import" if cli.command.is_none() => {
+                    let mut merge = false;
+                    let path = loop {
+                        match args.next().ok_or(ConfigError::MissingArgument("import"))? {
+                            // The format is detected from the content; --urls is accepted for clarity
+                            flag if flag == "--urls" => {}
+                            flag if flag == "--merge" => merge = true,
+                            path => break PathBuf::from(path),
+                        }
+                    };
+                    cli.command = Some(CliCommand::Import { path, merge });
+                }
+                "add" if cli.command.is_none() => {
+                    let link = args.next().ok_or(ConfigError::MissingArgument("add"))?;
+                    cli.command = Some(CliCommand::Add(link));
+                }
+                // Desktops open podcast:// links by passing them as the only argument
+                link if cli.command.is_none() && link.contains("://") => {
+                    cli.command = Some(CliCommand::Add(link.to_string()));
+                }
+                "validate" if cli.command.is_none() => {
+                    let url = args.next().ok_or(ConfigError::MissingArgument("validate"))?;
+                    cli.command = Some(CliCommand::Validate(url));
+                }
+                _ => {}
+            }
+        }
+        if cli.profile.as_deref() == Some("default") {
+            cli.profile = None;
+        }
+        if let Some(name) = &cli.profile {
+            validate_profile_name(name)?;
+        }
+        Ok(cli)
+    }
+
+    // The least verbose default level the -v flags ask for
+    pub fn verbosity_level(&self) -> LevelFilter {
+        match self.verbosity {
+            0 => LevelFilter::Off,
+            1 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(list: &[&str]) -> Result<CliArgs, ConfigError> {
+        CliArgs::parse(list.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_profile_flag() {
+        assert_eq!(parse(&[]).unwrap().profile, None);
+        assert_eq!(parse(&["--profile", "work"]).unwrap().profile, Some("work".to_string()));
+        assert_eq!(parse(&["--profile=test_1"]).unwrap().profile, Some("test_1".to_string()));
+        assert_eq!(parse(&["--profile", "default"]).unwrap().profile, None);
+        assert!(parse(&["--profile"]).is_err());
+        assert!(parse(&["--profile", "../home"]).is_err());
+        assert!(parse(&["--profile="]).is_err());
+    }
+
+    #[test]
+    fn test_log_flags() {
+        let cli = parse(&["--log-file", "/tmp/r.log", "--log-level=warn,rustero=debug"]).unwrap();
+        assert_eq!(cli.log_file, Some(PathBuf::from("/tmp/r.log")));
+        assert_eq!(cli.log_level.as_deref(), Some("warn,rustero=debug"));
+        assert!(parse(&["--log-level"]).is_err());
+        assert_eq!(parse(&["--log-format=json"]).unwrap().log_format, Some(LogFormat::Json));
+        assert!(parse(&["--log-format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_validate_command() {
+        let cli = parse(&["-v", "validate", "http://e.com/feed"]).unwrap();
+        assert_eq!(cli.command, Some(CliCommand::Validate("http://e.com/feed".to_string())));
+        assert_eq!(cli.verbosity, 1);
+        assert!(parse(&["validate"]).is_err());
+    }
+
+    #[test]
+    fn test_add_command() {
+        let add = |link: &str| Some(CliCommand::Add(link.to_string()));
+        assert_eq!(parse(&["add", "http://e.com/feed"]).unwrap().command, add("http://e.com/feed"));
+        assert_eq!(parse(&["podcast://e.com/feed"]).unwrap().command, add("podcast://e.com/feed"));
+        assert!(parse(&["add"]).is_err());
+    }
+
+    #[test]
+    fn test_import_command() {
+        let import =
+            |path: &str, merge| Some(CliCommand::Import { path: PathBuf::from(path), merge });
+        assert_eq!(
+            parse(&["import", "--urls", "feeds.txt"]).unwrap().command,
+            import("feeds.txt", false)
+        );
+        assert_eq!(parse(&["import", "subs.opml"]).unwrap().command, import("subs.opml", false));
+        assert_eq!(
+            parse(&["import", "--merge", "--urls", "feeds.txt"]).unwrap().command,
+            import("feeds.txt", true)
+        );
+        assert!(parse(&["import", "--urls"]).is_err());
+    }
+
+    #[test]
+    fn test_verbosity_flags() {
+        assert_eq!(parse(&[]).unwrap().verbosity_level(), LevelFilter::Off);
+        assert_eq!(parse(&["-v"]).unwrap().verbosity_level(), LevelFilter::Debug);
+        assert_eq!(parse(&["-vv"]).unwrap().verbosity_level(), LevelFilter::Trace);
+        assert_eq!(parse(&["-v", "--verbose"]).unwrap().verbosity_level(), LevelFilter::Trace);
+    }
+}
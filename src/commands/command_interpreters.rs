@@ -2,18 +2,49 @@
 use crate::commands::podcast_algebra::{CommandAccumulator, PodcastAlgebra};
 use crate::errors::PipelineError;
 use crate::podcast::PodcastURL;
-use crate::podcast_download::{FeedFetcher, download_and_create_podcast};
+use crate::podcast_download::{
+    FeedFetcher, FeedFormat, download_and_create_podcast_with_factory, sniff_feed_format,
+};
+use crate::podcast_factory::PodcastFactory;
 use async_trait::async_trait;
 use reqwest::Url;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
 pub struct PodcastPipelineInterpreter {
     fetcher: Arc<dyn FeedFetcher + Send + Sync>,
+    factory: PodcastFactory,
+    save_dir: Option<PathBuf>, // Where Save writes; None refuses (read-only, no data dir)
 }
 
 impl PodcastPipelineInterpreter {
     pub fn new(fetcher: Arc<dyn FeedFetcher + Send + Sync>) -> Self {
-        Self { fetcher }
+        Self { fetcher, factory: PodcastFactory::new(), save_dir: None }
+    }
+
+    // Builder methods
+    pub fn with_factory(mut self, factory: PodcastFactory) -> Self {
+        self.factory = factory;
+        self
+    }
+
+    // The profile's data directory, unless another instance holds it
+    pub fn with_save_dir(mut self, save_dir: Option<PathBuf>) -> Self {
+        self.save_dir = save_dir;
+        self
+    }
+}
+
+fn feed_format_from_content_type(content_type: &str) -> Option<FeedFormat> {
+    if content_type.contains("application/rss+xml") {
+        Some(FeedFormat::Rss)
+    } else if content_type.contains("application/atom+xml") {
+        Some(FeedFormat::Atom)
+    } else if content_type.contains("application/feed+json") {
+        Some(FeedFormat::JsonFeed)
+    } else {
+        None
     }
 }
 
@@ -30,7 +61,10 @@ impl PodcastAlgebra for PodcastPipelineInterpreter {
 
         let url_str = url_to_eval.as_str();
 
-        println!("Interpreter: Evaluating URL (efficiently): '{}'", url_str);
+        log::info!(
+            feed = url_str, step = "eval_url";
+            "Interpreter: Evaluating URL (efficiently): '{}'", url_str
+        );
 
         // Step 1: Basic URL parsing
         let parsed_url = match Url::parse(url_str) {
@@ -52,33 +86,48 @@ impl PodcastAlgebra for PodcastPipelineInterpreter {
         }
 
         // Step 3: Attempt to fetch headers to verify content type
-        match self.fetcher.fetch_headers(url_str).await {
+        let started = Instant::now();
+        let headers = self.fetcher.fetch_headers(url_str).await;
+        pipeline_data.timings.fetch += started.elapsed();
+        match headers {
             Ok(headers) => {
                 if let Some(content_type) = headers.get("content-type") {
                     let ct_lower = content_type.to_lowercase();
                     if ct_lower.contains("application/rss+xml")
                         || ct_lower.contains("application/atom+xml")
+                        || ct_lower.contains("application/feed+json")
                         || ct_lower.contains("application/xml")
                         || ct_lower.contains("text/xml")
                     {
-                        println!("Interpreter: URL validated by Content-Type: {}", content_type);
+                        log::info!(
+                            feed = url_str, step = "eval_url";
+                            "Interpreter: URL validated by Content-Type: {}", content_type
+                        );
                         pipeline_data.last_evaluated_url = Some(url_to_eval.clone());
                         pipeline_data.current_podcast = None;
+                        // Generic XML could be RSS or Atom; the download sniffs the body then
+                        pipeline_data.feed_format = feed_format_from_content_type(&ct_lower);
                         return Ok(pipeline_data); // Early return SUCCESS
                     } else {
-                        println!(
+                        log::info!(
+                            feed = url_str, step = "eval_url";
                             "Interpreter: Content-Type '{}' doesn't suggest RSS/Atom. Will try partial fetch.",
                             content_type
                         );
                     }
                 } else {
-                    println!("Interpreter: No Content-Type header found. Will try partial fetch.");
+                    log::info!(
+                        feed = url_str, step = "eval_url";
+                        "Interpreter: No Content-Type header found. Will try partial fetch."
+                    );
                 }
             }
             Err(e) => {
-                println!(
+                log::info!(
+                    feed = url_str, step = "eval_url";
                     "Interpreter: HEAD request failed for {}: {}. Will try partial fetch.",
-                    url_str, e
+                    url_str,
+                    e
                 );
                 // Don't return an error yet, partial fetch is the fallback
             }
@@ -86,20 +135,28 @@ impl PodcastAlgebra for PodcastPipelineInterpreter {
 
         // 4. Fallback to partial GET request. This is the final validation attempt.
         //    The result of this match block will be the function's return value.
-        match self.fetcher.fetch_partial_content(url_str, (0, 4095)).await {
+        let started = Instant::now();
+        let partial_content = self.fetcher.fetch_partial_content(url_str, (0, 4095)).await;
+        pipeline_data.timings.fetch += started.elapsed();
+        match partial_content {
             Ok(partial_content) => {
-                println!("Interpreter: Partial content: {}", partial_content);
-                if partial_content.to_lowercase().contains("<rss")
-                    || partial_content.to_lowercase().contains("<feed")
-                {
-                    println!("Interpreter: URL validated by partial content inspection.");
+                log::debug!(
+                    feed = url_str, step = "eval_url";
+                    "Interpreter: Partial content: {}", partial_content
+                );
+                if let Some(format) = sniff_feed_format(&partial_content) {
+                    log::info!(
+                        feed = url_str, step = "eval_url";
+                        "Interpreter: URL validated by partial content inspection ({:?}).", format
+                    );
                     pipeline_data.last_evaluated_url = Some(url_to_eval.clone());
                     pipeline_data.current_podcast = None;
+                    pipeline_data.feed_format = Some(format);
                     Ok(pipeline_data) // SUCCESSFUL VALIDATION
                 } else {
                     // DEFINITIVE FAILURE based on partial content
                     Err(PipelineError::EvaluationFailed(format!(
-                        "URL content (first 4KB) of '{}' doesn't appear to be a valid RSS/Atom feed.",
+                        "URL content (first 4KB) of '{}' doesn't appear to be an RSS, Atom or JSON feed.",
                         url_str
                     )))
                 }
@@ -126,39 +183,66 @@ impl PodcastAlgebra for PodcastPipelineInterpreter {
         }; // Propagate error
 
         // Strategy: Use evaluated URL if available, otherwise use the one from the Download command.
-        let url_to_use = match &pipeline_data.last_evaluated_url {
+        // The evaluated format only describes the evaluated URL.
+        let (url_to_use, feed_format) = match &pipeline_data.last_evaluated_url {
             Some(eval_url) => {
-                println!("Interpreter: Using evaluated URL for download: {}", eval_url.as_str());
-                eval_url
+                log::info!(
+                    feed = eval_url.as_str(), step = "download";
+                    "Interpreter: Using evaluated URL for download: {}", eval_url.as_str()
+                );
+                (eval_url, pipeline_data.feed_format)
             }
             None => {
-                println!(
+                log::info!(
+                    feed = explicit_url_from_command.as_str(), step = "download";
                     "Interpreter: No evaluated URL in context, using URL from Download command: {}",
                     explicit_url_from_command.as_str()
                 );
-                explicit_url_from_command
+                (explicit_url_from_command, None)
             }
         };
 
-        println!("Interpreter: Attempting download from: {}...", url_to_use.as_str());
+        log::info!(
+            feed = url_to_use.as_str(), step = "download";
+            "Interpreter: Attempting download from: {}...", url_to_use.as_str()
+        );
 
-        let podcast_obj = download_and_create_podcast(url_to_use, self.fetcher.as_ref()).await?; // The '?' handles the Result and early returns Err(DownloaderError) if needed
+        let podcast_obj = download_and_create_podcast_with_factory(
+            url_to_use,
+            self.fetcher.as_ref(),
+            &self.factory,
+            feed_format,
+            &mut pipeline_data.timings,
+        )
+        .await?; // The '?' handles the Result and early returns Err(DownloaderError) if needed
 
-        println!("Interpreter: Successfully downloaded '{}'.", podcast_obj.title());
+        log::info!(
+            feed = url_to_use.as_str(), step = "download";
+            "Interpreter: Successfully downloaded '{}' in {}.",
+            podcast_obj.title(),
+            pipeline_data.timings
+        );
         pipeline_data.current_podcast = Some(podcast_obj);
         pipeline_data.last_evaluated_url = None; // "Consume" the evaluated URL
+        pipeline_data.feed_format = None;
         Ok(pipeline_data)
     }
 
     async fn interpret_save(&mut self, current_acc: CommandAccumulator) -> CommandAccumulator {
-        let Ok(data) = current_acc else {
+        let Ok(mut data) = current_acc else {
             return current_acc;
         }; // Propagate error
 
+        let Some(save_dir) = &self.save_dir else {
+            return Err(PipelineError::SaveFailedWithMessage(
+                "no writable data directory (read-only, or no home directory)".to_string(),
+            ));
+        };
+        let started = Instant::now();
         if let Some(podcast_to_save) = &data.current_podcast {
-            println!(
-                "Interpreter: Saving podcast (from accumulator): '{}'...",
-                podcast_to_save.title()
+            log::info!(
+                feed = podcast_to_save.url().as_str(), step = "save";
+                "Interpreter: Saving podcast (from accumulator): '{}'...", podcast_to_save.title()
             );
 
             // Step 1: Serialize (handle its potential error)
@@ -178,24 +262,27 @@ impl PodcastAlgebra for PodcastPipelineInterpreter {
             // and if this function returns Result<_, PipelineError>, `?` can propagate it.
             // However, interpret_save returns CommandAccumulator (Result<PipelineData, PipelineError>),
             // so the success path of `?` needs to be `PipelineData`.
-            match std::fs::write("podcast.json", json_to_write).map_err(
-                |io_error: std::io::Error| PipelineError::SaveFailedWithSource {
-                    message: format!(
-                        "Failed to write podcast '{}' to disk",
-                        podcast_to_save.title()
-                    ),
-                    source: Box::new(io_error),
-                },
-            ) {
+            let write = std::fs::create_dir_all(save_dir)
+                .and_then(|_| std::fs::write(save_dir.join("podcast.json"), json_to_write));
+            match write.map_err(|io_error: std::io::Error| PipelineError::SaveFailedWithSource {
+                message: format!("Failed to write podcast '{}' to disk", podcast_to_save.title()),
+                source: Box::new(io_error),
+            }) {
                 Ok(_) => {
                     // fs::write succeeded
-                    println!("Interpreter: Podcast '{}' saved.", podcast_to_save.title());
+                    log::info!(
+                        feed = podcast_to_save.url().as_str(), step = "save";
+                        "Interpreter: Podcast '{}' saved.", podcast_to_save.title()
+                    );
+                    data.timings.save += started.elapsed();
                     Ok(data) // Return the original PipelineData
                 }
                 Err(pipeline_error) => Err(pipeline_error), // fs::write failed, map_err converted it
             }
         } else {
-            eprintln!("Interpreter: Save command executed, but no podcast in accumulator to save.");
+            log::warn!(
+                "Interpreter: Save command executed, but no podcast in accumulator to save."
+            );
             Err(PipelineError::InvalidState(
                 "Save called without a podcast in accumulator".to_string(),
             ))
@@ -203,7 +290,40 @@ impl PodcastAlgebra for PodcastPipelineInterpreter {
     }
 
     async fn interpret_end(&mut self, final_acc: CommandAccumulator) -> CommandAccumulator {
-        println!("Interpreter: Reached End. Final accumulator state: {:?}", final_acc);
+        log::debug!("Interpreter: Reached End. Final accumulator state: {:?}", final_acc);
         final_acc
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::podcast_algebra::{PipelineData, run_commands};
+    use crate::commands::podcast_commands::PodcastCmd;
+    use crate::podcast::Podcast;
+    use crate::podcast_download::FakeFetcher;
+
+    fn with_podcast() -> CommandAccumulator {
+        let url = PodcastURL::new("https://example.com/feed.xml");
+        let podcast = Podcast::new(url, "Example".to_string(), None, None, None, vec![]);
+        Ok(PipelineData { current_podcast: Some(podcast), ..PipelineData::default() })
+    }
+
+    #[tokio::test]
+    async fn test_save_writes_under_the_data_dir_only() {
+        let fetcher = Arc::new(FakeFetcher::new(""));
+        let cmd = PodcastCmd::save(PodcastCmd::end());
+
+        // Without a writable data directory nothing gets written, not even to the cwd
+        let mut read_only = PodcastPipelineInterpreter::new(fetcher.clone());
+        let result = run_commands(&cmd, with_podcast(), &mut read_only).await;
+        assert!(matches!(result, Err(PipelineError::SaveFailedWithMessage(_))));
+
+        let dir = std::env::temp_dir().join(format!("rustero-save-{}", std::process::id()));
+        let mut writable =
+            PodcastPipelineInterpreter::new(fetcher).with_save_dir(Some(dir.clone()));
+        assert!(run_commands(&cmd, with_podcast(), &mut writable).await.is_ok());
+        assert!(dir.join("podcast.json").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
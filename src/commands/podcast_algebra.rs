@@ -1,6 +1,8 @@
 // src/podcast_cmd.rs (continued)
 use crate::errors::PipelineError;
+use crate::metrics::FeedTimings;
 use crate::podcast::{Podcast, PodcastURL};
+use crate::podcast_download::FeedFormat;
 
 use crate::commands::podcast_commands::PodcastCmd;
 use async_trait::async_trait;
@@ -9,6 +11,8 @@ use async_trait::async_trait;
 pub struct PipelineData {
     pub last_evaluated_url: Option<PodcastURL>, // Result from EvalUrl
     pub current_podcast: Option<Podcast>,       // Result from Download
+    pub feed_format: Option<FeedFormat>, // What EvalUrl found the feed to be, if it could tell
+    pub timings: FeedTimings,            // Added to by every step
 }
 
 // The Accumulator type that will be threaded through
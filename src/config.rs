@@ -0,0 +1,181 @@
+use crate::episode_download::DEFAULT_DOWNLOAD_TEMPLATE;
+use crate::errors::ConfigError;
+use crate::logging::LogFormat;
+use crate::search::SearchProviderName;
+use crate::theme::ThemeName;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// User settings, read from $XDG_CONFIG_HOME/rustero/config.json
+// (or ~/.config/rustero/config.json). Missing keys fall back to defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub theme: ThemeName,
+    pub poll_interval_ms: u64, // Max time to block waiting for input
+    pub tick_rate_ms: u64,     // Interval for periodic UI work (clock, progress updates)
+    pub strip_tracking_prefixes: bool, // Unwrap podtrac/chartable/... redirects from audio URLs
+    pub compact_player: bool,  // Start with the single-line player (toggle with 'm')
+    pub search_provider: SearchProviderName, // Podcast discovery service
+    pub auto_refresh: bool,    // Refresh feeds in the background, paced by their publish cadence
+    pub refresh_on_startup: bool, // Refresh every feed once right after the UI comes up
+    pub download_dir: Option<PathBuf>, // Where episodes are saved; see `download_dir()`
+    pub download_template: String, // File layout below download_dir, e.g. "{podcast}/{date}-{title}.{ext}"
+    pub max_parallel_downloads: usize, // Further downloads wait in the queue
+    pub hide_explicit: bool,       // Start with explicit episodes filtered out (toggle with 'E')
+    pub trash_retention_days: u64, // Unsubscribed podcasts can be restored for this long
+    pub log_file: Option<PathBuf>, // See `log_file()`; --log-file overrides it
+    pub log_level: String,         // e.g. "info,rustero::refresh=debug"; --log-level overrides it
+    pub log_format: LogFormat,     // "text" or "json" (one object per line)
+    pub log_max_bytes: u64,        // The log is rotated to "<file>.1" past this size, 0 = never
+    #[serde(skip)]
+    pub profile: Option<String>, // Set from --profile, keeps config and data apart per profile
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: ThemeName::default(),
+            poll_interval_ms: 100,
+            tick_rate_ms: 1000,
+            strip_tracking_prefixes: false,
+            compact_player: false,
+            search_provider: SearchProviderName::default(),
+            auto_refresh: true,
+            refresh_on_startup: false,
+            download_dir: None,
+            download_template: DEFAULT_DOWNLOAD_TEMPLATE.to_string(),
+            max_parallel_downloads: 2,
+            hide_explicit: false,
+            trash_retention_days: 30,
+            log_file: None,
+            log_level: "info".to_string(),
+            log_format: LogFormat::default(),
+            log_max_bytes: 5 * 1024 * 1024,
+            profile: None,
+        }
+    }
+}
+
+impl Config {
+    // Zero would turn the event loop into a busy loop, so clamp to 1ms
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms.max(1))
+    }
+
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.tick_rate_ms.max(1))
+    }
+
+    // $XDG_DATA_HOME/rustero/downloads (or ~/.local/share/...) unless configured
+    pub fn download_dir(&self) -> PathBuf {
+        if let Some(dir) = &self.download_dir {
+            return dir.clone();
+        }
+        Self::data_dir(self.profile.as_deref())
+            .map(|dir| dir.join("downloads"))
+            .unwrap_or_else(|| PathBuf::from("downloads"))
+    }
+
+    // $XDG_DATA_HOME/rustero/rustero.log (or ~/.local/share/...) unless configured
+    pub fn log_file(&self) -> PathBuf {
+        if let Some(path) = &self.log_file {
+            return path.clone();
+        }
+        Self::data_dir(self.profile.as_deref())
+            .map(|dir| dir.join("rustero.log"))
+            .unwrap_or_else(|| PathBuf::from("rustero.log"))
+    }
+
+    // $XDG_DATA_HOME/rustero (or ~/.local/share/rustero) for files the app maintains itself.
+    // A named profile gets its own subdirectory below that.
+    pub fn data_dir(profile: Option<&str>) -> Option<PathBuf> {
+        let data_dir = std::env::var_os("XDG_DATA_HOME").map(PathBuf::from).or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+        Some(profile_dir(data_dir.join("rustero"), profile))
+    }
+
+    pub fn default_path(profile: Option<&str>) -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(profile_dir(config_dir.join("rustero"), profile).join("config.json"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|source| ConfigError::Io { path: path.to_path_buf(), source })?;
+        Self::from_json(&content)
+            .map_err(|source| ConfigError::Parse { path: path.to_path_buf(), source })
+    }
+
+    // A missing config file is not an error, a broken one is
+    pub fn load_or_default(profile: Option<&str>) -> Result<Self, ConfigError> {
+        let mut config = match Self::default_path(profile) {
+            Some(path) if path.exists() => Self::load(&path)?,
+            _ => Self::default(),
+        };
+        config.profile = profile.map(str::to_string);
+        Ok(config)
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(content)
+    }
+}
+
+// The default profile keeps the original layout, so existing setups are untouched
+fn profile_dir(base: PathBuf, profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(name) => base.join("profiles").join(name),
+        None => base,
+    }
+}
+
+// Profile names end up in paths, so keep them to a safe set of characters
+pub fn validate_profile_name(name: &str) -> Result<(), ConfigError> {
+    let valid =
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid { Ok(()) } else { Err(ConfigError::InvalidProfile(name.to_string())) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_keys_use_defaults() {
+        let config = Config::from_json("{}").unwrap();
+        assert_eq!(config.theme, ThemeName::Default);
+        assert_eq!(config.poll_interval(), Duration::from_millis(100));
+        assert_eq!(config.tick_rate(), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_intervals_are_read_from_json() {
+        let config =
+            Config::from_json(r#"{ "poll_interval_ms": 250, "tick_rate_ms": 0 }"#).unwrap();
+        assert_eq!(config.poll_interval(), Duration::from_millis(250));
+        assert_eq!(config.tick_rate(), Duration::from_millis(1));
+    }
+
+    #[test]
+    fn test_theme_is_read_from_json() {
+        let config = Config::from_json(r#"{ "theme": "monochrome" }"#).unwrap();
+        assert_eq!(config.theme, ThemeName::Monochrome);
+    }
+
+    #[test]
+    fn test_unknown_theme_is_an_error() {
+        assert!(Config::from_json(r#"{ "theme": "neon" }"#).is_err());
+    }
+
+    #[test]
+    fn test_profiles_get_their_own_directories() {
+        let base = PathBuf::from("/data/rustero");
+        assert_eq!(profile_dir(base.clone(), None), base);
+        assert_eq!(profile_dir(base, Some("work")), PathBuf::from("/data/rustero/profiles/work"));
+    }
+}
@@ -0,0 +1,62 @@
+// Only one rustero at a time writes a profile's data directory. The lock is an OS file
+// lock, so it goes away with the process even if that crashes; there's no stale lock file
+// to clean up by hand.
+use crate::errors::DataLockError;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub struct DataLock {
+    _file: File, // Held until dropped
+}
+
+impl DataLock {
+    pub fn acquire(data_dir: &Path) -> Result<DataLock, DataLockError> {
+        let path = lock_path(data_dir);
+        let io_error = |source| DataLockError::Io { path: path.clone(), source };
+        std::fs::create_dir_all(data_dir).map_err(io_error)?;
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false) // The holder's pid stays readable until we own the lock
+            .open(&path)
+            .map_err(io_error)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let pid = std::fs::read_to_string(&path).unwrap_or_default().trim().to_string();
+                let pid = if pid.is_empty() { "unknown".to_string() } else { pid };
+                return Err(DataLockError::Held { path: data_dir.to_path_buf(), pid });
+            }
+            Err(TryLockError::Error(e)) => return Err(io_error(e)),
+        }
+        // Only for the error message another instance shows
+        file.set_len(0).map_err(io_error)?;
+        write!(file, "{}", std::process::id()).map_err(io_error)?;
+        Ok(DataLock { _file: file })
+    }
+}
+
+fn lock_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("rustero.lock")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_lock_is_refused_until_the_first_is_dropped() {
+        let dir = std::env::temp_dir().join(format!("rustero-lock-{}", std::process::id()));
+        let lock = DataLock::acquire(&dir).unwrap();
+        match DataLock::acquire(&dir) {
+            Err(DataLockError::Held { pid, .. }) => assert_eq!(pid, std::process::id().to_string()),
+            other => panic!("expected the lock to be held, got {:?}", other),
+        }
+        drop(lock);
+        assert!(DataLock::acquire(&dir).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,638 @@
+use crate::errors::EpisodeDownloadError;
+use crate::podcast::{Episode, EpisodeKey, Podcast};
+use crate::tasks::TaskId;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+// Called with (bytes received, expected total) as data arrives
+pub type ProgressFn = dyn Fn(u64, Option<u64>) + Send + Sync;
+
+// Feed enclosure lengths are often stale (dynamic ad insertion, re-encodes), so only a
+// file this much shorter than the feed claims counts as incomplete
+const FEED_SIZE_TOLERANCE: f64 = 0.9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadState {
+    Queued,
+    Downloading { task: TaskId, dest: PathBuf }, // Progress is tracked by the task
+    Downloaded(PathBuf),
+    Corrupt { path: PathBuf, reason: String }, // Saved, but shorter than expected
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DownloadedFile {
+    pub bytes: u64,
+    pub content_length: Option<u64>, // As announced by the server
+}
+
+impl DownloadedFile {
+    // Err(reason) when fewer bytes arrived than the server announced, or clearly fewer than
+    // the feed's enclosure length when the server didn't say
+    pub fn check_complete(&self, feed_size: Option<u64>) -> Result<(), String> {
+        let short = |expected: u64| {
+            format!("incomplete: {} of {}", format_size(self.bytes), format_size(expected))
+        };
+        match (self.content_length, feed_size.filter(|size| *size > 0)) {
+            (Some(expected), _) if self.bytes < expected => Err(short(expected)),
+            (None, Some(expected))
+                if (self.bytes as f64) < expected as f64 * FEED_SIZE_TOLERANCE =>
+            {
+                Err(short(expected))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// "12.3 MB", "512 KB", "87 B"
+pub fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1_000_000 => format!("{:.1} MB", b as f64 / 1_000_000.0),
+        b if b >= 1_000 => format!("{} KB", b / 1_000),
+        b => format!("{} B", b),
+    }
+}
+
+// User-initiated downloads go ahead of automatic ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DownloadPriority {
+    User,
+    Auto,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedDownload {
+    pub episode: EpisodeKey,
+    pub title: String,
+    pub url: String,
+    pub dest: PathBuf,
+    pub priority: DownloadPriority,
+    pub expected_size: Option<u64>, // Enclosure length from the feed
+}
+
+// Downloads waiting for a free slot, next one first
+#[derive(Debug, Default)]
+pub struct DownloadQueue {
+    items: VecDeque<QueuedDownload>,
+}
+
+impl DownloadQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Behind everything of the same or higher priority, ahead of anything lower
+    pub fn push(&mut self, item: QueuedDownload) {
+        let index =
+            self.items.iter().position(|q| q.priority > item.priority).unwrap_or(self.items.len());
+        self.items.insert(index, item);
+    }
+
+    pub fn pop(&mut self) -> Option<QueuedDownload> {
+        self.items.pop_front()
+    }
+
+    pub fn remove(&mut self, episode: &EpisodeKey) -> Option<QueuedDownload> {
+        let index = self.items.iter().position(|q| &q.episode == episode)?;
+        self.items.remove(index)
+    }
+
+    // Manual reordering; returns the item's new index
+    pub fn move_up(&mut self, index: usize) -> usize {
+        if index == 0 || index >= self.items.len() {
+            return index;
+        }
+        self.items.swap(index, index - 1);
+        index - 1
+    }
+
+    pub fn move_down(&mut self, index: usize) -> usize {
+        if index + 1 >= self.items.len() {
+            return index;
+        }
+        self.items.swap(index, index + 1);
+        index + 1
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &QueuedDownload> {
+        self.items.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[async_trait]
+pub trait EpisodeDownloader: Send + Sync {
+    // Downloads `url` into `dest`
+    async fn download(
+        &self,
+        url: &str,
+        dest: &Path,
+        progress: &ProgressFn,
+    ) -> Result<DownloadedFile, EpisodeDownloadError>;
+}
+
+pub struct HttpEpisodeDownloader {
+    client: reqwest::Client,
+}
+
+impl HttpEpisodeDownloader {
+    pub fn new() -> Self {
+        Self { client: reqwest::Client::new() }
+    }
+}
+
+impl Default for HttpEpisodeDownloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EpisodeDownloader for HttpEpisodeDownloader {
+    // Streams into `<dest>.part` and renames once complete, so a file at `dest` is always whole.
+    // A `.part` left by an earlier attempt is continued with a Range request when its
+    // resume marker matches.
+    async fn download(
+        &self,
+        url: &str,
+        dest: &Path,
+        progress: &ProgressFn,
+    ) -> Result<DownloadedFile, EpisodeDownloadError> {
+        let part = partial_path(dest);
+        let io_error = |source| EpisodeDownloadError::Io { path: part.clone(), source };
+        let resume = ResumeMarker::read(dest).filter(|marker| marker.url == url);
+        let offset = match &resume {
+            Some(_) => tokio::fs::metadata(&part).await.map(|m| m.len()).unwrap_or(0),
+            None => 0,
+        };
+
+        let mut request = self.client.get(url);
+        if let Some(marker) = resume.as_ref().filter(|_| offset > 0) {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+            // Only continue if the file on the server is still the one we started with
+            if let Some(validator) = marker.etag.as_ref().or(marker.last_modified.as_ref()) {
+                request = request.header(reqwest::header::IF_RANGE, validator);
+            }
+        }
+        let mut response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(EpisodeDownloadError::Status(response.status()));
+        }
+        // 206 continues the partial file, anything else starts over
+        let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT && offset > 0;
+        let offset = if resumed { offset } else { 0 };
+        let total = response.content_length().map(|length| length + offset);
+
+        if let Some(dir) = dest.parent() {
+            tokio::fs::create_dir_all(dir).await.map_err(io_error)?;
+        }
+        match ResumeMarker::from_response(url, &response, total) {
+            Some(marker) => marker.write(dest),
+            None => ResumeMarker::remove(dest),
+        }
+        let mut file = if resumed {
+            tokio::fs::OpenOptions::new().append(true).open(&part).await.map_err(io_error)?
+        } else {
+            tokio::fs::File::create(&part).await.map_err(io_error)?
+        };
+        let mut received = offset;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await.map_err(io_error)?;
+            received += chunk.len() as u64;
+            progress(received, total);
+        }
+        file.flush().await.map_err(io_error)?;
+        tokio::fs::rename(&part, dest).await.map_err(io_error)?;
+        ResumeMarker::remove(dest);
+        Ok(DownloadedFile { bytes: received, content_length: total })
+    }
+}
+
+// Sits next to a `.part` file when the server supports byte ranges, so a later attempt can
+// pick up where this one stopped
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResumeMarker {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_length: Option<u64>,
+}
+
+impl ResumeMarker {
+    // None unless the server accepts ranges and gives us a validator to check on resume
+    fn from_response(url: &str, response: &reqwest::Response, total: Option<u64>) -> Option<Self> {
+        let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok());
+        let ranges = header(reqwest::header::ACCEPT_RANGES).is_some_and(|v| v.contains("bytes"))
+            || response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+        let etag = header(reqwest::header::ETAG).map(str::to_string);
+        let last_modified = header(reqwest::header::LAST_MODIFIED).map(str::to_string);
+        if !ranges || (etag.is_none() && last_modified.is_none()) {
+            return None;
+        }
+        Some(Self { url: url.to_string(), etag, last_modified, content_length: total })
+    }
+
+    pub fn path(dest: &Path) -> PathBuf {
+        with_suffix(&partial_path(dest), ".json")
+    }
+
+    pub fn read(dest: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::path(dest)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    // Best effort: without the marker the next attempt simply starts from scratch
+    pub fn write(&self, dest: &Path) {
+        let path = Self::path(dest);
+        let result = serde_json::to_string(self)
+            .map_err(std::io::Error::other)
+            .and_then(|json| std::fs::write(&path, json));
+        if let Err(e) = result {
+            log::warn!("Could not write {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn remove(dest: &Path) {
+        remove_if_exists(&Self::path(dest));
+    }
+}
+
+// Where a download in progress is written
+pub fn partial_path(dest: &Path) -> PathBuf {
+    with_suffix(dest, ".part")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn remove_if_exists(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        log::warn!("Could not remove {}: {}", path.display(), e);
+    }
+}
+
+// Removes what an aborted or failed download left behind. Missing files are fine.
+pub fn remove_partial(dest: &Path) {
+    remove_if_exists(&partial_path(dest));
+    ResumeMarker::remove(dest);
+}
+
+// Like `remove_partial`, but keeps a `.part` file that a later attempt can continue
+pub fn discard_partial_unless_resumable(dest: &Path) -> bool {
+    let resumable = ResumeMarker::read(dest).is_some() && partial_path(dest).exists();
+    if !resumable {
+        remove_partial(dest);
+    }
+    resumable
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct PartialCleanup {
+    pub kept: usize,    // Resumable `.part` files
+    pub removed: usize, // `.part` files without a marker, and markers without a `.part`
+}
+
+// Startup pass over the download directory: partial files nobody can resume are deleted,
+// and so are markers whose partial file is gone
+pub fn reconcile_partial_files(dir: &Path) -> PartialCleanup {
+    let mut cleanup = PartialCleanup::default();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return cleanup;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let nested = reconcile_partial_files(&path);
+            cleanup.kept += nested.kept;
+            cleanup.removed += nested.removed;
+            continue;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        if let Some(dest_name) = name.strip_suffix(".part") {
+            let dest = path.with_file_name(dest_name);
+            if ResumeMarker::read(&dest).is_some() {
+                cleanup.kept += 1;
+            } else {
+                remove_partial(&dest);
+                cleanup.removed += 1;
+            }
+        } else if let Some(dest_name) = name.strip_suffix(".part.json")
+            && !path.with_file_name(format!("{}.part", dest_name)).exists()
+        {
+            remove_if_exists(&path);
+            cleanup.removed += 1;
+        }
+    }
+    cleanup
+}
+
+pub const DEFAULT_DOWNLOAD_TEMPLATE: &str = "{podcast}/{title}.{ext}";
+
+// Expands a layout template like "{podcast}/{date}-{title}.{ext}" below `dir`.
+// Placeholders: {podcast} {title} {date} (YYYY-MM-DD) {year} {month} {id} {ext}.
+// Each '/'-separated part is sanitized on its own, so values can't add directories or
+// climb out of `dir`.
+pub fn episode_path(dir: &Path, template: &str, podcast: &Podcast, episode: &Episode) -> PathBuf {
+    let published = |format| {
+        episode.published_date().map_or("undated".to_string(), |d| d.format(format).to_string())
+    };
+    let values = [
+        ("{podcast}", podcast.title().to_string()),
+        ("{title}", episode.title().to_string()),
+        ("{date}", published("%Y-%m-%d")),
+        ("{year}", published("%Y")),
+        ("{month}", published("%m")),
+        ("{id}", episode.id().to_string()),
+        ("{ext}", file_extension(episode)),
+    ];
+    let template = if template.trim().is_empty() { DEFAULT_DOWNLOAD_TEMPLATE } else { template };
+    let mut path = dir.to_path_buf();
+    for segment in template.split(['/', '\\']).filter(|s| !s.trim().is_empty()) {
+        let mut expanded = segment.to_string();
+        for (placeholder, value) in &values {
+            expanded = expanded.replace(placeholder, &value.replace(['/', '\\'], "_"));
+        }
+        path.push(truncate_file_name(&sanitize_file_name(&expanded), MAX_NAME_BYTES));
+    }
+    path
+}
+
+// Longest file or directory name we write, in bytes. Filesystems mostly stop at 255; this
+// leaves room for ".part" and a " (2)" counter.
+const MAX_NAME_BYTES: usize = 200;
+
+// "Show/Title.mp3" -> "Show/Title (2).mp3", for an episode whose path another one took
+pub fn numbered_path(path: &Path, n: usize) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let (stem, ext) = split_extension(&name);
+    let suffix = format!(" ({}){}", n, ext);
+    let stem = truncate_at_char_boundary(stem, MAX_NAME_BYTES.saturating_sub(suffix.len()));
+    path.with_file_name(format!("{}{}", stem, suffix))
+}
+
+// Cuts long names down to `max` bytes, keeping the extension
+fn truncate_file_name(name: &str, max: usize) -> String {
+    if name.len() <= max {
+        return name.to_string();
+    }
+    let (stem, ext) = split_extension(name);
+    let stem = truncate_at_char_boundary(stem, max.saturating_sub(ext.len()));
+    let stem = stem.trim_end_matches(['.', ' ']);
+    format!("{}{}", if stem.is_empty() { "untitled" } else { stem }, ext)
+}
+
+fn truncate_at_char_boundary(s: &str, max: usize) -> &str {
+    let mut end = max.min(s.len());
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+// "Title.mp3" -> ("Title", ".mp3"). Only short endings count, "Ep. 12 of the show" has none.
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(i) if i > 0 && name.len() - i <= 6 && !name[i..].contains(' ') => name.split_at(i),
+        _ => (name, ""),
+    }
+}
+
+// Taken from the enclosure URL's path, e.g. "mp3" for ".../ep1.mp3?source=rss"
+fn file_extension(episode: &Episode) -> String {
+    let path = episode.audio_url().split(['?', '#']).next().unwrap_or_default();
+    let last_segment = path.rsplit('/').next().unwrap_or_default();
+    match last_segment.rsplit_once('.') {
+        Some((_, ext))
+            if !ext.is_empty()
+                && ext.len() <= 5
+                && ext.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            ext.to_ascii_lowercase()
+        }
+        _ => if episode.is_video() { "mp4" } else { "mp3" }.to_string(),
+    }
+}
+
+// Replaces characters that are invalid (or awkward) in file names on common filesystems
+pub fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Leading dots would hide the file, trailing dots and spaces upset Windows
+    let trimmed = cleaned.trim().trim_start_matches('.').trim_end_matches(['.', ' ']);
+    if trimmed.is_empty() { "untitled".to_string() } else { trimmed.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::podcast::{EpisodeID, EpisodeKey, PodcastURL};
+    use chrono::Utc;
+
+    fn episode(title: &str, audio_url: &str) -> Episode {
+        Episode::new(
+            EpisodeID::new("1"),
+            title.to_string(),
+            None,
+            Utc::now(),
+            None,
+            audio_url.to_string(),
+            None,
+        )
+    }
+
+    fn key(episode: &str) -> EpisodeKey {
+        EpisodeKey::new(&PodcastURL::new("http://example.com/feed"), &EpisodeID::new(episode))
+    }
+
+    fn queued(episode: &str, priority: DownloadPriority) -> QueuedDownload {
+        QueuedDownload {
+            episode: key(episode),
+            title: episode.to_string(),
+            url: format!("http://example.com/{}.mp3", episode),
+            dest: PathBuf::from(episode),
+            priority,
+            expected_size: None,
+        }
+    }
+
+    #[test]
+    fn test_user_downloads_go_ahead_of_auto_downloads() {
+        let mut queue = DownloadQueue::new();
+        queue.push(queued("auto1", DownloadPriority::Auto));
+        queue.push(queued("auto2", DownloadPriority::Auto));
+        queue.push(queued("user1", DownloadPriority::User));
+        queue.push(queued("user2", DownloadPriority::User));
+        let order: Vec<&str> = queue.items().map(|q| q.title.as_str()).collect();
+        assert_eq!(order, vec!["user1", "user2", "auto1", "auto2"]);
+
+        assert_eq!(queue.move_up(2), 1);
+        assert_eq!(queue.move_up(0), 0);
+        assert_eq!(queue.move_down(3), 3);
+        assert_eq!(queue.remove(&key("user2")).map(|q| q.title), Some("user2".into()));
+        let order: Vec<&str> = queue.items().map(|q| q.title.as_str()).collect();
+        assert_eq!(order, vec!["user1", "auto1", "auto2"]);
+        assert_eq!(queue.pop().map(|q| q.title), Some("user1".to_string()));
+    }
+
+    #[test]
+    fn test_check_complete() {
+        let file = |bytes, content_length| DownloadedFile { bytes, content_length };
+        assert!(file(100, Some(100)).check_complete(Some(5_000)).is_ok()); // Server wins
+        assert_eq!(
+            file(40_000_000, Some(52_000_000)).check_complete(None),
+            Err("incomplete: 40.0 MB of 52.0 MB".to_string())
+        );
+        // Without a Content-Length the feed's size is a loose hint
+        assert!(file(95, None).check_complete(Some(100)).is_ok());
+        assert!(file(50, None).check_complete(Some(100)).is_err());
+        assert!(file(50, None).check_complete(Some(0)).is_ok());
+        assert!(file(50, None).check_complete(None).is_ok());
+    }
+
+    #[test]
+    fn test_reconcile_keeps_only_resumable_partials() {
+        let dir = std::env::temp_dir().join(format!("rustero-partials-{}", std::process::id()));
+        let show = dir.join("Show");
+        std::fs::create_dir_all(&show).unwrap();
+        let marker = ResumeMarker {
+            url: "http://example.com/a.mp3".to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            content_length: Some(100),
+        };
+        std::fs::write(show.join("a.mp3.part"), b"resumable").unwrap();
+        marker.write(&show.join("a.mp3"));
+        std::fs::write(show.join("b.mp3.part"), b"no marker").unwrap();
+        std::fs::write(show.join("c.mp3.part.json"), b"{}").unwrap(); // Marker without a file
+        std::fs::write(show.join("d.mp3"), b"finished").unwrap();
+
+        let cleanup = reconcile_partial_files(&dir);
+        assert_eq!(cleanup, PartialCleanup { kept: 1, removed: 2 });
+        assert_eq!(ResumeMarker::read(&show.join("a.mp3")), Some(marker));
+        assert!(!show.join("b.mp3.part").exists());
+        assert!(!show.join("c.mp3.part.json").exists());
+        assert!(show.join("d.mp3").exists());
+
+        assert!(discard_partial_unless_resumable(&show.join("a.mp3")));
+        assert!(show.join("a.mp3.part").exists());
+        std::fs::remove_file(ResumeMarker::path(&show.join("a.mp3"))).unwrap();
+        assert!(!discard_partial_unless_resumable(&show.join("a.mp3")));
+        assert!(!show.join("a.mp3.part").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_episode_path_template() {
+        let podcast = Podcast::new(
+            PodcastURL::new("http://example.com/feed"),
+            "Show".to_string(),
+            None,
+            None,
+            None,
+            vec![],
+        );
+        let mut episode = episode("../../etc/passwd", "http://e.com/a.m4a");
+        episode = Episode::new(
+            EpisodeID::new("guid-1"),
+            episode.title().to_string(),
+            None,
+            chrono::DateTime::parse_from_rfc3339("2024-03-05T10:00:00Z").unwrap().into(),
+            None,
+            episode.audio_url().to_string(),
+            None,
+        );
+        let path = |template| episode_path(Path::new("/d"), template, &podcast, &episode);
+
+        assert_eq!(
+            path("{podcast}/{date}-{title}.{ext}"),
+            Path::new("/d/Show/2024-03-05-.._.._etc_passwd.m4a")
+        );
+        assert_eq!(path("{year}/{month}/{id}.{ext}"), Path::new("/d/2024/03/guid-1.m4a"));
+        // Literal dot-dot segments can't escape the download directory either
+        assert_eq!(path("../{id}.mp3"), Path::new("/d/untitled/guid-1.mp3"));
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("AC/DC: Live?"), "AC_DC_ Live_");
+        assert_eq!(sanitize_file_name("..hidden."), "hidden");
+        assert_eq!(sanitize_file_name("  "), "untitled");
+    }
+
+    #[test]
+    fn test_episode_path() {
+        let podcast = Podcast::new(
+            PodcastURL::new("http://example.com/feed"),
+            "Rust / Daily".to_string(),
+            None,
+            None,
+            None,
+            vec![],
+        );
+        let path = episode_path(
+            Path::new("/downloads"),
+            DEFAULT_DOWNLOAD_TEMPLATE,
+            &podcast,
+            &episode("Ep 1: Hello", "http://cdn.example.com/ep1.MP3?source=rss"),
+        );
+        assert_eq!(path, Path::new("/downloads/Rust _ Daily/Ep 1_ Hello.mp3"));
+
+        let no_extension =
+            episode_path(Path::new("/d"), "", &podcast, &episode("x", "http://e.com/x"));
+        assert_eq!(no_extension, Path::new("/d/Rust _ Daily/x.mp3"));
+        assert_eq!(partial_path(&path), Path::new("/downloads/Rust _ Daily/Ep 1_ Hello.mp3.part"));
+    }
+
+    #[test]
+    fn test_long_names_are_cut_at_a_char_boundary() {
+        let podcast = Podcast::new(
+            PodcastURL::new("http://example.com/feed"),
+            "ü".repeat(150),
+            None,
+            None,
+            None,
+            vec![],
+        );
+        let title = format!("A{}", "ö".repeat(300));
+        let path = episode_path(
+            Path::new("/d"),
+            DEFAULT_DOWNLOAD_TEMPLATE,
+            &podcast,
+            &episode(&title, "http://e.com/a.mp3"),
+        );
+        for name in path.iter().skip(2) {
+            assert!(name.len() <= MAX_NAME_BYTES, "{:?} is too long", name);
+        }
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        assert!(file_name.starts_with("Aöö") && file_name.ends_with("ö.mp3"));
+        assert_eq!(path.parent().unwrap().file_name().unwrap().len(), MAX_NAME_BYTES);
+
+        let numbered = numbered_path(&path, 12);
+        let numbered_name = numbered.file_name().unwrap().to_str().unwrap();
+        assert!(numbered_name.len() <= MAX_NAME_BYTES);
+        assert!(numbered_name.ends_with("ö (12).mp3"));
+        assert_eq!(numbered_path(Path::new("/d/Ep. 1"), 2), Path::new("/d/Ep. 1 (2)"));
+    }
+}
@@ -0,0 +1,267 @@
+use crate::errors::StateError;
+use crate::podcast::{EpisodeID, EpisodeKey, PodcastURL};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+// What the user did with an episode. Kept apart from the feed data, which is replaced
+// on every refresh.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EpisodeState {
+    pub starred: bool,
+    pub hidden: bool, // Left out of episode lists unless hidden episodes are shown
+    pub note: Option<String>, // Free text written by the user
+    pub new: bool,    // Not in the feed the last time we saw it; cleared once viewed
+}
+
+impl EpisodeState {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+// Per-episode user state (plus the podcast order), saved as JSON after every change
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EpisodeStateStore {
+    // GUIDs repeat across feeds, so each feed has its own map
+    #[serde(rename = "podcast_episodes")]
+    episodes: HashMap<PodcastURL, HashMap<EpisodeID, EpisodeState>>,
+    // Files written before that keyed by GUID alone. An entry moves to the first feed that
+    // lists its GUID (see `track_new_episodes`) and is kept until then.
+    #[serde(rename = "episodes", skip_serializing_if = "HashMap::is_empty")]
+    unclaimed: HashMap<EpisodeID, EpisodeState>,
+    podcast_order: Vec<PodcastURL>, // Set by moving podcasts around; empty keeps feed order
+    pinned_podcasts: Vec<PodcastURL>, // Listed above the others
+    known_episodes: HashMap<PodcastURL, HashSet<EpisodeID>>, // What each feed listed last time
+    #[serde(skip)]
+    path: Option<PathBuf>, // None keeps the store in memory only (tests, no home dir)
+}
+
+impl EpisodeStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // A missing file is an empty store that will be created on the first change
+    pub fn load(path: &Path) -> Result<Self, StateError> {
+        let mut store = match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|source| StateError::Parse { path: path.to_path_buf(), source })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(source) => return Err(StateError::Io { path: path.to_path_buf(), source }),
+        };
+        store.path = Some(path.to_path_buf());
+        Ok(store)
+    }
+
+    // Keeps changes in memory only, e.g. while another instance owns the file
+    pub fn without_saving(mut self) -> Self {
+        self.path = None;
+        self
+    }
+
+    pub fn save(&self) -> Result<(), StateError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let io_error = |source| StateError::Io { path: path.clone(), source };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|source| StateError::Parse { path: path.clone(), source })?;
+        // Write then rename, so a crash mid-write can't leave half a file behind
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(io_error)?;
+        std::fs::rename(&tmp, path).map_err(io_error)
+    }
+
+    fn find(&self, key: &EpisodeKey) -> Option<&EpisodeState> {
+        self.episodes.get(&key.podcast)?.get(&key.episode)
+    }
+
+    pub fn get(&self, key: &EpisodeKey) -> EpisodeState {
+        self.find(key).cloned().unwrap_or_default()
+    }
+
+    // Applies `change` and drops entries that are back to the defaults, keeping the file small
+    pub fn update(&mut self, key: &EpisodeKey, change: impl FnOnce(&mut EpisodeState)) {
+        let podcast = self.episodes.entry(key.podcast.clone()).or_default();
+        let state = podcast.entry(key.episode.clone()).or_default();
+        change(state);
+        if state.is_default() {
+            podcast.remove(&key.episode);
+            if podcast.is_empty() {
+                self.episodes.remove(&key.podcast);
+            }
+        }
+    }
+
+    pub fn is_starred(&self, key: &EpisodeKey) -> bool {
+        self.find(key).is_some_and(|s| s.starred)
+    }
+
+    pub fn is_hidden(&self, key: &EpisodeKey) -> bool {
+        self.find(key).is_some_and(|s| s.hidden)
+    }
+
+    pub fn note(&self, key: &EpisodeKey) -> Option<&str> {
+        self.find(key).and_then(|s| s.note.as_deref())
+    }
+
+    pub fn is_new(&self, key: &EpisodeKey) -> bool {
+        self.find(key).is_some_and(|s| s.new)
+    }
+
+    // Remembers which episodes `podcast` lists now and flags the ones it didn't list last
+    // time as new. A feed seen for the first time has nothing new. Also claims the feed's
+    // entries from old state files. False if nothing changed, so there's nothing to save.
+    pub fn track_new_episodes<'a>(
+        &mut self,
+        podcast: &PodcastURL,
+        episodes: impl IntoIterator<Item = &'a EpisodeID>,
+    ) -> bool {
+        let current: HashSet<EpisodeID> = episodes.into_iter().cloned().collect();
+        let claimed = self.claim(podcast, &current);
+        let fresh: Vec<EpisodeID> = match self.known_episodes.get(podcast) {
+            Some(known) if known == &current => return claimed,
+            Some(known) => current.difference(known).cloned().collect(),
+            None => Vec::new(),
+        };
+        for episode in &fresh {
+            self.update(&EpisodeKey::new(podcast, episode), |s| s.new = true);
+        }
+        self.known_episodes.insert(podcast.clone(), current);
+        true
+    }
+
+    // Moves GUID-only entries of the episodes `podcast` lists under that podcast
+    fn claim(&mut self, podcast: &PodcastURL, episodes: &HashSet<EpisodeID>) -> bool {
+        let mut claimed = false;
+        for episode in episodes {
+            if let Some(state) = self.unclaimed.remove(episode) {
+                self.episodes.entry(podcast.clone()).or_default().insert(episode.clone(), state);
+                claimed = true;
+            }
+        }
+        claimed
+    }
+
+    pub fn has_starred(&self) -> bool {
+        self.episodes.values().flat_map(|p| p.values()).any(|s| s.starred)
+    }
+
+    pub fn podcast_order(&self) -> &[PodcastURL] {
+        &self.podcast_order
+    }
+
+    pub fn set_podcast_order(&mut self, order: Vec<PodcastURL>) {
+        self.podcast_order = order;
+    }
+
+    pub fn is_pinned(&self, podcast: &PodcastURL) -> bool {
+        self.pinned_podcasts.contains(podcast)
+    }
+
+    pub fn toggle_pinned(&mut self, podcast: &PodcastURL) {
+        if self.is_pinned(podcast) {
+            self.pinned_podcasts.retain(|url| url != podcast);
+        } else {
+            self.pinned_podcasts.push(podcast.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(episode: &str) -> EpisodeKey {
+        EpisodeKey::new(&PodcastURL::new("http://example.com/feed"), &EpisodeID::new(episode))
+    }
+
+    #[test]
+    fn test_state_round_trips_through_file() {
+        let path = std::env::temp_dir()
+            .join(format!("rustero-state-{}", std::process::id()))
+            .join("state.json");
+        let mut store = EpisodeStateStore::load(&path).unwrap();
+        let ep = key("ep1");
+        store.update(&ep, |s| s.starred = true);
+        store.set_podcast_order(vec![PodcastURL::new("http://example.com/b")]);
+        store.save().unwrap();
+
+        let loaded = EpisodeStateStore::load(&path).unwrap();
+        assert_eq!(loaded.podcast_order(), [PodcastURL::new("http://example.com/b")]);
+        assert!(loaded.is_starred(&ep));
+        assert!(loaded.has_starred());
+        assert!(!loaded.is_starred(&key("ep2")));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_default_entries_are_dropped() {
+        let mut store = EpisodeStateStore::new();
+        let ep = key("ep1");
+        store.update(&ep, |s| s.starred = true);
+        store.update(&ep, |s| s.starred = false);
+        assert!(store.episodes.is_empty());
+        assert!(store.save().is_ok()); // No path, nothing written
+    }
+
+    #[test]
+    fn test_episodes_missing_last_time_are_new() {
+        let mut store = EpisodeStateStore::new();
+        let feed = PodcastURL::new("http://example.com/feed");
+        let (ep1, ep2) = (EpisodeID::new("ep1"), EpisodeID::new("ep2"));
+        assert!(store.track_new_episodes(&feed, [&ep1]));
+        assert!(!store.is_new(&key("ep1")));
+        assert!(!store.track_new_episodes(&feed, [&ep1]));
+
+        assert!(store.track_new_episodes(&feed, [&ep2, &ep1]));
+        assert!(store.is_new(&key("ep2")));
+        assert!(!store.is_new(&key("ep1")));
+    }
+
+    #[test]
+    fn test_same_guid_in_two_feeds_keeps_apart() {
+        let mut store = EpisodeStateStore::new();
+        let other =
+            EpisodeKey::new(&PodcastURL::new("http://example.com/other"), &key("1").episode);
+        store.update(&key("1"), |s| s.starred = true);
+        assert!(store.is_starred(&key("1")));
+        assert!(!store.is_starred(&other));
+    }
+
+    #[test]
+    fn test_old_state_files_are_claimed_by_feed() {
+        let path = std::env::temp_dir()
+            .join(format!("rustero-old-state-{}", std::process::id()))
+            .join("state.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            r#"{"episodes": {"ep1": {"starred": true}, "ep9": {"hidden": true}}}"#,
+        )
+        .unwrap();
+        let mut store = EpisodeStateStore::load(&path).unwrap();
+        assert!(!store.is_starred(&key("ep1")));
+
+        let feed = PodcastURL::new("http://example.com/feed");
+        assert!(store.track_new_episodes(&feed, [&EpisodeID::new("ep1")]));
+        assert!(store.is_starred(&key("ep1")));
+        store.save().unwrap();
+
+        // ep9's feed hasn't shown up yet, so it stays in the old format until it does
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("podcast_episodes") && saved.contains("ep9"));
+        let mut loaded = EpisodeStateStore::load(&path).unwrap();
+        assert!(loaded.is_starred(&key("ep1")));
+        loaded.track_new_episodes(&feed, [&EpisodeID::new("ep1"), &EpisodeID::new("ep9")]);
+        assert!(loaded.is_hidden(&key("ep9")));
+        assert!(loaded.unclaimed.is_empty());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
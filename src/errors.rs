@@ -25,8 +25,14 @@ pub enum DownloaderError {
     NetworkError(#[from] reqwest::Error), // For fetcher.fetch if it uses reqwest directly
     #[error("RSS parsing error: {0}")]
     RssError(#[from] rss::Error), // For rss::Channel::read_from
+    #[error("Atom parsing error: {0}")]
+    AtomError(#[from] atom_syndication::Error),
+    #[error("JSON Feed parsing error: {0}")]
+    JsonFeedError(#[from] serde_json::Error),
     #[error("Download failed: {0}")]
     Failed(String),
+    #[error("Response is not an XML feed: {0}")]
+    NotXml(String), // e.g. an HTML error or login page served with 200
 }
 
 #[derive(Error, Debug)]
@@ -54,3 +60,127 @@ pub enum PipelineError {
     #[error("An earlier step in the pipeline failed: {0}")] // {0} will display source
     UpstreamError(#[from] Box<PipelineError>),
 }
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read config file {path}: {source}")]
+    Io {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Invalid config file {path}: {source}")]
+    Parse {
+        path: std::path::PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Invalid profile name '{0}' (use letters, digits, '-' and '_')")]
+    InvalidProfile(String),
+    #[error("{0} expects a value")]
+    MissingArgument(&'static str),
+    #[error("Invalid value '{1}' for {0}")]
+    InvalidArgument(&'static str, String),
+}
+
+#[derive(Error, Debug)]
+pub enum LogError {
+    #[error("Cannot open log file {path}: {source}")]
+    Io {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Invalid log level '{0}' (use off, error, warn, info, debug or trace)")]
+    InvalidLevel(String),
+    #[error("The logger was already set up")]
+    AlreadyInitialized,
+}
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error("Backup failed at {path}: {source}")]
+    Io {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum TrashError {
+    #[error("Trash I/O error at {path}: {source}")]
+    Io {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Invalid podcast file in trash {path}: {source}")]
+    Parse {
+        path: std::path::PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum OpmlError {
+    #[error("Failed to read OPML file {path}: {source}")]
+    Io {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Invalid OPML: {0}")]
+    Parse(String),
+}
+
+#[derive(Error, Debug)]
+pub enum SearchError {
+    #[error("Search request failed: {0}")]
+    Fetch(#[from] DownloaderError),
+    #[error("Unexpected search response: {0}")]
+    InvalidResponse(String),
+}
+
+#[derive(Error, Debug)]
+pub enum EpisodeDownloadError {
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("Server answered {0}")]
+    Status(reqwest::StatusCode),
+    #[error("Failed to write {path}: {source}")]
+    Io {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum DataLockError {
+    #[error("Another rustero (pid {pid}) is using {path}")]
+    Held { path: std::path::PathBuf, pid: String },
+    #[error("Failed to lock {path}: {source}")]
+    Io {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum StateError {
+    #[error("Failed to access state file {path}: {source}")]
+    Io {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Invalid state file {path}: {source}")]
+    Parse {
+        path: std::path::PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+}
@@ -0,0 +1,23 @@
+use crate::metrics::FeedTimings;
+use crate::podcast::{EpisodeKey, Podcast, PodcastURL};
+use crate::tasks::{TaskId, TaskKind};
+use std::path::PathBuf;
+
+// Results of background work, sent to the UI thread and applied in `App::handle_event`
+#[derive(Debug)]
+pub enum AppEvent {
+    PodcastSubscribed(Podcast),
+    SubscriptionFailed { url: PodcastURL, error: String },
+    PodcastRefreshed(Podcast),
+    RefreshFailed { url: PodcastURL, error: String },
+    FeedTimed { url: PodcastURL, timings: FeedTimings }, // Sent before the feed's result
+    SubscribeRequested(PodcastURL), // A link forwarded by `rustero add` from another process
+    EpisodeDownloaded { episode: EpisodeKey, path: PathBuf },
+    EpisodeDownloadFailed { episode: EpisodeKey, error: String },
+    EnclosureForbidden { episode: EpisodeKey, error: String }, // 403, maybe an expired link
+    EpisodeDownloadIncomplete { episode: EpisodeKey, path: PathBuf, reason: String },
+    // Lifecycle of every background task, driving the activity indicator
+    TaskStarted { id: TaskId, kind: TaskKind, label: String },
+    TaskProgress { id: TaskId, done: u64, total: Option<u64> },
+    TaskFinished { id: TaskId, error: Option<String> },
+}
@@ -0,0 +1,256 @@
+// Atom and JSON Feed are turned into an `rss::Channel`, so `PodcastFactory` only ever has
+// to understand one shape of feed
+use crate::errors::DownloaderError;
+use crate::podcast_download::{FeedFormat, verify_xml};
+use rss::extension::itunes::{ITunesChannelExtension, ITunesItemExtension};
+use rss::{Category, Channel, Enclosure, Guid, Image, Item};
+use serde::Deserialize;
+
+pub fn parse_feed(content: &str, format: FeedFormat) -> Result<Channel, DownloaderError> {
+    match format {
+        FeedFormat::Rss => {
+            verify_xml(content)?;
+            Ok(Channel::read_from(content.as_bytes())?)
+        }
+        FeedFormat::Atom => {
+            verify_xml(content)?;
+            Ok(atom_to_channel(&atom_syndication::Feed::read_from(content.as_bytes())?))
+        }
+        FeedFormat::JsonFeed => Ok(json_feed_to_channel(serde_json::from_str(content)?)),
+    }
+}
+
+// ===== Atom
+fn alternate_link(links: &[atom_syndication::Link]) -> Option<String> {
+    links.iter().find(|l| l.rel() == "alternate").map(|l| l.href().to_string())
+}
+
+fn atom_to_channel(feed: &atom_syndication::Feed) -> Channel {
+    let items = feed
+        .entries()
+        .iter()
+        .map(|entry| {
+            let enclosure =
+                entry.links().iter().find(|l| l.rel() == "enclosure").map(|l| Enclosure {
+                    url: l.href().to_string(),
+                    length: l.length().unwrap_or_default().to_string(),
+                    mime_type: l.mime_type().unwrap_or_default().to_string(),
+                });
+            let published = entry.published().unwrap_or(entry.updated());
+            Item {
+                title: Some(entry.title().value.clone()),
+                link: alternate_link(entry.links()),
+                description: entry.summary().map(|s| s.value.clone()),
+                content: entry.content().and_then(|c| c.value()).map(String::from),
+                guid: Some(Guid { value: entry.id().to_string(), permalink: false }),
+                pub_date: Some(published.to_rfc2822()),
+                enclosure,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Channel {
+        title: feed.title().value.clone(),
+        link: alternate_link(feed.links()).unwrap_or_default(),
+        description: feed.subtitle().map(|s| s.value.clone()).unwrap_or_default(),
+        managing_editor: feed.authors().first().map(|a| a.name().to_string()),
+        language: feed.lang().map(String::from),
+        image: feed
+            .logo()
+            .or(feed.icon())
+            .map(|url| Image { url: url.to_string(), ..Default::default() }),
+        categories: feed
+            .categories()
+            .iter()
+            .map(|c| Category {
+                name: c.label().unwrap_or(c.term()).to_string(),
+                ..Default::default()
+            })
+            .collect(),
+        items,
+        ..Default::default()
+    }
+}
+
+// ===== JSON Feed (https://jsonfeed.org/version/1.1, 1.0's single `author` included)
+#[derive(Debug, Deserialize)]
+struct JsonFeed {
+    title: String,
+    home_page_url: Option<String>,
+    description: Option<String>,
+    icon: Option<String>,
+    language: Option<String>,
+    #[serde(default)]
+    authors: Vec<JsonAuthor>,
+    author: Option<JsonAuthor>,
+    #[serde(default)]
+    items: Vec<JsonItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonAuthor {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonItem {
+    id: serde_json::Value, // A string by the spec, but numbers are common
+    url: Option<String>,
+    title: Option<String>,
+    content_html: Option<String>,
+    content_text: Option<String>,
+    summary: Option<String>,
+    image: Option<String>,
+    date_published: Option<String>,
+    #[serde(default)]
+    attachments: Vec<JsonAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonAttachment {
+    url: String,
+    mime_type: String,
+    size_in_bytes: Option<u64>,
+    duration_in_seconds: Option<f64>,
+}
+
+fn json_feed_to_channel(feed: JsonFeed) -> Channel {
+    let items = feed
+        .items
+        .into_iter()
+        .map(|item| {
+            // Several attachments are usually the same episode in different formats
+            let attachment = item
+                .attachments
+                .iter()
+                .find(|a| a.mime_type.starts_with("audio/") || a.mime_type.starts_with("video/"))
+                .or(item.attachments.first());
+            let id = match item.id {
+                serde_json::Value::String(id) => id,
+                other => other.to_string(),
+            };
+            Item {
+                title: item.title,
+                link: item.url,
+                description: item.content_html.or(item.content_text).or(item.summary),
+                guid: Some(Guid { value: id, permalink: false }),
+                pub_date: item.date_published, // RFC 3339, which parse_pub_date accepts
+                enclosure: attachment.map(|a| Enclosure {
+                    url: a.url.clone(),
+                    length: a.size_in_bytes.map(|s| s.to_string()).unwrap_or_default(),
+                    mime_type: a.mime_type.clone(),
+                }),
+                itunes_ext: Some(ITunesItemExtension {
+                    duration: attachment
+                        .and_then(|a| a.duration_in_seconds)
+                        .map(|secs| (secs.round() as u64).to_string()),
+                    image: item.image,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let author = feed.authors.into_iter().chain(feed.author).find_map(|a| a.name);
+    Channel {
+        title: feed.title,
+        link: feed.home_page_url.unwrap_or_default(),
+        description: feed.description.unwrap_or_default(),
+        language: feed.language,
+        image: feed.icon.map(|url| Image { url, ..Default::default() }),
+        itunes_ext: Some(ITunesChannelExtension { author, ..Default::default() }),
+        items,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::podcast_factory::{ParsedFeed, PodcastFactory};
+
+    fn create(content: &str, format: FeedFormat) -> crate::podcast::Podcast {
+        let channel = parse_feed(content, format).unwrap();
+        PodcastFactory::new()
+            .create_podcast(ParsedFeed { channel }, "http://example.com/feed".to_string())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_atom_feed() {
+        let atom = r#"<?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">
+              <title>Atom Show</title>
+              <subtitle>Talk</subtitle>
+              <link rel="alternate" href="http://example.com/"/>
+              <id>urn:show</id>
+              <updated>2024-03-02T10:00:00Z</updated>
+              <author><name>Ann</name></author>
+              <entry>
+                <title>Pilot</title>
+                <id>urn:ep1</id>
+                <updated>2024-03-02T10:00:00Z</updated>
+                <published>2024-03-01T10:00:00+01:00</published>
+                <link rel="alternate" href="http://example.com/ep1"/>
+                <link rel="enclosure" type="audio/mpeg" length="1234" href="http://example.com/ep1.mp3"/>
+                <summary>The first one</summary>
+              </entry>
+            </feed>"#;
+        let podcast = create(atom, FeedFormat::Atom);
+        assert_eq!(podcast.title(), "Atom Show");
+        assert_eq!(podcast.author(), Some("Ann"));
+        assert_eq!(podcast.language(), Some("en"));
+        let episode = &podcast.episodes()[0];
+        assert_eq!(episode.title(), "Pilot");
+        assert_eq!(episode.audio_url(), "http://example.com/ep1.mp3");
+        assert_eq!(episode.size_in_bytes(), Some(1234));
+        assert_eq!(episode.link(), Some("http://example.com/ep1"));
+        assert_eq!(episode.published_date().unwrap().to_rfc3339(), "2024-03-01T09:00:00+00:00");
+    }
+
+    #[test]
+    fn test_json_feed() {
+        let json = r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "JSON Show",
+            "author": {"name": "Bo"},
+            "items": [
+                {
+                    "id": 7,
+                    "title": "Episode seven",
+                    "content_html": "<p>Notes</p>",
+                    "date_published": "2024-03-01T10:00:00Z",
+                    "attachments": [
+                        {"url": "http://example.com/7.txt", "mime_type": "text/plain"},
+                        {"url": "http://example.com/7.m4a", "mime_type": "audio/x-m4a",
+                         "size_in_bytes": 99, "duration_in_seconds": 1800}
+                    ]
+                },
+                {"id": "no-media", "title": "Blog post"}
+            ]
+        }"#;
+        let podcast = create(json, FeedFormat::JsonFeed);
+        assert_eq!(podcast.title(), "JSON Show");
+        assert_eq!(podcast.author(), Some("Bo"));
+        assert_eq!(podcast.episodes().len(), 1);
+        let episode = &podcast.episodes()[0];
+        assert_eq!(episode.id().to_string(), "7");
+        assert_eq!(episode.audio_url(), "http://example.com/7.m4a");
+        assert_eq!(episode.duration(), Some("1800"));
+        assert_eq!(episode.description(), Some("<p>Notes</p>"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse_feed("{\"title\": 1}", FeedFormat::JsonFeed),
+            Err(DownloaderError::JsonFeedError(_))
+        ));
+        assert!(matches!(
+            parse_feed("<html></html>", FeedFormat::Atom),
+            Err(DownloaderError::NotXml(_))
+        ));
+    }
+}
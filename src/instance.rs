@@ -0,0 +1,159 @@
+// One TUI per profile: `rustero add <link>` (or a podcast:// link handed over by the
+// desktop) passes the feed to an already running instance through a Unix socket in the
+// data directory instead of starting a second UI.
+use crate::events::AppEvent;
+use crate::podcast::PodcastURL;
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc::UnboundedSender;
+
+// Schemes podcast directories use to hand a feed to "the podcast app"
+const DEEP_LINK_SCHEMES: &[&str] = &["podcast", "pcast", "itpc", "feed"];
+
+// The feed URL behind a deep link: "podcast://host/feed.xml" becomes http://host/feed.xml
+// (servers redirect to https themselves), "feed:https://host/feed.xml" unwraps to the
+// inner URL, and plain http(s) URLs are taken as they are
+pub fn deep_link_url(link: &str) -> Option<PodcastURL> {
+    let link = link.trim();
+    let (scheme, rest) = link.split_once(':')?;
+    let scheme = scheme.to_ascii_lowercase();
+    let url = if scheme == "http" || scheme == "https" {
+        link.to_string()
+    } else if DEEP_LINK_SCHEMES.contains(&scheme.as_str()) {
+        let rest = rest.trim_start_matches('/');
+        if rest.starts_with("http://") || rest.starts_with("https://") {
+            rest.to_string()
+        } else {
+            format!("http://{}", rest)
+        }
+    } else {
+        return None;
+    };
+    let parsed = reqwest::Url::parse(&url).ok()?;
+    parsed.host_str().is_some().then(|| PodcastURL::new(&url))
+}
+
+pub fn socket_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("rustero.sock")
+}
+
+// Hands `url` to the instance listening on `socket`. Ok(false) when none is running.
+#[cfg(unix)]
+pub fn forward_to_running_instance(socket: &Path, url: &PodcastURL) -> io::Result<bool> {
+    use std::io::Write;
+    match std::os::unix::net::UnixStream::connect(socket) {
+        Ok(mut stream) => {
+            writeln!(stream, "{}", url)?;
+            Ok(true)
+        }
+        Err(e)
+            if matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused) =>
+        {
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn forward_to_running_instance(_socket: &Path, _url: &PodcastURL) -> io::Result<bool> {
+    Ok(false)
+}
+
+// Removes the socket file when the listening instance exits
+#[derive(Debug)]
+pub struct InstanceSocket {
+    path: PathBuf,
+}
+
+impl Drop for InstanceSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+// Accepts forwarded URLs on `socket` in the background; each arrives as a
+// SubscribeRequested event. Leaves a socket that another instance still answers on alone.
+#[cfg(unix)]
+pub fn listen(socket: &Path, tx: UnboundedSender<AppEvent>) -> io::Result<InstanceSocket> {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    if std::os::unix::net::UnixStream::connect(socket).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("another rustero is listening on {}", socket.display()),
+        ));
+    }
+    // Left behind by an instance that didn't exit cleanly
+    let _ = std::fs::remove_file(socket);
+    if let Some(dir) = socket.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let listener = tokio::net::UnixListener::bind(socket)?;
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stream).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    match deep_link_url(&line) {
+                        Some(url) => {
+                            let _ = tx.send(AppEvent::SubscribeRequested(url));
+                        }
+                        None => log::warn!("Ignoring forwarded link {:?}", line),
+                    }
+                }
+            });
+        }
+    });
+    Ok(InstanceSocket { path: socket.to_path_buf() })
+}
+
+#[cfg(not(unix))]
+pub fn listen(_socket: &Path, _tx: UnboundedSender<AppEvent>) -> io::Result<InstanceSocket> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "single-instance activation needs Unix sockets"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deep_link_url() {
+        let url = |link| deep_link_url(link).map(|u| u.to_string());
+        assert_eq!(
+            url("podcast://example.com/feed.xml").as_deref(),
+            Some("http://example.com/feed.xml")
+        );
+        assert_eq!(url("itpc://example.com/rss").as_deref(), Some("http://example.com/rss"));
+        assert_eq!(url("feed:https://example.com/rss").as_deref(), Some("https://example.com/rss"));
+        assert_eq!(
+            url("pcast://https://example.com/rss").as_deref(),
+            Some("https://example.com/rss")
+        );
+        assert_eq!(url(" https://example.com/rss ").as_deref(), Some("https://example.com/rss"));
+        assert_eq!(url("mailto:someone@example.com"), None);
+        assert_eq!(url("podcast://"), None);
+        assert_eq!(url("example.com/rss"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_forwarding_to_a_running_instance() {
+        let dir = std::env::temp_dir().join(format!("rustero-instance-{}", std::process::id()));
+        let socket = socket_path(&dir);
+        let url = PodcastURL::new("http://example.com/feed.xml");
+        assert!(!forward_to_running_instance(&socket, &url).unwrap());
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let guard = listen(&socket, tx.clone()).unwrap();
+        assert!(listen(&socket, tx).is_err(), "the socket is taken");
+        assert!(forward_to_running_instance(&socket, &url).unwrap());
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await;
+        assert!(matches!(event, Ok(Some(AppEvent::SubscribeRequested(u))) if u == url));
+
+        drop(guard);
+        assert!(!socket.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,143 @@
+// Every key binding in one table, so the help popup and the hint bar can't drift apart
+// from each other. The handlers themselves live in `App::on_key` and friends.
+use crate::app::FocusedPanel;
+use FocusedPanel::{Episodes, Podcasts, ShowNotes};
+use KeyScope::*;
+
+// Where a binding does something
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyScope {
+    Global,
+    Panel(FocusedPanel),
+    Typing, // A ':' command, show notes search or episode filter is being typed
+    Welcome,
+    Confirm,
+    FilePicker,
+    ImportSelection,
+    Tasks,
+    Note,
+    Help,
+    ReadOnlyPopup, // Podcast info, reports
+}
+
+#[derive(Debug)]
+pub struct KeyBinding {
+    pub keys: &'static str,
+    pub action: &'static str,       // As listed in the help popup
+    pub hint: Option<&'static str>, // Short form for the hint bar; None leaves it out
+    pub scope: KeyScope,
+}
+
+const fn bind(
+    keys: &'static str,
+    action: &'static str,
+    hint: Option<&'static str>,
+    scope: KeyScope,
+) -> KeyBinding {
+    KeyBinding { keys, action, hint, scope }
+}
+
+pub const KEYMAP: &[KeyBinding] = &[
+    bind("Tab / S-Tab", "Switch panel", Some("panel"), Global),
+    bind("Up / Down", "Move selection", None, Global),
+    bind("PgUp / PgDn", "Scroll a page", None, Global),
+    bind("Home / End", "Jump to first / last", None, Global),
+    bind("f", "Show notes fullscreen", None, Global),
+    bind("d", "Cycle episode date filter", None, Global),
+    bind("m", "Toggle compact player", None, Global),
+    bind("H", "Show / hide hidden episodes", None, Global),
+    bind("E", "Hide / show explicit episodes", None, Global),
+    bind("t", "Background tasks", None, Global),
+    bind(
+        ":",
+        "Command line (:add, :import, :lang, :category, :restore, :q)",
+        Some("command"),
+        Global,
+    ),
+    bind("(paste)", "Subscribe to a pasted feed URL", None, Global),
+    bind("?", "This help", Some("help"), Global),
+    bind("q", "Quit", Some("quit"), Global),
+    bind("a", "Add a feed by URL", Some("add"), Panel(Podcasts)),
+    bind("i", "Podcast info", Some("info"), Panel(Podcasts)),
+    bind("p", "Pin podcast to the top", Some("pin"), Panel(Podcasts)),
+    bind("S-Up / S-Down", "Move podcast up / down", Some("move"), Panel(Podcasts)),
+    bind("D", "Download the new episodes", Some("download new"), Panel(Podcasts)),
+    bind("x / Del", "Unsubscribe", Some("unsubscribe"), Panel(Podcasts)),
+    bind("D / c", "Download / cancel download", Some("download"), Panel(Episodes)),
+    bind("s", "Star episode", Some("star"), Panel(Episodes)),
+    bind("h", "Hide episode", Some("hide"), Panel(Episodes)),
+    bind("a", "Write a note on the episode", Some("note"), Panel(Episodes)),
+    bind("y", "Copy a share text for the episode", Some("share"), Panel(Episodes)),
+    bind("/", "Filter episodes by title / description / note", Some("filter"), Panel(Episodes)),
+    bind("/  n  N", "Search show notes, next / previous match", Some("search"), Panel(ShowNotes)),
+    bind("Enter", "Confirm", Some("confirm"), Typing),
+    bind("Esc", "Cancel", Some("cancel"), Typing),
+    bind("Up / Down", "Earlier input", Some("history"), Typing),
+    bind("i", "Import an OPML file or URL list", Some("import"), Welcome),
+    bind("a", "Add a feed by URL", Some("add"), Welcome),
+    bind("Esc", "Skip to the empty library", Some("skip"), Welcome),
+    bind("q", "Quit", Some("quit"), Welcome),
+    bind("y / n", "Yes / no", Some("yes / no"), Confirm),
+    bind("Enter", "Choose the highlighted button", Some("choose"), Confirm),
+    bind("Enter / Right", "Open", Some("open"), FilePicker),
+    bind("Backspace / Left", "Parent directory", Some("up"), FilePicker),
+    bind(".", "Show / hide hidden files", Some("hidden files"), FilePicker),
+    bind("Esc", "Cancel", Some("cancel"), FilePicker),
+    bind("Space", "Tick / untick the feed", Some("tick"), ImportSelection),
+    bind("a", "Tick / untick all", Some("all"), ImportSelection),
+    bind("Enter", "Import the ticked feeds", Some("import"), ImportSelection),
+    bind("Esc", "Cancel", Some("cancel"), ImportSelection),
+    bind("+ / -", "Move a queued download", Some("reorder"), Tasks),
+    bind("c / x / Del", "Cancel the task", Some("cancel"), Tasks),
+    bind("Esc / q / t", "Close", Some("close"), Tasks),
+    bind("Enter", "Save the note", Some("save"), Note),
+    bind("Esc", "Discard changes", Some("cancel"), Note),
+    bind("↑ ↓ / PgUp PgDn", "Scroll", Some("scroll"), Help),
+    bind("any other key", "Close", Some("close"), Help),
+    bind("any key", "Close", Some("close"), ReadOnlyPopup),
+];
+
+// What the help popup lists: everything outside popups and text input
+pub fn help_bindings() -> impl Iterator<Item = &'static KeyBinding> {
+    KEYMAP.iter().filter(|b| matches!(b.scope, Global | Panel(_)))
+}
+
+// "D / c download · s star · ..." for `scope`; a panel's own keys come before the global ones
+pub fn hint_line(scope: KeyScope) -> String {
+    let in_scope = KEYMAP.iter().filter(|b| b.scope == scope);
+    let global = KEYMAP.iter().filter(|b| matches!(scope, Panel(_)) && b.scope == Global);
+    in_scope
+        .chain(global)
+        .filter_map(|b| b.hint.map(|hint| format!("{} {}", b.keys, hint)))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+impl KeyScope {
+    // Shown after the action in the help popup
+    pub fn help_suffix(&self) -> &'static str {
+        match self {
+            Panel(Podcasts) => " (Podcasts panel)",
+            Panel(Episodes) => " (Episodes panel)",
+            Panel(ShowNotes) => " (Show Notes panel)",
+            _ => "",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hint_line_follows_the_scope() {
+        let episodes = hint_line(Panel(Episodes));
+        assert!(episodes.starts_with("D / c download · s star"));
+        assert!(episodes.ends_with(": command · ? help · q quit"));
+        assert!(!episodes.contains("unsubscribe"));
+
+        assert_eq!(hint_line(Note), "Enter save · Esc cancel");
+        assert!(!hint_line(Typing).contains("quit"));
+        assert!(help_bindings().all(|b| !matches!(b.scope, Typing | Note)));
+    }
+}
@@ -1,9 +1,32 @@
 // src/lib.rs
 pub mod app;
-mod errors;
+pub mod backup;
+pub mod cli;
+pub mod config;
+pub mod data_lock;
+pub mod episode_download;
+pub mod episode_state;
+pub mod errors;
+pub mod events;
+pub mod feed_formats;
+pub mod instance;
+pub mod keymap;
+pub mod logging;
+pub mod metrics;
+pub mod opml;
 pub mod podcast;
 pub mod podcast_download;
 pub mod podcast_factory;
+pub mod refresh;
+pub mod search;
+pub mod share;
+pub mod show_notes;
+pub mod startup;
+pub mod tasks;
+pub mod theme;
+pub mod trash;
 pub mod ui;
+pub mod validate;
+pub mod widgets;
 
 pub mod commands; // Add this line
@@ -0,0 +1,286 @@
+use crate::errors::LogError;
+use log::kv::{Key, Value, VisitSource};
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Which records get through: a default level plus overrides for module prefixes, written
+// like "info,rustero::refresh=debug,reqwest=warn"
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>, // Longest prefix first
+}
+
+impl LogFilter {
+    pub fn parse(spec: &str) -> Result<Self, LogError> {
+        let level = |s: &str| {
+            s.trim()
+                .parse::<LevelFilter>()
+                .map_err(|_| LogError::InvalidLevel(s.trim().to_string()))
+        };
+        let mut filter = LogFilter { default: LevelFilter::Info, modules: Vec::new() };
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.split_once('=') {
+                Some((module, lvl)) => {
+                    filter.modules.push((module.trim().to_string(), level(lvl)?))
+                }
+                None => filter.default = level(part)?,
+            }
+        }
+        filter.modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        Ok(filter)
+    }
+
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .find(|(module, _)| {
+                target
+                    .strip_prefix(module.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    // -v / -vv: makes the default level at least as verbose as `level`. Modules that were
+    // quietened explicitly stay that way.
+    pub fn with_verbosity(mut self, level: LevelFilter) -> Self {
+        self.default = self.default.max(level);
+        self
+    }
+
+    // The most verbose level anything is logged at, for `log::set_max_level`
+    pub fn max_level(&self) -> LevelFilter {
+        self.modules.iter().map(|(_, level)| *level).fold(self.default, |a, b| a.max(b))
+    }
+}
+
+// A log file that is moved to "<name>.1" (replacing the previous one) once it grows past
+// `max_bytes`, so at most two files' worth of logs are kept
+struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    file: File,
+    written: u64,
+}
+
+impl RotatingFile {
+    fn open(path: &Path, max_bytes: u64) -> Result<Self, LogError> {
+        let io_error = |source| LogError::Io { path: path.to_path_buf(), source };
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path).map_err(io_error)?;
+        let written = file.metadata().map_err(io_error)?.len();
+        Ok(Self { path: path.to_path_buf(), max_bytes, file, written })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.max_bytes > 0
+            && self.written > 0
+            && self.written + line.len() as u64 > self.max_bytes
+        {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(".1");
+        std::fs::rename(&self.path, rotated)?;
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+// How each record is written: plain lines for reading, or one JSON object per line for
+// log collectors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    fn format(&self, record: &Record) -> String {
+        let now = chrono::Local::now();
+        let mut fields = Fields::default();
+        let _ = record.key_values().visit(&mut fields);
+        match self {
+            LogFormat::Text => {
+                let fields: String = fields
+                    .0
+                    .iter()
+                    .map(|(key, value)| match value {
+                        serde_json::Value::String(s) => format!(" {}={}", key, s),
+                        value => format!(" {}={}", key, value),
+                    })
+                    .collect();
+                format!(
+                    "{} {:<5} {}: {}{}\n",
+                    now.format("%Y-%m-%d %H:%M:%S%.3f"),
+                    record.level(),
+                    record.target(),
+                    record.args(),
+                    fields
+                )
+            }
+            LogFormat::Json => {
+                let mut json = serde_json::json!({
+                    "time": now.to_rfc3339(),
+                    "level": record.level().as_str(),
+                    "target": record.target(),
+                    "message": record.args().to_string(),
+                });
+                // Structured fields such as `feed` and `step` sit next to the message, so
+                // collectors can filter on them
+                if let Some(object) = json.as_object_mut() {
+                    for (key, value) in fields.0 {
+                        object.entry(key).or_insert(value);
+                    }
+                }
+                format!("{}\n", json)
+            }
+        }
+    }
+}
+
+// Key-value pairs of a record, e.g. `log::info!(feed = url.as_str(); "...")`
+#[derive(Default)]
+struct Fields(Vec<(String, serde_json::Value)>);
+
+impl<'kvs> VisitSource<'kvs> for Fields {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), log::kv::Error> {
+        let value = if let Some(b) = value.to_bool() {
+            b.into()
+        } else if let Some(n) = value.to_i64() {
+            n.into()
+        } else if let Some(n) = value.to_f64() {
+            n.into()
+        } else {
+            value.to_string().into()
+        };
+        self.0.push((key.to_string(), value));
+        Ok(())
+    }
+}
+
+struct FileLogger {
+    filter: LogFilter,
+    format: LogFormat,
+    file: Mutex<RotatingFile>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = self.format.format(record);
+        // Logging must never take the app down; a failed write is dropped
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_line(&line);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+// Sends `log` records to a file. The terminal belongs to the TUI, so nothing is printed.
+pub fn init(
+    path: &Path,
+    filter: LogFilter,
+    format: LogFormat,
+    max_bytes: u64,
+) -> Result<(), LogError> {
+    let file = RotatingFile::open(path, max_bytes)?;
+    let max_level = filter.max_level();
+    // The logger lives for the rest of the process anyway
+    let logger = Box::leak(Box::new(FileLogger { filter, format, file: Mutex::new(file) }));
+    log::set_logger(logger).map_err(|_| LogError::AlreadyInitialized)?;
+    log::set_max_level(max_level);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_matches_module_prefixes() {
+        let filter = LogFilter::parse("warn, rustero=info, rustero::refresh=debug").unwrap();
+        assert_eq!(filter.level_for("reqwest::connect"), LevelFilter::Warn);
+        assert_eq!(filter.level_for("rustero::app"), LevelFilter::Info);
+        assert_eq!(filter.level_for("rustero::refresh"), LevelFilter::Debug);
+        assert_eq!(filter.level_for("rusterox"), LevelFilter::Warn);
+        assert_eq!(filter.max_level(), LevelFilter::Debug);
+        assert!(LogFilter::parse("loud").is_err());
+
+        let verbose = filter.with_verbosity(LevelFilter::Trace);
+        assert_eq!(verbose.level_for("reqwest::connect"), LevelFilter::Trace);
+        assert_eq!(verbose.level_for("rustero::app"), LevelFilter::Info);
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let record = Record::builder()
+            .level(log::Level::Warn)
+            .target("rustero::refresh")
+            .args(format_args!("feed \"x\" failed"))
+            .build();
+        let line = LogFormat::Json.format(&record);
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["level"], "WARN");
+        assert_eq!(json["target"], "rustero::refresh");
+        assert_eq!(json["message"], "feed \"x\" failed");
+    }
+
+    #[test]
+    fn test_structured_fields() {
+        let fields = [("feed", "http://example.com/feed"), ("step", "download")];
+        let record = Record::builder()
+            .level(log::Level::Info)
+            .target("rustero::commands")
+            .args(format_args!("fetched"))
+            .key_values(&fields)
+            .build();
+        let json: serde_json::Value =
+            serde_json::from_str(&LogFormat::Json.format(&record)).unwrap();
+        assert_eq!(json["feed"], "http://example.com/feed");
+        assert_eq!(json["step"], "download");
+        assert_eq!(json["message"], "fetched");
+        let text = LogFormat::Text.format(&record);
+        assert!(text.ends_with("fetched feed=http://example.com/feed step=download\n"));
+    }
+
+    #[test]
+    fn test_file_rotates_past_max_size() {
+        let dir = std::env::temp_dir().join(format!("rustero-log-{}", std::process::id()));
+        let path = dir.join("rustero.log");
+        let mut file = RotatingFile::open(&path, 10).unwrap();
+        file.write_line("12345678\n").unwrap();
+        file.write_line("abcdefgh\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "abcdefgh\n");
+        assert_eq!(std::fs::read_to_string(dir.join("rustero.log.1")).unwrap(), "12345678\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,120 +1,71 @@
-use chrono::Utc;
-use rustero::app::{self, App};
-use rustero::commands::command_interpreters::PodcastPipelineInterpreter;
-use rustero::commands::podcast_algebra::{CommandAccumulator, PipelineData, run_commands};
-use rustero::commands::podcast_commands::PodcastCmd;
-use rustero::podcast::{Episode, EpisodeID, Podcast, PodcastURL};
+use rustero::app::{self, DuplicateFeeds};
+use rustero::cli::{CliArgs, CliCommand};
+use rustero::config::Config;
+use rustero::instance;
+use rustero::logging::{self, LogFilter};
+use rustero::podcast::PodcastURL;
 use rustero::podcast_download::{FeedFetcher, HttpFeedFetcher};
+use rustero::startup::{self, Startup};
+use rustero::validate::validate_feed;
 use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Create new app instance
-    let mut app = App::new();
+    let cli = CliArgs::parse(std::env::args().skip(1))?;
+    let profile = cli.profile.clone();
+    // Without a config file this is most likely the first start
+    let first_run = Config::default_path(profile.as_deref()).is_none_or(|path| !path.exists());
+    let mut config = Config::load_or_default(profile.as_deref())?;
+    if let Some(path) = cli.log_file.clone() {
+        config.log_file = Some(path);
+    }
+    if let Some(level) = cli.log_level.clone() {
+        config.log_level = level;
+    }
+    if let Some(format) = cli.log_format {
+        config.log_format = format;
+    }
+    let log_filter = LogFilter::parse(&config.log_level)?.with_verbosity(cli.verbosity_level());
+    logging::init(&config.log_file(), log_filter, config.log_format, config.log_max_bytes)?;
 
+    if let Some(CliCommand::Validate(url)) = &cli.command {
+        let report = validate_feed(&PodcastURL::new(url), &HttpFeedFetcher::new()).await;
+        println!("{}", report);
+        std::process::exit(if report.has_errors() { 1 } else { 0 });
+    }
+    // With another instance running, hand it the link and stop there
+    let socket = Config::data_dir(profile.as_deref()).map(|dir| instance::socket_path(&dir));
+    let added = match &cli.command {
+        Some(CliCommand::Add(link)) => match instance::deep_link_url(link) {
+            Some(url) => Some(url),
+            None => anyhow::bail!("Not a feed link: {}", link),
+        },
+        _ => None,
+    };
+    if let (Some(url), Some(socket)) = (&added, &socket)
+        && instance::forward_to_running_instance(socket, url)?
+    {
+        println!("Sent {} to the running rustero", url);
+        return Ok(());
+    }
     let fetcher: Arc<dyn FeedFetcher + Send + Sync> = Arc::new(HttpFeedFetcher::new());
-    let mut interpreter = PodcastPipelineInterpreter::new(fetcher.clone());
+    let data_dir = Config::data_dir(profile.as_deref());
+    let Startup { mut app, data_lock: _data_lock } =
+        startup::prepare_app(config, data_dir.as_deref(), first_run, fetcher)?;
+    if let Some(socket) = &socket {
+        app.listen_for_links(socket);
+    }
 
-    let cmd_seq1 = PodcastCmd::eval_url_from_str(
-        "https://feeds.zencastr.com/f/oSn1i316.rss", // URL as string for EvalUrl
-        PodcastCmd::download(
-            // This URL is a fallback if EvalUrl somehow didn't populate the accumulator
-            // or if the interpreter logic for Download was different.
-            // With current interpreter, eval'd URL takes precedence.
-            PodcastURL::new("http://unused-fallback.com/rss"),
-            PodcastCmd::save(PodcastCmd::end()),
-        ),
-    );
+    // `rustero import <file>`: the subscriptions arrive while the UI is already up
+    if let Some(CliCommand::Import { path, merge }) = &cli.command {
+        let duplicates = if *merge { DuplicateFeeds::Refresh } else { DuplicateFeeds::Ask };
+        app.import_opml(path, duplicates);
+    }
+    if let Some(url) = added {
+        app.offer_subscription(url);
+    }
 
-    println!("--- Running Sequence 1: Eval -> Download -> Save ---");
-    let initial_acc: CommandAccumulator = Ok(PipelineData::default());
-    let result1 = run_commands(&cmd_seq1, initial_acc, &mut interpreter).await;
-
-    // Create test episodes using the proper constructor
-    let test_episodes_1 = vec![
-        Episode::new(
-            EpisodeID::new("ep1"),
-            "First Episode".to_string(),
-            Some("This is episode 1".to_string()),
-            Utc::now(),
-            Some("20:00".to_string()),
-            "http://example.com/ep1.mp3".to_string(),
-            Some(1024 * 1024), // 1MB size
-        ),
-        Episode::new(
-            EpisodeID::new("ep2"),
-            "Second Episode".to_string(),
-            Some("This is episode 2".to_string()),
-            Utc::now(),
-            Some("25:00".to_string()),
-            "http://example.com/ep2.mp3".to_string(),
-            Some(1024 * 1024 * 2), // 2MB size
-        ),
-    ];
-
-    let test_episodes_2 = vec![
-        Episode::new(
-            EpisodeID::new("ep10"),
-            "10th Episode".to_string(),
-            Some("This is episode 10".to_string()),
-            Utc::now(),
-            Some("20:00".to_string()),
-            "http://example.com/ep10.mp3".to_string(),
-            Some(1024 * 1024), // 1MB size
-        ),
-        Episode::new(
-            EpisodeID::new("ep11"),
-            "11th Episode".to_string(),
-            Some("This is episode 11".to_string()),
-            Utc::now(),
-            Some("25:00".to_string()),
-            "http://example.com/ep11.mp3".to_string(),
-            Some(1024 * 1024 * 2), // 2MB size
-        ),
-    ];
-
-    // Create a test podcast with episodes
-    let test_podcast = Podcast::new(
-        PodcastURL::new("http://example.com/feed1"),
-        "Rust Daily News".to_string(),
-        Some("Daily news about Rust".to_string()),
-        None,
-        None,
-        test_episodes_1.clone(),
-    );
-    app.podcasts.push(test_podcast);
-
-    // Add another test podcast
-    let test_podcast2 = Podcast::new(
-        PodcastURL::new("http://example.com/feed2"),
-        "Programming Tips".to_string(),
-        Some("Programming tips and tricks".to_string()),
-        None,
-        None,
-        test_episodes_2.clone(),
-    );
-    app.podcasts.push(test_podcast2);
-
-    // match result1 {
-    //     Ok(data) => {
-    //         println!("\nSequence 1 completed successfully.");
-    //         if let Some(p) = data.current_podcast {
-    //             // current_podcast should still be Some after save
-    //             println!("Last processed podcast in accumulator: {}", p);
-    //         } else {
-    //             println!(
-    //                 "Sequence 1 completed, but no podcast was in the final accumulator context."
-    //             );
-    //         }
-    //
-    //         Ok(()) // Explicitly return Ok(()) for the success case of main
-    //     }
-    //     Err(pipeline_err) => {
-    //         eprintln!("\nSequence 1 failed: {}", pipeline_err);
-    //         Err(anyhow!(pipeline_err)) // Using anyhow! macro
-    //     }
-    // }
-    //
     // Start the UI with our initialized app
     app::start_ui(Some(app))
 }
@@ -0,0 +1,173 @@
+// How long getting each feed took, so slow subscriptions can be spotted and pruned
+use crate::podcast::PodcastURL;
+use std::collections::HashSet;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+// Time spent per stage for one feed; stages that didn't run stay at zero. Evaluating the
+// URL (HEAD / partial GET) counts as fetching.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeedTimings {
+    pub fetch: Duration,
+    pub parse: Duration,
+    pub save: Duration,
+}
+
+impl FeedTimings {
+    pub fn total(&self) -> Duration {
+        self.fetch + self.parse + self.save
+    }
+}
+
+impl fmt::Display for FeedTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (fetch {}, parse {}",
+            secs(self.total()),
+            secs(self.fetch),
+            secs(self.parse)
+        )?;
+        if !self.save.is_zero() {
+            write!(f, ", save {}", secs(self.save))?;
+        }
+        write!(f, ")")
+    }
+}
+
+fn secs(duration: Duration) -> String {
+    format!("{:.1}s", duration.as_secs_f64())
+}
+
+// The feeds of one import or refresh-all. Each feed reports its timings and new episodes
+// (if it got that far) and then finishes; the summary is ready once none are pending.
+#[derive(Debug)]
+pub struct BulkRun {
+    label: &'static str, // "import", "refresh"
+    pending: HashSet<PodcastURL>,
+    timings: Vec<(PodcastURL, FeedTimings)>,
+    new_episodes: Vec<(PodcastURL, usize)>,
+    failures: Vec<(PodcastURL, String)>,
+    started: Instant,
+}
+
+impl BulkRun {
+    pub fn new(label: &'static str, urls: &[PodcastURL]) -> Self {
+        Self {
+            label,
+            pending: urls.iter().cloned().collect(),
+            timings: Vec::new(),
+            new_episodes: Vec::new(),
+            failures: Vec::new(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn record_timings(&mut self, url: &PodcastURL, timings: FeedTimings) {
+        if self.pending.contains(url) {
+            self.timings.push((url.clone(), timings));
+        }
+    }
+
+    pub fn record_new_episodes(&mut self, url: &PodcastURL, count: usize) {
+        if count > 0 && self.pending.contains(url) {
+            self.new_episodes.push((url.clone(), count));
+        }
+    }
+
+    pub fn finished(&mut self, url: &PodcastURL, error: Option<&str>) {
+        if self.pending.remove(url)
+            && let Some(error) = error
+        {
+            self.failures.push((url.clone(), error.to_string()));
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    // Slowest first
+    pub fn slowest(&self, count: usize) -> Vec<&(PodcastURL, FeedTimings)> {
+        let mut timings: Vec<_> = self.timings.iter().collect();
+        timings.sort_by_key(|(_, t)| std::cmp::Reverse(t.total()));
+        timings.truncate(count);
+        timings
+    }
+
+    // "Import of 12 feed(s) took 8.1s, 1 failed; slowest: A 3.2s (fetch 3.0s, parse 0.2s), ..."
+    pub fn summary(&self, count: usize, name: impl Fn(&PodcastURL) -> String) -> String {
+        let feeds = self.timings.len() + self.failures.len();
+        let mut summary =
+            format!("{} of {} feed(s) took {}", self.label, feeds, secs(self.started.elapsed()));
+        if let Some(first) = summary.get_mut(..1) {
+            first.make_ascii_uppercase();
+        }
+        if !self.failures.is_empty() {
+            summary.push_str(&format!(", {} failed", self.failures.len()));
+        }
+        let slowest: Vec<String> =
+            self.slowest(count).iter().map(|(url, t)| format!("{} {}", name(url), t)).collect();
+        if !slowest.is_empty() {
+            summary.push_str(&format!("; slowest: {}", slowest.join(", ")));
+        }
+        summary
+    }
+
+    // The end-of-run report: new episodes per feed, then what failed and why
+    pub fn report(&self, name: impl Fn(&PodcastURL) -> String) -> Vec<String> {
+        let feeds = self.timings.len() + self.failures.len();
+        let mut lines =
+            vec![format!("Checked {} feed(s) in {}", feeds, secs(self.started.elapsed()))];
+        let total: usize = self.new_episodes.iter().map(|(_, count)| count).sum();
+        if total == 0 {
+            lines.push("No new episodes".to_string());
+        } else {
+            lines.push(format!("{} new episode(s):", total));
+            for (url, count) in &self.new_episodes {
+                lines.push(format!("  {}: {}", name(url), count));
+            }
+        }
+        if !self.failures.is_empty() {
+            lines.push(format!("{} failed:", self.failures.len()));
+            for (url, error) in &self.failures {
+                lines.push(format!("  {}: {}", name(url), error));
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bulk_run_summary() {
+        let (a, b, c) = (PodcastURL::new("a"), PodcastURL::new("b"), PodcastURL::new("c"));
+        let mut run = BulkRun::new("refresh", &[a.clone(), b.clone(), c.clone()]);
+        let ms = Duration::from_millis;
+        run.record_timings(&a, FeedTimings { fetch: ms(200), parse: ms(100), save: ms(0) });
+        run.record_new_episodes(&a, 2);
+        run.finished(&a, None);
+        run.record_timings(&b, FeedTimings { fetch: ms(2900), parse: ms(300), save: ms(0) });
+        run.record_new_episodes(&b, 0);
+        run.finished(&b, None);
+        assert!(!run.is_done());
+        run.finished(&c, Some("timed out"));
+        assert!(run.is_done());
+
+        let name = |url: &PodcastURL| url.as_str().to_uppercase();
+        let summary = run.summary(1, name);
+        assert!(summary.starts_with("Refresh of 3 feed(s) took "), "{}", summary);
+        assert!(summary.ends_with(", 1 failed; slowest: B 3.2s (fetch 2.9s, parse 0.3s)"));
+
+        let report = run.report(name);
+        assert!(report[0].starts_with("Checked 3 feed(s) in "));
+        assert_eq!(report[1..], ["2 new episode(s):", "  A: 2", "1 failed:", "  C: timed out"]);
+    }
+}
@@ -0,0 +1,162 @@
+// Reading subscription lists exported by other podcast apps (OPML 1.0/2.0)
+use crate::errors::OpmlError;
+use crate::podcast::PodcastURL;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpmlFeed {
+    pub title: Option<String>,
+    pub url: PodcastURL,
+}
+
+pub fn read_opml_file(path: &Path) -> Result<Vec<OpmlFeed>, OpmlError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|source| OpmlError::Io { path: path.to_path_buf(), source })?;
+    parse_opml(&content)
+}
+
+// OPML, or a plain text list of feed URLs when the file doesn't look like XML
+pub fn read_subscription_file(path: &Path) -> Result<Vec<OpmlFeed>, OpmlError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|source| OpmlError::Io { path: path.to_path_buf(), source })?;
+    if content.trim_start_matches('\u{feff}').trim_start().starts_with('<') {
+        parse_opml(&content)
+    } else {
+        parse_url_list(&content)
+    }
+}
+
+// One feed URL per line. Blank lines and lines starting with '#' are skipped, as is
+// anything after " #" on a line. Duplicate URLs are only returned once.
+pub fn parse_url_list(content: &str) -> Result<Vec<OpmlFeed>, OpmlError> {
+    let mut feeds: Vec<OpmlFeed> = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.split(" #").next().unwrap_or_default().trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let is_feed_url =
+            reqwest::Url::parse(line).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+        if !is_feed_url {
+            return Err(OpmlError::Parse(format!(
+                "line {} is not a feed URL: {}",
+                number + 1,
+                line
+            )));
+        }
+        let url = PodcastURL::new(line);
+        if !feeds.iter().any(|f| f.url == url) {
+            feeds.push(OpmlFeed { title: None, url });
+        }
+    }
+    Ok(feeds)
+}
+
+// Every <outline> with an xmlUrl, however deeply nested in category outlines.
+// Duplicate URLs are only returned once.
+pub fn parse_opml(content: &str) -> Result<Vec<OpmlFeed>, OpmlError> {
+    let mut reader = Reader::from_str(content);
+    let mut feeds: Vec<OpmlFeed> = Vec::new();
+    let mut saw_opml = false;
+
+    loop {
+        let event = reader.read_event().map_err(|e| {
+            OpmlError::Parse(format!("at byte {}: {}", reader.buffer_position(), e))
+        })?;
+        let element = match event {
+            Event::Start(e) | Event::Empty(e) => e,
+            Event::Eof => break,
+            _ => continue,
+        };
+        match element.local_name().as_ref() {
+            b"opml" => saw_opml = true,
+            b"outline" => {
+                let mut url = None;
+                let mut title = None;
+                let mut text = None;
+                for attr in element.attributes().flatten() {
+                    let value = attr
+                        .decode_and_unescape_value(reader.decoder())
+                        .map_err(|e| OpmlError::Parse(e.to_string()))?
+                        .trim()
+                        .to_string();
+                    if value.is_empty() {
+                        continue;
+                    }
+                    // Some exporters lowercase attribute names
+                    match attr.key.local_name().as_ref().to_ascii_lowercase().as_slice() {
+                        b"xmlurl" => url = Some(value),
+                        b"title" => title = Some(value),
+                        b"text" => text = Some(value),
+                        _ => {}
+                    }
+                }
+                let Some(url) = url.map(|u| PodcastURL::new(&u)) else {
+                    continue;
+                };
+                if !feeds.iter().any(|f| f.url == url) {
+                    feeds.push(OpmlFeed { title: title.or(text), url });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !saw_opml {
+        return Err(OpmlError::Parse("no <opml> element".to_string()));
+    }
+    Ok(feeds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url_list() {
+        let list = "# My feeds\n\nhttp://example.com/a.xml\n  https://example.com/b.xml # news\nhttp://example.com/a.xml/\n";
+        let urls: Vec<String> =
+            parse_url_list(list).unwrap().into_iter().map(|f| f.url.to_string()).collect();
+        assert_eq!(urls, vec!["http://example.com/a.xml", "https://example.com/b.xml"]);
+        assert!(parse_url_list("http://example.com/a.xml\nnot a url\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_nested_outlines() {
+        let opml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <opml version="2.0">
+                <head><title>Subscriptions</title></head>
+                <body>
+                    <outline text="Tech">
+                        <outline type="rss" text="Rust &amp; Friends" xmlUrl="http://example.com/rust.xml"/>
+                        <outline text="Dupe" xmlUrl="http://example.com/rust.xml/"/>
+                    </outline>
+                    <outline title="Other" text="Ignored" xmlurl="http://example.com/other.xml"/>
+                    <outline text="No feed" htmlUrl="http://example.com"/>
+                </body>
+            </opml>"#;
+
+        let feeds = parse_opml(opml).unwrap();
+        assert_eq!(
+            feeds,
+            vec![
+                OpmlFeed {
+                    title: Some("Rust & Friends".to_string()),
+                    url: PodcastURL::new("http://example.com/rust.xml"),
+                },
+                OpmlFeed {
+                    title: Some("Other".to_string()),
+                    url: PodcastURL::new("http://example.com/other.xml"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_opml() {
+        assert!(matches!(parse_opml("<rss></rss>"), Err(OpmlError::Parse(_))));
+        assert!(matches!(parse_opml("<opml><body><outline"), Err(OpmlError::Parse(_))));
+    }
+}
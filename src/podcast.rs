@@ -23,6 +23,13 @@ impl PartialEq for PodcastURL {
 
 impl Eq for PodcastURL {}
 
+impl std::hash::Hash for PodcastURL {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // Must agree with PartialEq, which ignores trailing slashes
+        self.0.trim_end_matches('/').hash(state);
+    }
+}
+
 impl PodcastURL {
     pub fn new(s: &str) -> Self {
         PodcastURL(s.to_string())
@@ -41,7 +48,7 @@ impl AsRef<str> for PodcastURL {
 }
 
 // === EPISODE STRUCTURES ===
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EpisodeID(String);
 
 impl std::fmt::Display for EpisodeID {
@@ -56,6 +63,20 @@ impl EpisodeID {
     }
 }
 
+// An episode across all podcasts. GUIDs are only unique within a feed, and plenty of
+// feeds number theirs "1", "2", ...
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EpisodeKey {
+    pub podcast: PodcastURL,
+    pub episode: EpisodeID,
+}
+
+impl EpisodeKey {
+    pub fn new(podcast: &PodcastURL, episode: &EpisodeID) -> Self {
+        Self { podcast: podcast.clone(), episode: episode.clone() }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Podcast {
     #[serde(rename = "url")]
@@ -72,6 +93,14 @@ pub struct Podcast {
     episodes: Vec<Episode>,
     #[serde(rename = "last_updated")]
     last_updated: DateTime<Utc>,
+    #[serde(rename = "author", default)]
+    author: Option<String>,
+    #[serde(rename = "language", default)]
+    language: Option<String>,
+    #[serde(rename = "categories", default)]
+    categories: Vec<String>,
+    #[serde(rename = "explicit", default)]
+    explicit: bool, // itunes:explicit on the channel
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,16 +111,39 @@ pub struct Episode {
     title: String,
     #[serde(rename = "description")]
     description: Option<String>,
-    #[serde(rename = "published_date")]
-    published_date: DateTime<Utc>,
+    #[serde(rename = "published_date", default)]
+    published_date: Option<DateTime<Utc>>, // None when the feed's date couldn't be read
     #[serde(rename = "duration")]
     duration: Option<String>,
     #[serde(rename = "audio_url")]
     audio_url: String,
     #[serde(rename = "size_in_bytes")]
     size_in_bytes: Option<u64>,
+    #[serde(rename = "mime_type", default)]
+    mime_type: Option<String>, // Enclosure type, e.g. "audio/mpeg" or "video/mp4"
+    #[serde(rename = "image_url", default)]
+    image_url: Option<String>, // Episode artwork (itunes:image on the item)
+    #[serde(rename = "explicit", default)]
+    explicit: bool, // itunes:explicit on the item, else the channel's
+    #[serde(rename = "link", default)]
+    link: Option<String>, // The episode's web page, if the feed has one
 }
 
+// Feeds use RFC 5646 tags ("en", "en-US", "de-at"); a filter of "en" matches all English
+// variants while "en-us" only matches that one. Case doesn't matter.
+pub fn language_matches(language: Option<&str>, filter: &str) -> bool {
+    let Some(language) = language else {
+        return false;
+    };
+    let (language, filter) =
+        (language.trim().to_ascii_lowercase(), filter.trim().to_ascii_lowercase());
+    language == filter
+        || language.strip_prefix(&filter).is_some_and(|rest| rest.starts_with(['-', '_']))
+}
+
+// Used when a feed doesn't declare the enclosure type
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "mov", "webm", "mkv"];
+
 impl Podcast {
     pub fn new(
         url: PodcastURL,
@@ -101,7 +153,39 @@ impl Podcast {
         website_url: Option<String>,
         episodes: Vec<Episode>,
     ) -> Self {
-        Self { url, title, description, image_url, website_url, episodes, last_updated: Utc::now() }
+        Self {
+            url,
+            title,
+            description,
+            image_url,
+            website_url,
+            episodes,
+            last_updated: Utc::now(),
+            author: None,
+            language: None,
+            categories: Vec::new(),
+            explicit: false,
+        }
+    }
+
+    pub fn with_author(mut self, author: Option<String>) -> Self {
+        self.author = author.filter(|a| !a.trim().is_empty());
+        self
+    }
+
+    pub fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language.filter(|l| !l.trim().is_empty());
+        self
+    }
+
+    pub fn with_categories(mut self, categories: Vec<String>) -> Self {
+        self.categories = categories;
+        self
+    }
+
+    pub fn with_explicit(mut self, explicit: bool) -> Self {
+        self.explicit = explicit;
+        self
     }
     // Accessor methods
 
@@ -133,6 +217,35 @@ impl Podcast {
         self.last_updated
     }
 
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    pub fn categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    pub fn is_explicit(&self) -> bool {
+        self.explicit
+    }
+
+    pub fn has_category(&self, category: &str) -> bool {
+        self.categories.iter().any(|c| c.eq_ignore_ascii_case(category.trim()))
+    }
+
+    pub fn matches_language(&self, filter: &str) -> bool {
+        language_matches(self.language(), filter)
+    }
+
+    // Artwork to show for an episode: its own image if it has one, else the podcast's
+    pub fn artwork_url<'a>(&'a self, episode: &'a Episode) -> Option<&'a str> {
+        episode.image_url().or(self.image_url())
+    }
+
     // Mutable accessor for adding episodes
     pub fn add_episode(&mut self, episode: Episode) {
         self.episodes.push(episode);
@@ -149,7 +262,45 @@ impl Episode {
         audio_url: String,
         size_in_bytes: Option<u64>,
     ) -> Self {
-        Self { id, title, description, published_date, duration, audio_url, size_in_bytes }
+        Self {
+            id,
+            title,
+            description,
+            published_date: Some(published_date),
+            duration,
+            audio_url,
+            size_in_bytes,
+            mime_type: None,
+            image_url: None,
+            explicit: false,
+            link: None,
+        }
+    }
+
+    pub fn with_mime_type(mut self, mime_type: Option<String>) -> Self {
+        self.mime_type = mime_type.filter(|m| !m.trim().is_empty());
+        self
+    }
+
+    pub fn with_image_url(mut self, image_url: Option<String>) -> Self {
+        self.image_url = image_url.filter(|u| !u.trim().is_empty());
+        self
+    }
+
+    pub fn with_explicit(mut self, explicit: bool) -> Self {
+        self.explicit = explicit;
+        self
+    }
+
+    // Builder method: None marks the date as unknown instead of guessing one
+    pub fn with_published_date(mut self, published_date: Option<DateTime<Utc>>) -> Self {
+        self.published_date = published_date;
+        self
+    }
+
+    pub fn with_link(mut self, link: Option<String>) -> Self {
+        self.link = link.filter(|l| !l.trim().is_empty());
+        self
     }
 
     pub fn id(&self) -> &EpisodeID {
@@ -164,7 +315,7 @@ impl Episode {
         self.description.as_deref()
     }
 
-    pub fn published_date(&self) -> DateTime<Utc> {
+    pub fn published_date(&self) -> Option<DateTime<Utc>> {
         self.published_date
     }
 
@@ -179,6 +330,49 @@ impl Episode {
     pub fn size_in_bytes(&self) -> Option<u64> {
         self.size_in_bytes
     }
+
+    pub fn mime_type(&self) -> Option<&str> {
+        self.mime_type.as_deref()
+    }
+
+    pub fn image_url(&self) -> Option<&str> {
+        self.image_url.as_deref()
+    }
+
+    pub fn is_explicit(&self) -> bool {
+        self.explicit
+    }
+
+    pub fn link(&self) -> Option<&str> {
+        self.link.as_deref()
+    }
+
+    // What a newer copy of this episode (same GUID) changed that the user would notice
+    pub fn changed_fields(&self, newer: &Episode) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.title != newer.title {
+            changed.push("title");
+        }
+        if self.description != newer.description {
+            changed.push("description");
+        }
+        if self.audio_url != newer.audio_url {
+            changed.push("enclosure");
+        }
+        changed
+    }
+
+    pub fn is_video(&self) -> bool {
+        match &self.mime_type {
+            Some(mime) => mime.trim().to_ascii_lowercase().starts_with("video/"),
+            None => {
+                let path = self.audio_url.split(['?', '#']).next().unwrap_or_default();
+                path.rsplit_once('.').is_some_and(|(_, ext)| {
+                    VIDEO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+                })
+            }
+        }
+    }
 }
 
 impl fmt::Display for Podcast {
@@ -1,10 +1,17 @@
 use crate::errors::DownloaderError;
+use crate::feed_formats::parse_feed;
+use crate::metrics::FeedTimings;
 use crate::podcast::{Podcast, PodcastURL};
 use crate::podcast_factory::{ParsedFeed, PodcastFactory};
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
 pub struct RawFeedData {
@@ -32,32 +39,107 @@ pub trait FeedFetcher: Send + Sync {
         url: &str,
         byte_range: (u64, u64), // e.g., (0, 4095)
     ) -> Result<String, DownloaderError>;
+
+    // Until when the server asked us not to fetch `url` again (Cache-Control max-age
+    // or Retry-After on the last response). None if there's no such hint.
+    fn fresh_until(&self, _url: &str) -> Option<Instant> {
+        None
+    }
+}
+
+// ===== Cache hints
+// Servers occasionally send absurd windows (max-age of a year); never wait longer than this
+const MAX_CACHE_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+// "max-age=N" from a Cache-Control header; no-cache/no-store mean there's no window
+pub fn parse_max_age(cache_control: &str) -> Option<Duration> {
+    let directives: Vec<String> =
+        cache_control.split(',').map(|d| d.trim().to_ascii_lowercase()).collect();
+    if directives.iter().any(|d| d == "no-cache" || d == "no-store") {
+        return None;
+    }
+    directives
+        .iter()
+        .find_map(|d| d.strip_prefix("max-age="))
+        .and_then(|secs| secs.trim_matches('"').parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(|secs| Duration::from_secs(secs).min(MAX_CACHE_WINDOW))
+}
+
+// Retry-After is either delay-seconds or an HTTP date
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    let delay = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => {
+            (DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc) - now).to_std().ok()?
+        }
+    };
+    Some(delay.min(MAX_CACHE_WINDOW))
 }
 
 // ===== Live http fetcher
 pub struct HttpFeedFetcher {
     client: reqwest::Client,
+    fresh_until: Mutex<HashMap<String, Instant>>, // From the last response per URL
+}
+
+impl Default for HttpFeedFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl HttpFeedFetcher {
+    // With reqwest's gzip/brotli/deflate features enabled the client sends Accept-Encoding
+    // and decodes compressed responses transparently
     pub fn new() -> Self {
-        Self { client: reqwest::Client::new() }
+        Self { client: reqwest::Client::new(), fresh_until: Mutex::new(HashMap::new()) }
+    }
+
+    fn remember_cache_window(&self, url: &str, headers: &reqwest::header::HeaderMap) {
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+        let window = [
+            header(reqwest::header::RETRY_AFTER).and_then(|v| parse_retry_after(v, Utc::now())),
+            header(reqwest::header::CACHE_CONTROL).and_then(parse_max_age),
+        ]
+        .into_iter()
+        .flatten()
+        .max();
+
+        let mut fresh_until = self.fresh_until.lock().unwrap_or_else(|e| e.into_inner());
+        match window {
+            Some(window) => fresh_until.insert(url.to_string(), Instant::now() + window),
+            None => fresh_until.remove(url),
+        };
     }
 }
 
 #[async_trait]
 impl FeedFetcher for HttpFeedFetcher {
     async fn fetch(&self, url: &str) -> Result<String, DownloaderError> {
-        println!("HttpFeedFetcher: fetching {}", url);
-        Ok(self
-            .client
-            .get(url)
-            .send()
-            .await
-            .map_err(DownloaderError::NetworkError)?
-            .text()
-            .await
-            .map_err(DownloaderError::NetworkError)?)
+        log::info!("HttpFeedFetcher: fetching {}", url);
+        let response = self.client.get(url).send().await.map_err(DownloaderError::NetworkError)?;
+        self.remember_cache_window(url, response.headers());
+        if !response.status().is_success() {
+            return Err(DownloaderError::Failed(format!(
+                "Request failed with status: {}",
+                response.status()
+            )));
+        }
+        let charset = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(charset_from_content_type)
+            .map(String::from);
+        let bytes = response.bytes().await.map_err(DownloaderError::NetworkError)?;
+        Ok(decode_feed(&bytes, charset.as_deref()))
+    }
+
+    fn fresh_until(&self, url: &str) -> Option<Instant> {
+        let fresh_until = self.fresh_until.lock().unwrap_or_else(|e| e.into_inner());
+        fresh_until.get(url).copied().filter(|until| *until > Instant::now())
     }
 
     async fn fetch_headers(&self, url: &str) -> Result<HashMap<String, String>, DownloaderError> {
@@ -98,27 +180,119 @@ impl FeedFetcher for HttpFeedFetcher {
                 response.status()
             )));
         }
-        response.text().await.map_err(DownloaderError::NetworkError)
+        // Servers that ignore Range answer 200 with the whole body, which can be a
+        // multi-megabyte feed; read only as far as the range reaches and drop the rest
+        let skip = match response.status() {
+            reqwest::StatusCode::PARTIAL_CONTENT => 0,
+            _ => byte_range.0 as usize,
+        };
+        let wanted = (byte_range.1.saturating_sub(byte_range.0) + 1) as usize;
+        let charset = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(charset_from_content_type)
+            .map(String::from);
+        let mut response = response;
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(DownloaderError::NetworkError)? {
+            bytes.extend_from_slice(&chunk);
+            if bytes.len() >= skip + wanted {
+                break;
+            }
+        }
+        let end = bytes.len().min(skip + wanted);
+        Ok(decode_feed(&bytes[skip.min(end)..end], charset.as_deref()))
     }
 }
 
 // ===== Fake http fetcher for testing
+// Scriptable: map URLs to distinct bodies or HTTP status errors, add latency,
+// and make the Nth call fail to exercise retry logic deterministically.
+#[derive(Debug, Clone)]
+enum FakeResponse {
+    Body(String),
+    Status(u16),
+}
+
 pub struct FakeFetcher {
-    pub response: String,
+    default_response: String, // Served for URLs that have no scripted response
+    responses: HashMap<String, FakeResponse>,
+    latency: Option<Duration>,
+    fail_on_call: Option<usize>, // 1-based, counted across all fetch methods
+    calls: AtomicUsize,
+}
+
+impl FakeFetcher {
+    pub fn new(default_response: impl Into<String>) -> Self {
+        Self {
+            default_response: default_response.into(),
+            responses: HashMap::new(),
+            latency: None,
+            fail_on_call: None,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    // Builder methods
+    pub fn with_response(mut self, url: &str, body: impl Into<String>) -> Self {
+        self.responses.insert(url.to_string(), FakeResponse::Body(body.into()));
+        self
+    }
+
+    pub fn with_status(mut self, url: &str, status: u16) -> Self {
+        self.responses.insert(url.to_string(), FakeResponse::Status(status));
+        self
+    }
+
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    pub fn with_failure_on_call(mut self, call: usize) -> Self {
+        self.fail_on_call = Some(call);
+        self
+    }
+
+    pub fn call_count(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    async fn respond(&self, url: &str) -> Result<&str, DownloaderError> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Some(latency) = self.latency {
+            tokio::time::sleep(latency).await;
+        }
+        if self.fail_on_call == Some(call) {
+            return Err(DownloaderError::Failed(format!(
+                "Simulated failure on call {} for {}",
+                call, url
+            )));
+        }
+        match self.responses.get(url) {
+            Some(FakeResponse::Body(body)) => Ok(body),
+            Some(FakeResponse::Status(status)) => {
+                Err(DownloaderError::Failed(format!("Request failed with status: {}", status)))
+            }
+            None => Ok(&self.default_response),
+        }
+    }
 }
 
 #[async_trait]
 impl FeedFetcher for FakeFetcher {
-    async fn fetch(&self, _url: &str) -> Result<String, DownloaderError> {
-        Ok(self.response.clone())
+    async fn fetch(&self, url: &str) -> Result<String, DownloaderError> {
+        self.respond(url).await.map(String::from)
     }
 
     // New method for HEAD request
 
-    async fn fetch_headers(&self, _url: &str) -> Result<HashMap<String, String>, DownloaderError> {
-        // Return some fake headers, e.g., based on self.response for testing
+    async fn fetch_headers(&self, url: &str) -> Result<HashMap<String, String>, DownloaderError> {
+        // Return some fake headers, based on the scripted response for testing
+        let response = self.respond(url).await?;
         let mut headers = HashMap::new();
-        if self.response.contains("<rss") || self.response.contains("<feed") {
+        if response.contains("<rss") || response.contains("<feed") {
             headers.insert("content-type".to_string(), "application/xml".to_string());
         } else {
             headers.insert("content-type".to_string(), "text/html".to_string());
@@ -129,32 +303,280 @@ impl FeedFetcher for FakeFetcher {
     // For partial content
     async fn fetch_partial_content(
         &self,
-        _url: &str,
+        url: &str,
         byte_range: (u64, u64),
     ) -> Result<String, DownloaderError> {
+        let response = self.respond(url).await?;
         let start = byte_range.0 as usize;
         let end = (byte_range.1 + 1) as usize; // Range is inclusive, slice is exclusive at end
-        if start < self.response.len() {
-            let effective_end = std::cmp::min(end, self.response.len());
-            Ok(self.response[start..effective_end].to_string())
+        if start < response.len() {
+            let effective_end = std::cmp::min(end, response.len());
+            Ok(response[start..effective_end].to_string())
         } else {
             Ok("".to_string())
         }
     }
 }
 
+// ===== Record/replay fetcher for fixtures
+// Wraps another fetcher: the first call for a URL is forwarded and its response saved
+// under `fixture_dir`, later calls are replayed from disk without touching the network.
+pub struct RecordingFetcher<F> {
+    inner: F,
+    fixture_dir: PathBuf,
+    record: bool, // False fails on a missing fixture instead of fetching it
+}
+
+impl<F: FeedFetcher> RecordingFetcher<F> {
+    pub fn new(inner: F, fixture_dir: impl Into<PathBuf>) -> Self {
+        Self { inner, fixture_dir: fixture_dir.into(), record: true }
+    }
+
+    // Builder methods
+    pub fn replay_only(mut self) -> Self {
+        self.record = false;
+        self
+    }
+
+    pub fn inner(&self) -> &F {
+        &self.inner
+    }
+
+    fn fixture_path(&self, url: &str, kind: &str) -> PathBuf {
+        let name: String =
+            url.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+        self.fixture_dir.join(format!("{}.{}", name, kind))
+    }
+
+    async fn replay(&self, path: &Path) -> Result<Option<String>, DownloaderError> {
+        match tokio::fs::read_to_string(path).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(DownloaderError::Failed(format!(
+                "Failed to read fixture {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    fn check_recording(&self, path: &Path) -> Result<(), DownloaderError> {
+        if self.record {
+            Ok(())
+        } else {
+            Err(DownloaderError::Failed(format!("No fixture {}", path.display())))
+        }
+    }
+
+    async fn record(&self, path: &Path, content: &str) -> Result<(), DownloaderError> {
+        let write = async {
+            tokio::fs::create_dir_all(&self.fixture_dir).await?;
+            tokio::fs::write(path, content).await
+        };
+        write.await.map_err(|e: std::io::Error| {
+            DownloaderError::Failed(format!("Failed to write fixture {}: {}", path.display(), e))
+        })
+    }
+}
+
+#[async_trait]
+impl<F: FeedFetcher> FeedFetcher for RecordingFetcher<F> {
+    async fn fetch(&self, url: &str) -> Result<String, DownloaderError> {
+        let path = self.fixture_path(url, "body");
+        if let Some(content) = self.replay(&path).await? {
+            return Ok(content);
+        }
+        self.check_recording(&path)?;
+        let content = self.inner.fetch(url).await?;
+        self.record(&path, &content).await?;
+        Ok(content)
+    }
+
+    async fn fetch_headers(&self, url: &str) -> Result<HashMap<String, String>, DownloaderError> {
+        let path = self.fixture_path(url, "headers.json");
+        if let Some(content) = self.replay(&path).await? {
+            return serde_json::from_str(&content).map_err(|e| {
+                DownloaderError::Failed(format!("Invalid fixture {}: {}", path.display(), e))
+            });
+        }
+        self.check_recording(&path)?;
+        let headers = self.inner.fetch_headers(url).await?;
+        let json = serde_json::to_string_pretty(&headers)
+            .map_err(|e| DownloaderError::Failed(format!("Failed to encode headers: {}", e)))?;
+        self.record(&path, &json).await?;
+        Ok(headers)
+    }
+
+    async fn fetch_partial_content(
+        &self,
+        url: &str,
+        byte_range: (u64, u64),
+    ) -> Result<String, DownloaderError> {
+        let path = self.fixture_path(url, &format!("partial-{}-{}", byte_range.0, byte_range.1));
+        if let Some(content) = self.replay(&path).await? {
+            return Ok(content);
+        }
+        self.check_recording(&path)?;
+        let content = self.inner.fetch_partial_content(url, byte_range).await?;
+        self.record(&path, &content).await?;
+        Ok(content)
+    }
+
+    fn fresh_until(&self, url: &str) -> Option<Instant> {
+        self.inner.fresh_until(url)
+    }
+}
+
+// ===== Feed encodings
+// Picks the encoding from (in order) a byte order mark, the XML declaration, the HTTP
+// charset, then UTF-8. Bytes that aren't valid in the chosen encoding fall back to
+// Windows-1252, which is what mislabeled "ISO-8859-1"/"UTF-8" feeds usually are.
+// The XML declaration is rewritten to UTF-8 so the parser doesn't decode twice.
+pub fn decode_feed(bytes: &[u8], http_charset: Option<&str>) -> String {
+    let declared = Encoding::for_bom(bytes)
+        .map(|(encoding, _)| encoding)
+        .or_else(|| declared_xml_encoding(bytes).and_then(|l| Encoding::for_label(l.as_bytes())))
+        .or_else(|| http_charset.and_then(|l| Encoding::for_label(l.as_bytes())))
+        .unwrap_or(UTF_8);
+
+    let (text, used, had_errors) = declared.decode(bytes);
+    let text = if had_errors && used != WINDOWS_1252 {
+        log::warn!("Feed is not valid {}, decoding as windows-1252", used.name());
+        WINDOWS_1252.decode(bytes).0
+    } else {
+        text
+    };
+    with_utf8_declaration(&text)
+}
+
+fn charset_from_content_type(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim().eq_ignore_ascii_case("charset").then(|| value.trim().trim_matches('"'))
+    })
+}
+
+// The `encoding="..."` of a leading `<?xml ...?>`, read as ASCII
+fn declared_xml_encoding(bytes: &[u8]) -> Option<String> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(256)]);
+    let declaration = xml_declaration(&head)?;
+    let (_, rest) = declaration.split_once("encoding")?;
+    let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &rest[1..];
+    value.find(quote).map(|end| value[..end].to_string())
+}
+
+fn xml_declaration(text: &str) -> Option<&str> {
+    let start = text.trim_start_matches('\u{feff}').trim_start();
+    start.starts_with("<?xml").then(|| start.find("?>").map(|end| &start[..end + 2]))?
+}
+
+fn with_utf8_declaration(text: &str) -> String {
+    let Some(declaration) = xml_declaration(text) else {
+        return text.to_string();
+    };
+    match declared_xml_encoding(declaration.as_bytes()) {
+        Some(label) if !label.eq_ignore_ascii_case("utf-8") => {
+            let fixed = declaration.replacen(&label, "UTF-8", 1);
+            text.replacen(declaration, &fixed, 1)
+        }
+        _ => text.to_string(),
+    }
+}
+
+// Catches bodies that were served successfully but aren't a feed (HTML error pages,
+// undecoded compressed data) before they reach the RSS parser
+pub fn verify_xml(content: &str) -> Result<(), DownloaderError> {
+    let start = content.trim_start_matches('\u{feff}').trim_start();
+    let head: String = start.chars().take(64).collect();
+    let lowercase_head = head.to_ascii_lowercase();
+    if !start.starts_with('<') {
+        Err(DownloaderError::NotXml(format!("starts with {:?}", head)))
+    } else if lowercase_head.starts_with("<!doctype html") || lowercase_head.starts_with("<html") {
+        Err(DownloaderError::NotXml("got an HTML page".to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss, // RSS 2.0, or RSS 1.0 (<rdf:RDF>)
+    Atom,
+    JsonFeed,
+}
+
+// Which kind of feed the start of a body is, going by its root element (or the JSON Feed
+// version URL). A BOM, the XML declaration, comments and a doctype before the root are
+// skipped; None if the prefix ends before the root element shows up.
+pub fn sniff_feed_format(prefix: &str) -> Option<FeedFormat> {
+    let mut rest = prefix.trim_start_matches('\u{feff}').trim_start();
+    if rest.starts_with('{') {
+        return rest.contains("jsonfeed.org/version").then_some(FeedFormat::JsonFeed);
+    }
+    loop {
+        let end = if rest.starts_with("<?") {
+            rest.find("?>").map(|i| i + 2)
+        } else if rest.starts_with("<!--") {
+            rest.find("-->").map(|i| i + 3)
+        } else if rest.starts_with("<!") {
+            rest.find('>').map(|i| i + 1)
+        } else {
+            break;
+        };
+        rest = rest[end?..].trim_start();
+    }
+    let mut name =
+        rest.strip_prefix('<')?.split(|c: char| c.is_whitespace() || c == '>' || c == '/');
+    // Namespace prefixes vary ("atom:feed", "rdf:RDF"), only the local name counts
+    let local = name.next()?.rsplit(':').next()?.to_ascii_lowercase();
+    match local.as_str() {
+        "rss" | "rdf" => Some(FeedFormat::Rss),
+        "feed" => Some(FeedFormat::Atom),
+        _ => None,
+    }
+}
+
 // Implementation of the download function
 pub async fn download_and_create_podcast(
     url: &PodcastURL,
     fetcher: &(dyn FeedFetcher + Send + Sync),
 ) -> Result<Podcast, DownloaderError> {
-    println!("download_and_create_podcast: Fetching content for URL: {}", url.as_str());
-    let content = fetcher.fetch(url.as_str()).await?;
-    println!("download_and_create_podcast: Content fetched, length: {}", content.len());
-    let channel = rss::Channel::read_from(content.as_bytes())?;
-    let parsed = ParsedFeed { channel };
+    let mut timings = FeedTimings::default();
+    download_and_create_podcast_with_factory(
+        url,
+        fetcher,
+        &PodcastFactory::new(),
+        None,
+        &mut timings,
+    )
+    .await
+}
+
+// `format` is what evaluating the URL found out (Content-Type or a sniffed prefix); without
+// it the body is sniffed, and anything unrecognised is handed to the RSS parser.
+// Fetch and parse durations are added to `timings`, also when a step fails.
+pub async fn download_and_create_podcast_with_factory(
+    url: &PodcastURL,
+    fetcher: &(dyn FeedFetcher + Send + Sync),
+    factory: &PodcastFactory,
+    format: Option<FeedFormat>,
+    timings: &mut FeedTimings,
+) -> Result<Podcast, DownloaderError> {
+    log::info!("download_and_create_podcast: Fetching content for URL: {}", url.as_str());
+    let started = Instant::now();
+    let content = fetcher.fetch(url.as_str()).await;
+    timings.fetch += started.elapsed();
+    let content = content?;
+    log::info!("download_and_create_podcast: Content fetched, length: {}", content.len());
 
-    PodcastFactory::new().create_podcast(parsed, url.to_string())
+    let started = Instant::now();
+    let format = format.or_else(|| sniff_feed_format(&content)).unwrap_or(FeedFormat::Rss);
+    let podcast = parse_feed(&content, format)
+        .and_then(|channel| factory.create_podcast(ParsedFeed { channel }, url.to_string()));
+    timings.parse += started.elapsed();
+    podcast
 }
 
 #[cfg(test)]
@@ -162,6 +584,85 @@ mod tests {
     use super::*;
     use crate::podcast::PodcastURL;
 
+    #[test]
+    fn test_decode_feed_uses_declared_encoding() {
+        let mut latin1 = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><rss><title>Caf".to_vec();
+        latin1.extend_from_slice(&[0xE9, 0x20, 0x96, 0x20, 0x80]); // e-acute, en dash, euro
+        latin1.extend_from_slice(b"</title></rss>");
+
+        let decoded = decode_feed(&latin1, None);
+        assert_eq!(
+            decoded,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><rss><title>Café – €</title></rss>"
+        );
+    }
+
+    #[test]
+    fn test_decode_feed_falls_back_to_http_charset_and_windows_1252() {
+        assert_eq!(decode_feed(&[b'<', b'a', b'>', 0xE9], Some("iso-8859-1")), "<a>é");
+        // Claims UTF-8 but isn't
+        assert_eq!(decode_feed(&[b'<', b'a', b'>', 0x93, b'x', 0x94], None), "<a>“x”");
+        assert_eq!(decode_feed("<a>ü</a>".as_bytes(), None), "<a>ü</a>");
+        assert_eq!(
+            charset_from_content_type("text/xml; charset=\"Windows-1252\""),
+            Some("Windows-1252")
+        );
+    }
+
+    #[test]
+    fn test_verify_xml() {
+        assert!(verify_xml("<?xml version=\"1.0\"?><rss/>").is_ok());
+        assert!(verify_xml("\u{feff}\n  <rss version=\"2.0\"></rss>").is_ok());
+        assert!(matches!(
+            verify_xml("<!DOCTYPE html><html></html>"),
+            Err(DownloaderError::NotXml(_))
+        ));
+        assert!(matches!(verify_xml("\u{1f}\u{8b}garbage"), Err(DownloaderError::NotXml(_))));
+        assert!(matches!(verify_xml(""), Err(DownloaderError::NotXml(_))));
+    }
+
+    #[test]
+    fn test_sniff_feed_format() {
+        let rss = "\u{feff}<?xml version=\"1.0\"?>\n<!-- generated by x -->\n<rss version=\"2.0\">";
+        assert_eq!(sniff_feed_format(rss), Some(FeedFormat::Rss));
+        assert_eq!(sniff_feed_format("<rdf:RDF xmlns:rdf=\"...\">"), Some(FeedFormat::Rss));
+        assert_eq!(
+            sniff_feed_format("<?xml-stylesheet href=\"a.xsl\"?><atom:feed>"),
+            Some(FeedFormat::Atom)
+        );
+        assert_eq!(
+            sniff_feed_format("{\"version\": \"https://jsonfeed.org/version/1.1\", \"items\": ["),
+            Some(FeedFormat::JsonFeed)
+        );
+        assert_eq!(sniff_feed_format("{\"error\": \"not found\"}"), None);
+        // An HTML page that merely mentions <rss somewhere
+        assert_eq!(sniff_feed_format("<!DOCTYPE html><html><p>&lt;rss</p><rss"), None);
+        // Cut off inside a long comment
+        assert_eq!(sniff_feed_format("<!-- lots of licence text"), None);
+    }
+
+    #[test]
+    fn test_parse_max_age() {
+        assert_eq!(parse_max_age("public, max-age=600"), Some(Duration::from_secs(600)));
+        assert_eq!(parse_max_age("Max-Age=60, must-revalidate"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_max_age("max-age=600, no-cache"), None);
+        assert_eq!(parse_max_age("max-age=0"), None);
+        assert_eq!(parse_max_age("private"), None);
+        assert_eq!(parse_max_age("max-age=31536000"), Some(MAX_CACHE_WINDOW));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT").unwrap().into();
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:30:00 GMT", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now), None); // In the past
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
     #[tokio::test]
     async fn test_download_and_create_podcast() {
         // Create a dummy RSS feed content
@@ -180,7 +681,7 @@ mod tests {
         "#
         .to_string();
 
-        let fetcher = FakeFetcher { response: dummy_feed };
+        let fetcher = FakeFetcher::new(dummy_feed);
 
         let url = PodcastURL::new("http://example.com/feed");
         let podcast = download_and_create_podcast(&url, &fetcher).await.unwrap();
@@ -193,7 +694,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_real_feed_download() {
-        let fetcher = HttpFeedFetcher::new();
+        // Replays the captured response; never touches the network or writes fixtures
+        let fetcher = RecordingFetcher::new(
+            HttpFeedFetcher::new(),
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"),
+        )
+        .replay_only();
         let url = PodcastURL::new("https://feeds.zencastr.com/f/oSn1i316.rss");
 
         let podcast = download_and_create_podcast(&url, &fetcher).await.unwrap();
@@ -206,6 +712,7 @@ mod tests {
         assert!(podcast.description().is_some());
         assert!(podcast.image_url().is_some());
         assert_eq!(podcast.website_url(), Some("http://www.developervoices.com"));
+        assert_eq!(podcast.episodes().len(), 2);
     }
 
     // SAD PATHS
@@ -213,10 +720,81 @@ mod tests {
     #[tokio::test]
     async fn test_malformed_feed() {
         let malformed_xml = r#"<?xml version="1.0"?><rss><channel>"#;
-        let fetcher = FakeFetcher { response: malformed_xml.to_string() };
+        let fetcher = FakeFetcher::new(malformed_xml);
 
         let result =
             download_and_create_podcast(&PodcastURL::new("http://example.com"), &fetcher).await;
         assert!(matches!(result, Err(DownloaderError::RssError(_))));
     }
+
+    // SCRIPTED FAKE FETCHER
+
+    #[tokio::test]
+    async fn test_fake_fetcher_serves_distinct_urls() {
+        let fetcher = FakeFetcher::new("default")
+            .with_response("http://a.example.com", "<rss>a</rss>")
+            .with_response("http://b.example.com", "<rss>b</rss>");
+
+        assert_eq!(fetcher.fetch("http://a.example.com").await.unwrap(), "<rss>a</rss>");
+        assert_eq!(fetcher.fetch("http://b.example.com").await.unwrap(), "<rss>b</rss>");
+        assert_eq!(fetcher.fetch("http://other.example.com").await.unwrap(), "default");
+        assert_eq!(fetcher.call_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fake_fetcher_status_error() {
+        let fetcher = FakeFetcher::new("").with_status("http://gone.example.com", 404);
+
+        let result = fetcher.fetch_headers("http://gone.example.com").await;
+        assert!(matches!(result, Err(DownloaderError::Failed(msg)) if msg.contains("404")));
+    }
+
+    #[tokio::test]
+    async fn test_fake_fetcher_fails_only_on_nth_call() {
+        let fetcher = FakeFetcher::new("<rss></rss>").with_failure_on_call(2);
+
+        assert!(fetcher.fetch("http://example.com").await.is_ok());
+        assert!(fetcher.fetch("http://example.com").await.is_err());
+        assert!(fetcher.fetch("http://example.com").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fake_fetcher_latency() {
+        let fetcher = FakeFetcher::new("<rss></rss>").with_latency(Duration::from_millis(20));
+
+        let started = std::time::Instant::now();
+        fetcher.fetch("http://example.com").await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    // RECORD/REPLAY FETCHER
+
+    #[tokio::test]
+    async fn test_recording_fetcher_replays_recorded_responses() {
+        let fixture_dir = std::env::temp_dir().join(format!(
+            "rustero-fixtures-{}-{}",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or(0)
+        ));
+        let url = "http://example.com/feed";
+
+        let recorder = RecordingFetcher::new(FakeFetcher::new("<rss>recorded</rss>"), &fixture_dir);
+        assert_eq!(recorder.fetch(url).await.unwrap(), "<rss>recorded</rss>");
+        let headers = recorder.fetch_headers(url).await.unwrap();
+        assert_eq!(recorder.inner().call_count(), 2);
+
+        // A fetcher that would fail every call proves the responses come from disk
+        let replayer =
+            RecordingFetcher::new(FakeFetcher::new("").with_status(url, 500), &fixture_dir);
+        assert_eq!(replayer.fetch(url).await.unwrap(), "<rss>recorded</rss>");
+        assert_eq!(replayer.fetch_headers(url).await.unwrap(), headers);
+        assert_eq!(replayer.inner().call_count(), 0);
+
+        // Replay-only: an unknown URL fails without asking the wrapped fetcher
+        let replayer = RecordingFetcher::new(FakeFetcher::new(""), &fixture_dir).replay_only();
+        assert!(replayer.fetch("http://example.com/other").await.is_err());
+        assert_eq!(replayer.inner().call_count(), 0);
+
+        std::fs::remove_dir_all(&fixture_dir).unwrap();
+    }
 }
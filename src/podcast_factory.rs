@@ -1,28 +1,249 @@
 use crate::errors::DownloaderError;
 use crate::podcast::{Episode, EpisodeID, Podcast, PodcastURL};
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use rss::Channel;
+use rss::extension::Extension;
+use std::collections::HashSet;
 
 #[derive(Debug)]
 pub struct ParsedFeed {
     pub channel: Channel,
 }
 
+// By publish date; episodes with the same (or no) date keep their feed order, and undated
+// ones go last either way
 #[derive(Debug, Clone, Copy)]
 pub enum EpisodeSortOrder {
     NewestFirst,
     OldestFirst,
 }
 
+// Analytics/redirect services that prefix the real media URL, e.g.
+// https://dts.podtrac.com/redirect.mp3/traffic.example.com/ep1.mp3.
+// Each entry is the prefix (without scheme) and how many id segments follow it.
+const TRACKING_PREFIXES: &[(&str, usize)] = &[
+    ("chtbl.com/track/", 1),
+    ("chrt.fm/track/", 1),
+    ("dts.podtrac.com/redirect.mp3/", 0),
+    ("www.podtrac.com/pts/redirect.mp3/", 0),
+    ("podtrac.com/pts/redirect.mp3/", 0),
+    ("pdst.fm/e/", 0),
+    ("op3.dev/e/", 0),
+    ("op3.dev/e,", 1), // op3.dev/e,pg=<guid>/<url>
+    ("pfx.vpixl.com/", 1),
+    ("mgln.ai/e/", 1),
+    ("arttrk.com/p/", 1),
+    ("verifi.podscribe.com/rss/p/", 0),
+    ("pscrb.fm/rss/p/", 0),
+    ("claritaspod.com/measure/", 0),
+    ("tracking.swap.fm/track/", 1),
+];
+
+// Unwraps (possibly chained) tracking prefixes, returning a direct URL to the media host
+pub fn strip_tracking_prefixes(url: &str) -> String {
+    let mut current = url.to_string();
+    loop {
+        match strip_one_tracking_prefix(&current) {
+            Some(stripped) => current = stripped,
+            None => return current,
+        }
+    }
+}
+
+fn strip_one_tracking_prefix(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let (prefix, id_segments) =
+        TRACKING_PREFIXES.iter().find(|(prefix, _)| rest.starts_with(prefix))?;
+
+    let mut target = &rest[prefix.len()..];
+    for _ in 0..*id_segments {
+        target = target.split_once('/')?.1;
+    }
+
+    // The wrapped URL may or may not repeat its scheme
+    if target.starts_with("http://") || target.starts_with("https://") {
+        return Some(target.to_string());
+    }
+    let host = target.split('/').next()?;
+    if !host.contains('.') {
+        return None; // Doesn't look like host/path, leave the URL alone
+    }
+    Some(format!("{}://{}", scheme, target))
+}
+
+// Prefers the description, falling back to content:encoded; blank ones count as missing.
+// HTML cleanup happens at display time in `show_notes`.
+fn episode_description(item: &rss::Item) -> Option<String> {
+    [item.description(), item.content()]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|d| !d.is_empty())
+        .map(String::from)
+}
+
+// itunes:explicit has been spelled many ways over the years; None means "not stated"
+fn parse_explicit(value: Option<&str>) -> Option<bool> {
+    match value?.trim().to_ascii_lowercase().as_str() {
+        "yes" | "true" | "explicit" => Some(true),
+        "no" | "false" | "clean" => Some(false),
+        _ => None,
+    }
+}
+
+// Plain RSS categories plus iTunes ones (including subcategories), without duplicates
+fn channel_categories(channel: &Channel) -> Vec<String> {
+    let rss = channel.categories().iter().map(|c| c.name().to_string());
+    let itunes = channel.itunes_ext().into_iter().flat_map(|it| {
+        it.categories().iter().flat_map(|c| {
+            std::iter::once(c.text().to_string())
+                .chain(c.subcategory().map(|sub| sub.text().to_string()))
+        })
+    });
+
+    let mut categories: Vec<String> = Vec::new();
+    for category in rss.chain(itunes) {
+        let category = category.trim().to_string();
+        if !category.is_empty() && !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+    categories
+}
+
+// ===== Media RSS
+const MEDIA_RSS_NAMESPACE: &str = "http://search.yahoo.com/mrss/";
+
+// Feeds may bind the Media RSS namespace to any prefix
+pub(crate) fn media_prefix(channel: &Channel) -> &str {
+    channel
+        .namespaces()
+        .iter()
+        .find(|(_, uri)| uri.trim_end_matches('/') == MEDIA_RSS_NAMESPACE.trim_end_matches('/'))
+        .map_or("media", |(prefix, _)| prefix.as_str())
+}
+
+// Date formats seen in the wild once the weekday is dropped, tried in order
+const DATE_FORMATS_WITH_ZONE: &[&str] = &[
+    "%d %B %Y %H:%M:%S %z", // %B also accepts abbreviated month names
+    "%d %B %Y %H:%M %z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S %z",
+];
+// Without a (known) zone the date is read as UTC
+const DATE_FORMATS_WITHOUT_ZONE: &[&str] = &[
+    "%d %B %Y %H:%M:%S",
+    "%d %B %Y %H:%M",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+];
+
+// pubDate as RSS specifies it (RFC 2822), with fallbacks for ISO 8601 / RFC 3339 dates and
+// common slips: wrong or spelled-out weekdays, full month names, missing seconds, extra
+// whitespace and zone names like "PST" or "CEST"
+pub fn parse_pub_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    let parsed =
+        DateTime::parse_from_rfc2822(value).or_else(|_| DateTime::parse_from_rfc3339(value));
+    if let Ok(dt) = parsed {
+        return Some(dt.with_timezone(&Utc));
+    }
+    // A wrong weekday makes the strict parser give up, and it carries no information anyway
+    let value = match value.split_once(',') {
+        Some((weekday, rest)) if weekday.trim().chars().all(char::is_alphabetic) => rest,
+        _ => value,
+    };
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if let Some(dt) =
+        DATE_FORMATS_WITH_ZONE.iter().find_map(|f| DateTime::parse_from_str(&value, f).ok())
+    {
+        return Some(dt.with_timezone(&Utc));
+    }
+    let without_zone = match value.rsplit_once(' ') {
+        Some((rest, zone)) if zone.chars().all(|c| c.is_ascii_alphabetic()) => rest,
+        _ => value.as_str(),
+    };
+    DATE_FORMATS_WITHOUT_ZONE
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(without_zone, f).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(without_zone, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+        .map(|dt| dt.and_utc())
+}
+
+// The playable file of an item: its <enclosure>, or a <media:content> when there is none
+pub(crate) struct MediaFile {
+    url: String,
+    mime_type: Option<String>,
+    size_in_bytes: Option<u64>,
+    duration: Option<String>, // Only media:content carries one, in seconds
+}
+
+pub(crate) fn media_file(item: &rss::Item, media_prefix: &str) -> Option<MediaFile> {
+    if let Some(enclosure) = item.enclosure() {
+        return Some(MediaFile {
+            url: enclosure.url().to_string(),
+            mime_type: Some(enclosure.mime_type().to_string()),
+            size_in_bytes: enclosure.length().parse::<u64>().ok(),
+            duration: None,
+        });
+    }
+    let content = best_media_content(item, media_prefix)?;
+    let attr = |name: &str| content.attrs().get(name).filter(|v| !v.trim().is_empty());
+    Some(MediaFile {
+        url: attr("url")?.to_string(),
+        mime_type: attr("type").cloned(),
+        size_in_bytes: attr("fileSize").and_then(|s| s.parse().ok()),
+        duration: attr("duration").cloned(),
+    })
+}
+
+// media:content may appear directly or inside a media:group (one per bitrate/format).
+// Prefer the one flagged isDefault, then any audio/video, then whatever comes first.
+fn best_media_content<'a>(item: &'a rss::Item, media_prefix: &str) -> Option<&'a Extension> {
+    let media = item.extensions().get(media_prefix)?;
+    let direct = media.get("content").into_iter().flatten();
+    let grouped = media
+        .get("group")
+        .into_iter()
+        .flatten()
+        .flat_map(|group| group.children().get("content").into_iter().flatten());
+    let candidates: Vec<&Extension> =
+        direct.chain(grouped).filter(|c| c.attrs().contains_key("url")).collect();
+
+    let is_default = |c: &&Extension| c.attrs().get("isDefault").is_some_and(|v| v == "true");
+    let is_playable = |c: &&Extension| {
+        let attr = |name: &str| c.attrs().get(name).map(|v| v.to_ascii_lowercase());
+        matches!(attr("medium").as_deref(), Some("audio" | "video"))
+            || attr("type").is_some_and(|t| t.starts_with("audio/") || t.starts_with("video/"))
+    };
+    candidates
+        .iter()
+        .copied()
+        .find(is_default)
+        .or_else(|| candidates.iter().copied().find(is_playable))
+        .or_else(|| candidates.first().copied())
+}
+
+#[derive(Debug, Clone)]
 pub struct PodcastFactory {
     episode_limit: Option<usize>,
     sort_order: EpisodeSortOrder,
+    strip_tracking_prefixes: bool,
 }
 
 impl Default for PodcastFactory {
     fn default() -> Self {
-        Self { episode_limit: None, sort_order: EpisodeSortOrder::NewestFirst }
+        Self {
+            episode_limit: None,
+            sort_order: EpisodeSortOrder::NewestFirst,
+            strip_tracking_prefixes: false,
+        }
     }
 }
 
@@ -42,67 +263,116 @@ impl PodcastFactory {
         self
     }
 
+    pub fn with_tracking_prefix_stripping(mut self, enabled: bool) -> Self {
+        self.strip_tracking_prefixes = enabled;
+        self
+    }
+
     pub fn create_podcast(
         &self,
         parsed: ParsedFeed,
         feed_url: String,
     ) -> Result<Podcast, DownloaderError> {
+        let media_prefix = media_prefix(&parsed.channel);
+
+        let channel_explicit =
+            parse_explicit(parsed.channel.itunes_ext().and_then(|it| it.explicit()))
+                .unwrap_or(false);
+
         let mut episodes: Vec<Episode> = parsed
             .channel
             .items()
             .iter()
             .filter_map(|item| {
+                let media = media_file(item, media_prefix)?;
                 let id = item
                     .guid()
                     .map(|g| g.value().to_string())
-                    .or_else(|| item.link().map(String::from))?;
+                    .or_else(|| item.link().map(String::from))
+                    .unwrap_or_else(|| media.url.clone());
                 let title = item.title()?.to_string();
-                let description = item.description().map(String::from);
-                let enclosure = item.enclosure()?; // enclosure is Option<rss::Enclosure>
-                let audio_url = enclosure.url().to_string();
-                let size_in_bytes = enclosure.length().parse::<u64>().ok();
-                let duration = item.itunes_ext().and_then(|it| it.duration().map(String::from));
+                let description = episode_description(item);
+                let audio_url = if self.strip_tracking_prefixes {
+                    strip_tracking_prefixes(&media.url)
+                } else {
+                    media.url
+                };
+                let duration = item
+                    .itunes_ext()
+                    .and_then(|it| it.duration().map(String::from))
+                    .or(media.duration);
+                // dc:date is ISO 8601; some feeds use it instead of pubDate
                 let pub_date = item
                     .pub_date()
-                    .and_then(|s| DateTime::parse_from_rfc2822(s).ok())
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(Utc::now);
-
-                Some(Episode::new(
-                    EpisodeID::new(&id),
-                    title,
-                    description,
-                    pub_date,
-                    duration,
-                    audio_url,
-                    size_in_bytes,
-                ))
+                    .or_else(|| {
+                        item.dublin_core_ext().and_then(|dc| dc.dates().first()).map(String::as_str)
+                    })
+                    .and_then(parse_pub_date);
+
+                Some(
+                    Episode::new(
+                        EpisodeID::new(&id),
+                        title,
+                        description,
+                        pub_date.unwrap_or_default(), // Replaced right below
+                        duration,
+                        audio_url,
+                        media.size_in_bytes,
+                    )
+                    .with_published_date(pub_date)
+                    .with_mime_type(media.mime_type)
+                    .with_image_url(item.itunes_ext().and_then(|it| it.image().map(String::from)))
+                    .with_explicit(
+                        parse_explicit(item.itunes_ext().and_then(|it| it.explicit()))
+                            .unwrap_or(channel_explicit),
+                    )
+                    .with_link(item.link().map(String::from)),
+                )
             })
             .collect();
 
+        // Some feeds repeat items, or re-publish them with a new enclosure URL under the
+        // same GUID; the first (usually newest) copy wins
+        let mut seen = HashSet::new();
+        episodes.retain(|e| seen.insert(e.id().clone()));
+
+        // Feeds aren't reliably newest-first, so sort by date rather than trusting the order.
+        // The sorts are stable, which keeps feed order for ties. The limit keeps the newest.
+        episodes.sort_by_key(|e| std::cmp::Reverse(e.published_date()));
         if let Some(limit) = self.episode_limit {
             episodes.truncate(limit);
         }
-
         if let EpisodeSortOrder::OldestFirst = self.sort_order {
-            episodes.reverse();
+            episodes.sort_by_key(|e| (e.published_date().is_none(), e.published_date()));
         }
 
+        let channel = &parsed.channel;
+        let author = channel
+            .itunes_ext()
+            .and_then(|it| it.author())
+            .or(channel.managing_editor())
+            .map(String::from);
+
         Ok(Podcast::new(
             PodcastURL::new(&feed_url),
-            parsed.channel.title().to_string(),
-            Some(parsed.channel.description().to_string()),
-            parsed.channel.image().map(|img| img.url().to_string()),
-            Some(parsed.channel.link().to_string()),
+            channel.title().to_string(),
+            Some(channel.description().to_string()),
+            channel.image().map(|img| img.url().to_string()),
+            Some(channel.link().to_string()),
             episodes,
-        ))
+        )
+        .with_author(author)
+        .with_language(channel.language().map(String::from))
+        .with_categories(channel_categories(channel))
+        .with_explicit(channel_explicit))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rss::{ChannelBuilder, ImageBuilder};
+    use chrono::TimeZone;
+    use rss::{ChannelBuilder, EnclosureBuilder, GuidBuilder, ImageBuilder, ItemBuilder};
 
     #[test]
     fn test_create_podcast_from_parsed_feed() {
@@ -132,4 +402,315 @@ mod tests {
         assert_eq!(podcast.website_url(), Some("http://example.com/feed"));
         assert!(podcast.episodes().is_empty());
     }
+
+    #[test]
+    fn test_strip_tracking_prefixes() {
+        let cases = [
+            (
+                "https://dts.podtrac.com/redirect.mp3/traffic.example.com/ep1.mp3",
+                "https://traffic.example.com/ep1.mp3",
+            ),
+            (
+                "https://chtbl.com/track/ABC123/traffic.example.com/ep1.mp3",
+                "https://traffic.example.com/ep1.mp3",
+            ),
+            // Chained prefixes, inner one keeps its own scheme
+            (
+                "https://dts.podtrac.com/redirect.mp3/chtbl.com/track/ABC/http://cdn.example.com/a.mp3",
+                "http://cdn.example.com/a.mp3",
+            ),
+            (
+                "https://op3.dev/e,pg=1234-abcd/https://cdn.example.com/a.mp3",
+                "https://cdn.example.com/a.mp3",
+            ),
+            // Untracked URLs are left untouched
+            ("https://cdn.example.com/a.mp3", "https://cdn.example.com/a.mp3"),
+            // Prefix without anything host-like behind it
+            ("https://pdst.fm/e/", "https://pdst.fm/e/"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(strip_tracking_prefixes(input), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_factory_strips_tracking_prefixes_only_when_enabled() {
+        let item = ItemBuilder::default()
+            .title("Episode".to_string())
+            .guid(GuidBuilder::default().value("ep1".to_string()).build())
+            .enclosure(
+                EnclosureBuilder::default()
+                    .url("https://pdst.fm/e/cdn.example.com/ep1.mp3".to_string())
+                    .build(),
+            )
+            .build();
+        let channel = ChannelBuilder::default().title("Test".to_string()).items(vec![item]).build();
+        let url = "http://example.com/feed".to_string();
+
+        let stripping = PodcastFactory::new().with_tracking_prefix_stripping(true);
+        let podcast =
+            stripping.create_podcast(ParsedFeed { channel: channel.clone() }, url.clone()).unwrap();
+        assert_eq!(podcast.episodes()[0].audio_url(), "https://cdn.example.com/ep1.mp3");
+
+        let podcast = PodcastFactory::new().create_podcast(ParsedFeed { channel }, url).unwrap();
+        assert_eq!(podcast.episodes()[0].audio_url(), "https://pdst.fm/e/cdn.example.com/ep1.mp3");
+    }
+
+    #[test]
+    fn test_video_enclosures_are_marked_as_video() {
+        let item = |id: &str, url: &str, mime: &str| {
+            ItemBuilder::default()
+                .title(id.to_string())
+                .guid(GuidBuilder::default().value(id.to_string()).build())
+                .enclosure(
+                    EnclosureBuilder::default()
+                        .url(url.to_string())
+                        .mime_type(mime.to_string())
+                        .build(),
+                )
+                .build()
+        };
+        let channel = ChannelBuilder::default()
+            .title("Test".to_string())
+            .items(vec![
+                item("audio", "http://example.com/a.mp3", "audio/mpeg"),
+                item("video", "http://example.com/v.mp4", "video/mp4"),
+                item("untyped", "http://example.com/v.m4v?token=1", ""),
+            ])
+            .build();
+
+        let podcast = PodcastFactory::new()
+            .create_podcast(ParsedFeed { channel }, "http://example.com/feed".to_string())
+            .unwrap();
+        let episodes = podcast.episodes();
+
+        assert!(!episodes[0].is_video());
+        assert_eq!(episodes[1].mime_type(), Some("video/mp4"));
+        assert!(episodes[1].is_video());
+        assert_eq!(episodes[2].mime_type(), None);
+        assert!(episodes[2].is_video());
+    }
+
+    #[test]
+    fn test_blank_description_falls_back_to_content_encoded() {
+        let item = ItemBuilder::default()
+            .title("ep".to_string())
+            .guid(GuidBuilder::default().value("ep".to_string()).build())
+            .description("  \n ".to_string())
+            .content("<p>Full notes</p>".to_string())
+            .enclosure(
+                EnclosureBuilder::default().url("http://example.com/a.mp3".to_string()).build(),
+            )
+            .build();
+        let channel = ChannelBuilder::default().title("Test".to_string()).items(vec![item]).build();
+
+        let podcast = PodcastFactory::new()
+            .create_podcast(ParsedFeed { channel }, "http://example.com/feed".to_string())
+            .unwrap();
+        assert_eq!(podcast.episodes()[0].description(), Some("<p>Full notes</p>"));
+    }
+
+    #[test]
+    fn test_media_content_fallback_without_enclosure() {
+        let feed = r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:m="http://search.yahoo.com/mrss/">
+            <channel><title>Media</title><link>http://example.com</link><description>d</description>
+                <item>
+                    <title>Grouped</title><guid>grouped</guid>
+                    <m:group>
+                        <m:content url="http://example.com/thumb.jpg" type="image/jpeg"/>
+                        <m:content url="http://example.com/ep.mp3" type="audio/mpeg"
+                            fileSize="1234" duration="1800"/>
+                    </m:group>
+                </item>
+                <item>
+                    <title>Direct</title>
+                    <m:content url="http://example.com/ep2.mp4" medium="video"/>
+                </item>
+                <item><title>Nothing playable</title><guid>none</guid></item>
+            </channel></rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+
+        let podcast = PodcastFactory::new()
+            .create_podcast(ParsedFeed { channel }, "http://example.com/feed".to_string())
+            .unwrap();
+        let episodes = podcast.episodes();
+
+        assert_eq!(episodes.len(), 2);
+        assert_eq!(episodes[0].audio_url(), "http://example.com/ep.mp3");
+        assert_eq!(episodes[0].mime_type(), Some("audio/mpeg"));
+        assert_eq!(episodes[0].size_in_bytes(), Some(1234));
+        assert_eq!(episodes[0].duration(), Some("1800"));
+        // No guid or link: the media URL identifies the episode
+        assert_eq!(episodes[1].id().to_string(), "http://example.com/ep2.mp4");
+        assert!(episodes[1].is_video());
+    }
+
+    #[test]
+    fn test_episode_artwork_prefers_item_image() {
+        let feed = r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+            <channel><title>Art</title><link>http://example.com</link><description>d</description>
+                <image><url>http://example.com/show.jpg</url><title>Art</title><link>http://example.com</link></image>
+                <item>
+                    <title>Own art</title><guid>a</guid>
+                    <itunes:image href="http://example.com/a.jpg"/>
+                    <enclosure url="http://example.com/a.mp3" length="1" type="audio/mpeg"/>
+                </item>
+                <item>
+                    <title>Show art</title><guid>b</guid>
+                    <enclosure url="http://example.com/b.mp3" length="1" type="audio/mpeg"/>
+                </item>
+            </channel></rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+
+        let podcast = PodcastFactory::new()
+            .create_podcast(ParsedFeed { channel }, "http://example.com/feed".to_string())
+            .unwrap();
+        let episodes = podcast.episodes();
+
+        assert_eq!(episodes[0].image_url(), Some("http://example.com/a.jpg"));
+        assert_eq!(podcast.artwork_url(&episodes[0]), Some("http://example.com/a.jpg"));
+        assert_eq!(episodes[1].image_url(), None);
+        assert_eq!(podcast.artwork_url(&episodes[1]), Some("http://example.com/show.jpg"));
+    }
+
+    #[test]
+    fn test_episodes_are_deduplicated_by_guid() {
+        let feed = r#"<?xml version="1.0"?>
+            <rss version="2.0"><channel><title>D</title><link>http://example.com</link><description>d</description>
+                <item><title>New URL</title><guid>a</guid>
+                    <enclosure url="http://cdn2.example.com/a.mp3" length="1" type="audio/mpeg"/></item>
+                <item><title>Other</title><guid>b</guid>
+                    <enclosure url="http://example.com/b.mp3" length="1" type="audio/mpeg"/></item>
+                <item><title>Old URL</title><guid>a</guid>
+                    <enclosure url="http://example.com/a.mp3" length="1" type="audio/mpeg"/></item>
+            </channel></rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+
+        let podcast = PodcastFactory::new()
+            .with_episode_limit(2)
+            .create_podcast(ParsedFeed { channel }, "http://example.com/feed".to_string())
+            .unwrap();
+        let titles: Vec<&str> = podcast.episodes().iter().map(|e| e.title()).collect();
+        assert_eq!(titles, vec!["New URL", "Other"]);
+    }
+
+    #[test]
+    fn test_explicit_flag_falls_back_to_channel() {
+        let feed = r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+            <channel><title>E</title><link>http://example.com</link><description>d</description>
+                <itunes:explicit>Yes</itunes:explicit>
+                <item>
+                    <title>Inherited</title><guid>a</guid>
+                    <enclosure url="http://example.com/a.mp3" length="1" type="audio/mpeg"/>
+                </item>
+                <item>
+                    <title>Clean</title><guid>b</guid>
+                    <itunes:explicit>clean</itunes:explicit>
+                    <enclosure url="http://example.com/b.mp3" length="1" type="audio/mpeg"/>
+                </item>
+            </channel></rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+
+        let podcast = PodcastFactory::new()
+            .create_podcast(ParsedFeed { channel }, "http://example.com/feed".to_string())
+            .unwrap();
+
+        assert!(podcast.is_explicit());
+        assert!(podcast.episodes()[0].is_explicit());
+        assert!(!podcast.episodes()[1].is_explicit());
+        assert_eq!(parse_explicit(Some("false")), Some(false));
+        assert_eq!(parse_explicit(Some("maybe")), None);
+    }
+
+    #[test]
+    fn test_parse_pub_date_fallbacks() {
+        let expected = Utc.with_ymd_and_hms(2024, 3, 5, 10, 0, 0).unwrap();
+        for date in [
+            "Tue, 05 Mar 2024 10:00:00 +0000",
+            "Mon, 05 Mar 2024 10:00:00 +0000", // Wrong weekday
+            "Tuesday, 5 March 2024 10:00:00 GMT",
+            "Tue,  05 Mar 2024  10:00 +0000",
+            "2024-03-05T10:00:00Z",
+            "2024-03-05T12:00:00+02:00",
+            "2024-03-05T10:00:00.000+0000",
+            "2024-03-05 10:00:00",
+            "Tue, 05 Mar 2024 10:00:00 CEST", // Unknown zone name, read as UTC
+        ] {
+            assert_eq!(parse_pub_date(date), Some(expected), "{}", date);
+        }
+        assert_eq!(
+            parse_pub_date("2024-03-05"),
+            Utc.with_ymd_and_hms(2024, 3, 5, 0, 0, 0).single()
+        );
+        assert_eq!(parse_pub_date("yesterday"), None);
+        assert_eq!(parse_pub_date(""), None);
+    }
+
+    #[test]
+    fn test_unparsable_dates_are_unknown() {
+        let feed = r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+            <channel><title>D</title><link>http://example.com</link><description>d</description>
+                <item><title>Garbled</title><guid>a</guid><pubDate>soon</pubDate>
+                    <enclosure url="http://example.com/a.mp3" length="1" type="audio/mpeg"/></item>
+                <item><title>Dublin Core</title><guid>b</guid><dc:date>2024-03-05T10:00:00Z</dc:date>
+                    <enclosure url="http://example.com/b.mp3" length="1" type="audio/mpeg"/></item>
+            </channel></rss>"#;
+        let channel = Channel::read_from(feed.as_bytes()).unwrap();
+
+        let podcast = PodcastFactory::new()
+            .create_podcast(ParsedFeed { channel }, "http://example.com/feed".to_string())
+            .unwrap();
+        // Undated episodes sort last
+        assert_eq!(podcast.episodes()[1].published_date(), None);
+        assert_eq!(
+            podcast.episodes()[0].published_date(),
+            Utc.with_ymd_and_hms(2024, 3, 5, 10, 0, 0).single()
+        );
+    }
+
+    #[test]
+    fn test_episodes_are_sorted_by_date() {
+        let feed = r#"<?xml version="1.0"?>
+            <rss version="2.0"><channel><title>S</title><link>http://example.com</link><description>d</description>
+                <item><title>Middle</title><guid>b</guid><pubDate>Wed, 06 Mar 2024 10:00:00 +0000</pubDate>
+                    <enclosure url="http://example.com/b.mp3" length="1" type="audio/mpeg"/></item>
+                <item><title>Undated</title><guid>u</guid>
+                    <enclosure url="http://example.com/u.mp3" length="1" type="audio/mpeg"/></item>
+                <item><title>Oldest</title><guid>a</guid><pubDate>Tue, 05 Mar 2024 10:00:00 +0000</pubDate>
+                    <enclosure url="http://example.com/a.mp3" length="1" type="audio/mpeg"/></item>
+                <item><title>Newest 1</title><guid>c</guid><pubDate>Thu, 07 Mar 2024 10:00:00 +0000</pubDate>
+                    <enclosure url="http://example.com/c.mp3" length="1" type="audio/mpeg"/></item>
+                <item><title>Newest 2</title><guid>d</guid><pubDate>Thu, 07 Mar 2024 10:00:00 +0000</pubDate>
+                    <enclosure url="http://example.com/d.mp3" length="1" type="audio/mpeg"/></item>
+            </channel></rss>"#;
+        let titles = |factory: PodcastFactory| {
+            let channel = Channel::read_from(feed.as_bytes()).unwrap();
+            let podcast = factory
+                .create_podcast(ParsedFeed { channel }, "http://example.com/feed".to_string())
+                .unwrap();
+            podcast.episodes().iter().map(|e| e.title().to_string()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            titles(PodcastFactory::new()),
+            vec!["Newest 1", "Newest 2", "Middle", "Oldest", "Undated"]
+        );
+        assert_eq!(
+            titles(PodcastFactory::new().with_sort_order(EpisodeSortOrder::OldestFirst)),
+            vec!["Oldest", "Middle", "Newest 1", "Newest 2", "Undated"]
+        );
+        // The limit keeps the newest episodes, whatever the order
+        assert_eq!(
+            titles(
+                PodcastFactory::new()
+                    .with_episode_limit(2)
+                    .with_sort_order(EpisodeSortOrder::OldestFirst)
+            ),
+            vec!["Newest 1", "Newest 2"]
+        );
+    }
 }
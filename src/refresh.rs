@@ -0,0 +1,180 @@
+use crate::podcast::{Podcast, PodcastURL};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::{Duration, Instant};
+
+// Bounds for the adaptive interval: daily shows get checked a few times a day,
+// monthly ones still at least once a day
+pub const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+pub const MAX_REFRESH_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+// Check roughly this many times per typical gap between episodes
+const CHECKS_PER_EPISODE_GAP: u32 = 4;
+// Only the recent publish history says anything about the current cadence
+const CADENCE_SAMPLE_SIZE: usize = 10;
+// Each due time is pushed back by up to this fraction of the interval
+const JITTER_FRACTION: f64 = 0.1;
+
+// Derives how often a feed should be refreshed from the median gap between its recent episodes
+pub fn refresh_interval(podcast: &Podcast) -> Duration {
+    let mut dates: Vec<DateTime<Utc>> =
+        podcast.episodes().iter().filter_map(|e| e.published_date()).collect();
+    dates.sort_unstable_by(|a, b| b.cmp(a));
+    dates.truncate(CADENCE_SAMPLE_SIZE);
+
+    let mut gaps: Vec<Duration> =
+        dates.windows(2).filter_map(|pair| (pair[0] - pair[1]).to_std().ok()).collect();
+    if gaps.is_empty() {
+        return MAX_REFRESH_INTERVAL;
+    }
+    gaps.sort_unstable();
+    let median = gaps[gaps.len() / 2];
+    (median / CHECKS_PER_EPISODE_GAP).clamp(MIN_REFRESH_INTERVAL, MAX_REFRESH_INTERVAL)
+}
+
+// `fraction` is in [0, 1); the result lies in [interval, interval * (1 + JITTER_FRACTION))
+pub fn with_jitter(interval: Duration, fraction: f64) -> Duration {
+    interval + interval.mul_f64(JITTER_FRACTION * fraction.clamp(0.0, 1.0))
+}
+
+// Good enough randomness for spreading refreshes without pulling in `rand`
+fn random_fraction() -> f64 {
+    let bits = RandomState::new().hash_one(Instant::now());
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// How the last background refresh of a feed went, shown in the podcast info popup
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefreshOutcome {
+    pub at: DateTime<Utc>,
+    pub error: Option<String>,
+}
+
+// Tracks when each subscribed feed is next due for a refresh
+#[derive(Debug, Default)]
+pub struct RefreshSchedule {
+    next_due: HashMap<PodcastURL, Instant>,
+}
+
+impl RefreshSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn next_due(&self, url: &PodcastURL) -> Option<Instant> {
+        self.next_due.get(url).copied()
+    }
+
+    // Adds podcasts that aren't scheduled yet, counting from their last update.
+    // Jitter keeps a freshly started app from refreshing everything at once.
+    pub fn track_new(&mut self, podcasts: &[Podcast], now: Instant) {
+        let wall_now = Utc::now();
+        for podcast in podcasts {
+            if self.next_due.contains_key(podcast.url()) {
+                continue;
+            }
+            let interval = refresh_interval(podcast);
+            let since_update = (wall_now - podcast.last_updated()).to_std().unwrap_or_default();
+            let wait = with_jitter(interval.saturating_sub(since_update), random_fraction());
+            self.next_due.insert(podcast.url().clone(), now + wait);
+        }
+    }
+
+    // Called after a refresh attempt, successful or not
+    pub fn reschedule(&mut self, podcast: &Podcast, now: Instant) {
+        let wait = with_jitter(refresh_interval(podcast), random_fraction());
+        self.next_due.insert(podcast.url().clone(), now + wait);
+    }
+
+    // The server asked us to wait; check again once its cache window has passed
+    pub fn postpone_until(&mut self, url: &PodcastURL, until: Instant) {
+        self.next_due.insert(url.clone(), until);
+    }
+
+    // Returns the due feeds and unschedules them until `reschedule` is called,
+    // so a slow refresh isn't started twice
+    pub fn take_due(&mut self, now: Instant) -> Vec<PodcastURL> {
+        let due: Vec<PodcastURL> = self
+            .next_due
+            .iter()
+            .filter(|(_, at)| **at <= now)
+            .map(|(url, _)| url.clone())
+            .collect();
+        for url in &due {
+            self.next_due.remove(url);
+        }
+        due
+    }
+
+    // Forget feeds that are no longer subscribed
+    pub fn retain(&mut self, podcasts: &[Podcast]) {
+        self.next_due.retain(|url, _| podcasts.iter().any(|p| p.url() == url));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::podcast::{Episode, EpisodeID};
+
+    fn podcast_with_gap(url: &str, gap: chrono::Duration, count: i32) -> Podcast {
+        let now = Utc::now();
+        let episodes = (0..count)
+            .map(|i| {
+                Episode::new(
+                    EpisodeID::new(&i.to_string()),
+                    format!("Episode {}", i),
+                    None,
+                    now - gap * i,
+                    None,
+                    format!("http://example.com/{}.mp3", i),
+                    None,
+                )
+            })
+            .collect();
+        Podcast::new(PodcastURL::new(url), "Test".to_string(), None, None, None, episodes)
+    }
+
+    #[test]
+    fn test_interval_follows_publish_cadence() {
+        let daily = podcast_with_gap("http://a", chrono::Duration::days(1), 5);
+        assert_eq!(refresh_interval(&daily), Duration::from_secs(6 * 60 * 60));
+
+        let monthly = podcast_with_gap("http://b", chrono::Duration::days(30), 5);
+        assert_eq!(refresh_interval(&monthly), MAX_REFRESH_INTERVAL);
+
+        let hourly = podcast_with_gap("http://c", chrono::Duration::minutes(30), 5);
+        assert_eq!(refresh_interval(&hourly), MIN_REFRESH_INTERVAL);
+
+        let single = podcast_with_gap("http://d", chrono::Duration::days(1), 1);
+        assert_eq!(refresh_interval(&single), MAX_REFRESH_INTERVAL);
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let interval = Duration::from_secs(1000);
+        assert_eq!(with_jitter(interval, 0.0), interval);
+        assert_eq!(with_jitter(interval, 1.0), Duration::from_secs(1100));
+        assert_eq!(with_jitter(interval, 7.0), Duration::from_secs(1100));
+    }
+
+    #[test]
+    fn test_schedule_takes_due_feeds_once() {
+        let podcasts = vec![
+            podcast_with_gap("http://a", chrono::Duration::days(1), 5),
+            podcast_with_gap("http://b", chrono::Duration::days(30), 5),
+        ];
+        let start = Instant::now();
+        let mut schedule = RefreshSchedule::new();
+        schedule.track_new(&podcasts, start);
+
+        assert!(schedule.take_due(start).is_empty());
+        let after_daily = start + Duration::from_secs(7 * 60 * 60); // 6h + at most 10% jitter
+        assert_eq!(schedule.take_due(after_daily), vec![PodcastURL::new("http://a")]);
+        assert!(schedule.take_due(after_daily).is_empty());
+
+        schedule.reschedule(&podcasts[0], after_daily);
+        assert!(schedule.next_due(podcasts[0].url()).unwrap() > after_daily);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::config::Config;
+use crate::errors::SearchError;
+use crate::podcast::{PodcastURL, language_matches};
+use crate::podcast_download::FeedFetcher;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+// A podcast found through a discovery service, not yet subscribed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub title: String,
+    pub feed_url: PodcastURL,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub website_url: Option<String>,
+    pub image_url: Option<String>,
+    pub language: Option<String>,
+}
+
+impl SearchResult {
+    // Results without a language never match, so a filter only keeps what is known to fit
+    pub fn matches_language(&self, filter: &str) -> bool {
+        language_matches(self.language.as_deref(), filter)
+    }
+}
+
+#[async_trait]
+pub trait SearchProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchResult>, SearchError>;
+}
+
+// Discovery services that can be selected with "search_provider" in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SearchProviderName {
+    #[default]
+    Fyyd,
+}
+
+pub fn provider_from_config(
+    config: &Config,
+    fetcher: Arc<dyn FeedFetcher + Send + Sync>,
+) -> Box<dyn SearchProvider> {
+    match config.search_provider {
+        SearchProviderName::Fyyd => Box::new(FyydSearchProvider::new(fetcher)),
+    }
+}
+
+// ===== fyyd.de: no API key needed, good European coverage
+const FYYD_API_URL: &str = "https://api.fyyd.de/0.2/search/podcast";
+const FYYD_RESULT_COUNT: usize = 25;
+
+pub struct FyydSearchProvider {
+    fetcher: Arc<dyn FeedFetcher + Send + Sync>,
+    api_url: String,
+}
+
+impl FyydSearchProvider {
+    pub fn new(fetcher: Arc<dyn FeedFetcher + Send + Sync>) -> Self {
+        Self { fetcher, api_url: FYYD_API_URL.to_string() }
+    }
+
+    // Builder method, for mirrors or tests
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.to_string();
+        self
+    }
+
+    fn search_url(&self, query: &str) -> Result<String, SearchError> {
+        let count = FYYD_RESULT_COUNT.to_string();
+        reqwest::Url::parse_with_params(&self.api_url, [("term", query), ("count", &count)])
+            .map(String::from)
+            .map_err(|e| SearchError::InvalidResponse(format!("Invalid search URL: {}", e)))
+    }
+}
+
+#[async_trait]
+impl SearchProvider for FyydSearchProvider {
+    fn name(&self) -> &'static str {
+        "fyyd.de"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchResult>, SearchError> {
+        let url = self.search_url(query)?;
+        let body = self.fetcher.fetch(&url).await?;
+        parse_fyyd_response(&body)
+    }
+}
+
+#[derive(Deserialize)]
+struct FyydResponse {
+    #[serde(default)]
+    status: i64,
+    #[serde(default)]
+    msg: Option<String>,
+    #[serde(default)]
+    data: Vec<FyydPodcast>,
+}
+
+#[derive(Deserialize)]
+struct FyydPodcast {
+    title: Option<String>,
+    #[serde(rename = "xmlURL")]
+    xml_url: Option<String>,
+    #[serde(rename = "htmlURL")]
+    html_url: Option<String>,
+    #[serde(rename = "imgURL")]
+    img_url: Option<String>,
+    author: Option<String>,
+    description: Option<String>,
+    language: Option<String>,
+}
+
+fn parse_fyyd_response(body: &str) -> Result<Vec<SearchResult>, SearchError> {
+    let response: FyydResponse = serde_json::from_str(body)
+        .map_err(|e| SearchError::InvalidResponse(format!("fyyd.de: {}", e)))?;
+    if response.status != 1 {
+        return Err(SearchError::InvalidResponse(format!(
+            "fyyd.de returned status {}: {}",
+            response.status,
+            response.msg.unwrap_or_default()
+        )));
+    }
+
+    let non_empty = |s: Option<String>| s.filter(|s| !s.trim().is_empty());
+    Ok(response
+        .data
+        .into_iter()
+        .filter_map(|p| {
+            // Entries without a feed URL can't be subscribed to
+            let feed_url = non_empty(p.xml_url)?;
+            Some(SearchResult {
+                title: non_empty(p.title).unwrap_or_else(|| feed_url.clone()),
+                feed_url: PodcastURL::new(&feed_url),
+                author: non_empty(p.author),
+                description: non_empty(p.description),
+                website_url: non_empty(p.html_url),
+                image_url: non_empty(p.img_url),
+                language: non_empty(p.language),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::podcast_download::FakeFetcher;
+
+    const FYYD_RESPONSE: &str = r#"{
+        "status": 1,
+        "msg": "ok",
+        "meta": { "paging": { "count": 2, "page": 0 } },
+        "data": [
+            {
+                "title": "Rust Talk",
+                "xmlURL": "https://example.com/rust.xml",
+                "htmlURL": "https://example.com",
+                "imgURL": "https://example.com/rust.jpg",
+                "author": "Ferris",
+                "description": "All about Rust",
+                "language": "en"
+            },
+            { "title": "No Feed", "xmlURL": "" }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_fyyd_response() {
+        let results = parse_fyyd_response(FYYD_RESPONSE).unwrap();
+        assert_eq!(results.len(), 1); // Entry without feed URL is skipped
+        let result = &results[0];
+        assert_eq!(result.title, "Rust Talk");
+        assert_eq!(result.feed_url, PodcastURL::new("https://example.com/rust.xml"));
+        assert_eq!(result.author.as_deref(), Some("Ferris"));
+        assert_eq!(result.language.as_deref(), Some("en"));
+        assert!(result.matches_language("EN"));
+        assert!(!result.matches_language("en-gb"));
+        assert!(!result.matches_language("e"));
+    }
+
+    #[test]
+    fn test_parse_fyyd_error_status() {
+        let result = parse_fyyd_response(r#"{ "status": 0, "msg": "rate limited", "data": [] }"#);
+        assert!(
+            matches!(result, Err(SearchError::InvalidResponse(msg)) if msg.contains("rate limited"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fyyd_search_queries_api() {
+        let url = "https://api.fyyd.de/0.2/search/podcast?term=rust+talk&count=25";
+        let fetcher = Arc::new(FakeFetcher::new("").with_response(url, FYYD_RESPONSE));
+        let provider = FyydSearchProvider::new(fetcher);
+
+        let results = provider.search("rust talk").await.unwrap();
+        assert_eq!(results[0].title, "Rust Talk");
+    }
+}
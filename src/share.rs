@@ -0,0 +1,80 @@
+// Text for telling someone about an episode, and getting it onto the clipboard
+use crate::podcast::{Episode, Podcast};
+use crate::ui::format_duration;
+use std::time::Duration;
+
+// "Title" from Show (at 12:34), followed by the best link we have: the episode's web page,
+// else the audio file
+pub fn share_text(podcast: &Podcast, episode: &Episode, position: Option<Duration>) -> String {
+    let at = position.map_or(String::new(), |p| format!(" (at {})", format_duration(p)));
+    let link = episode.link().unwrap_or(episode.audio_url());
+    format!("\"{}\" from {}{}\n{}", episode.title(), podcast.title(), at, link)
+}
+
+// OSC 52 asks the terminal to put `text` on the system clipboard. It works over SSH and
+// without a clipboard daemon; terminals that don't support it ignore the sequence.
+pub fn osc52_copy(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::podcast::{EpisodeID, PodcastURL};
+    use chrono::Utc;
+
+    #[test]
+    fn test_share_text() {
+        let episode = Episode::new(
+            EpisodeID::new("ep1"),
+            "Borrowing".to_string(),
+            None,
+            Utc::now(),
+            None,
+            "http://example.com/ep1.mp3".to_string(),
+            None,
+        );
+        let podcast = Podcast::new(
+            PodcastURL::new("http://example.com/feed"),
+            "Rust Daily".to_string(),
+            None,
+            None,
+            None,
+            vec![],
+        );
+        assert_eq!(
+            share_text(&podcast, &episode, None),
+            "\"Borrowing\" from Rust Daily\nhttp://example.com/ep1.mp3"
+        );
+        let episode = episode.with_link(Some("http://example.com/ep1".to_string()));
+        assert_eq!(
+            share_text(&podcast, &episode, Some(Duration::from_secs(3725))),
+            "\"Borrowing\" from Rust Daily (at 1:02:05)\nhttp://example.com/ep1"
+        );
+    }
+
+    #[test]
+    fn test_osc52_encoding() {
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+        assert_eq!(osc52_copy("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
@@ -0,0 +1,275 @@
+// Turns episode descriptions (usually HTML) into plain, pre-wrapped lines for the Show Notes panel
+
+// Tags that start a new line when rendered as text
+const BLOCK_TAGS: &[&str] =
+    &["p", "br", "div", "ul", "ol", "tr", "h1", "h2", "h3", "h4", "h5", "h6", "blockquote", "hr"];
+// Tags whose content is never shown
+const HIDDEN_TAGS: &[&str] = &["style", "script", "head", "title"];
+// How many layers of entity-encoded HTML ("&amp;lt;p&amp;gt;") get unwrapped
+const MAX_ENCODING_DEPTH: usize = 3;
+
+pub fn format_episode_description(description: &str, width: usize) -> Vec<String> {
+    let description = strip_cdata_markers(description);
+    let text = html_to_text(&unwrap_nested_encoding(&description));
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let paragraph = paragraph.trim();
+        if paragraph.is_empty() {
+            // Collapse runs of blank lines into one
+            if lines.last().is_some_and(|l: &String| !l.is_empty()) {
+                lines.push(String::new());
+            }
+            continue;
+        }
+        lines.extend(wrap(paragraph, width));
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+// CDATA sections that leaked into the text (double-wrapped in the feed)
+fn strip_cdata_markers(description: &str) -> String {
+    description.replace("<![CDATA[", "").replace("]]>", "")
+}
+
+// Some feeds escape their HTML once too often, so the parsed description contains
+// "&lt;p&gt;" instead of "<p>". Decode until real markup shows up.
+fn unwrap_nested_encoding(description: &str) -> String {
+    let mut current = description.to_string();
+    for _ in 0..MAX_ENCODING_DEPTH {
+        if contains_markup(&current) {
+            return current;
+        }
+        let decoded = decode_entities(&current);
+        if decoded == current {
+            break;
+        }
+        current = decoded;
+    }
+    // Plain text that merely mentions "&lt;" stays as it was
+    if contains_markup(&current) { current } else { description.to_string() }
+}
+
+// A '<' that starts a tag, closing tag or comment rather than e.g. "a < b"
+fn contains_markup(text: &str) -> bool {
+    text.match_indices('<').any(|(i, _)| {
+        text[i + 1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '/' || c == '!')
+    })
+}
+
+fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(tag_start) = rest.find('<') {
+        push_text(&mut text, &rest[..tag_start]);
+        if let Some(comment) = rest[tag_start..].strip_prefix("<!--") {
+            rest = comment.split_once("-->").map_or("", |(_, after)| after);
+            continue;
+        }
+        let Some(tag_len) = rest[tag_start..].find('>') else {
+            // Unterminated '<' is just text
+            push_text(&mut text, &rest[tag_start..]);
+            rest = "";
+            break;
+        };
+        let tag = &rest[tag_start + 1..tag_start + tag_len];
+        rest = &rest[tag_start + tag_len + 1..];
+
+        let name = tag_name(tag);
+        if !tag.starts_with('/') && HIDDEN_TAGS.contains(&name.as_str()) {
+            rest = skip_past_closing_tag(rest, &name);
+            continue;
+        }
+        push_tag(&mut text, tag, &name);
+    }
+    push_text(&mut text, rest);
+    text
+}
+
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+// Everything after `</name>`, or nothing if the element is never closed
+fn skip_past_closing_tag<'a>(html: &'a str, name: &str) -> &'a str {
+    let closing = format!("</{}", name);
+    let lowercase = html.to_ascii_lowercase(); // Same byte offsets as `html`
+    lowercase
+        .find(&closing)
+        .and_then(|start| html[start..].find('>').map(|end| &html[start + end + 1..]))
+        .unwrap_or("")
+}
+
+fn push_tag(text: &mut String, tag: &str, name: &str) {
+    if name == "li" {
+        if !tag.starts_with('/') {
+            text.push_str("\n• ");
+        }
+    } else if BLOCK_TAGS.contains(&name) {
+        text.push('\n');
+    }
+}
+
+// Appends a text node, collapsing whitespace the way a browser would
+fn push_text(text: &mut String, raw: &str) {
+    let decoded = decode_entities(raw);
+    for c in decoded.chars() {
+        if c.is_whitespace() && c != '\u{a0}' {
+            if !text.ends_with([' ', '\n']) && !text.is_empty() {
+                text.push(' ');
+            }
+        } else if c == '\u{a0}' {
+            text.push(' ');
+        } else {
+            text.push(c);
+        }
+    }
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" | "#39" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// Greedy word wrap; words longer than the line are split
+fn wrap(paragraph: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_len = 0;
+
+    for word in paragraph.split(' ').filter(|w| !w.is_empty()) {
+        let mut word: Vec<char> = word.chars().collect();
+        let sep = usize::from(line_len > 0);
+        if line_len + sep + word.len() <= width {
+            if sep == 1 {
+                line.push(' ');
+            }
+            line.extend(word.iter());
+            line_len += sep + word.len();
+            continue;
+        }
+        if line_len > 0 {
+            lines.push(std::mem::take(&mut line));
+        }
+        while word.len() > width {
+            lines.push(word.drain(..width).collect());
+        }
+        line = word.iter().collect();
+        line_len = word.len();
+    }
+    if line_len > 0 {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_tags_and_keeps_paragraphs() {
+        let lines = format_episode_description("<p>Hello <b>world</b></p><p>Second</p>", 40);
+        assert_eq!(lines, vec!["Hello world", "", "Second"]);
+    }
+
+    #[test]
+    fn test_decodes_entities() {
+        let lines = format_episode_description("Tom &amp; Jerry &lt;3 &#8212; &#x41;&nbsp;B", 40);
+        assert_eq!(lines, vec!["Tom & Jerry <3 — A B"]);
+    }
+
+    #[test]
+    fn test_list_items_become_bullets() {
+        let lines = format_episode_description("<ul><li>One</li><li>Two</li></ul>", 40);
+        assert_eq!(lines, vec!["• One", "• Two"]);
+    }
+
+    #[test]
+    fn test_wraps_to_width() {
+        let lines = format_episode_description("aaa bbb ccc dddddddddd", 7);
+        assert_eq!(lines, vec!["aaa bbb", "ccc", "ddddddd", "ddd"]);
+    }
+
+    #[test]
+    fn test_unwraps_double_encoded_html() {
+        let lines = format_episode_description(
+            "&lt;p&gt;Tom &amp;amp; Jerry&lt;/p&gt;&lt;p&gt;Two&lt;/p&gt;",
+            40,
+        );
+        assert_eq!(lines, vec!["Tom & Jerry", "", "Two"]);
+
+        let lines = format_episode_description("&amp;lt;b&amp;gt;Bold&amp;lt;/b&amp;gt;", 40);
+        assert_eq!(lines, vec!["Bold"]);
+
+        // An escaped comparison in real markup stays escaped
+        let lines = format_episode_description("<p>1 &lt; 2</p>", 40);
+        assert_eq!(lines, vec!["1 < 2"]);
+    }
+
+    #[test]
+    fn test_strips_style_script_comments_and_cdata() {
+        let html = "<![CDATA[<style type=\"text/css\">p { color: red; }</style>\
+            <p style=\"margin:0\">Intro<!-- tracking --></p><SCRIPT>alert('x')</SCRIPT>Outro]]>";
+        let lines = format_episode_description(html, 40);
+        assert_eq!(lines, vec!["Intro", "Outro"]);
+    }
+
+    #[test]
+    fn test_br_only_formatting() {
+        let lines = format_episode_description("Line one<br>Line two<br/><br />Next paragraph", 40);
+        assert_eq!(lines, vec!["Line one", "Line two", "", "Next paragraph"]);
+    }
+
+    #[test]
+    fn test_plain_text_is_left_alone() {
+        let lines = format_episode_description("Just text with a stray < and & sign", 80);
+        assert_eq!(lines, vec!["Just text with a stray < and & sign"]);
+    }
+}
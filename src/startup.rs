@@ -0,0 +1,100 @@
+// What the binary sets up between reading the config and starting the UI, so tests start
+// the app the same way. Logging and the single-instance socket stay in main.
+use crate::app::App;
+use crate::backup::Backups;
+use crate::config::Config;
+use crate::data_lock::DataLock;
+use crate::episode_state::EpisodeStateStore;
+use crate::errors::DataLockError;
+use crate::podcast_download::FeedFetcher;
+use crate::trash::Trash;
+use chrono::Utc;
+use std::path::Path;
+use std::sync::Arc;
+
+pub struct Startup {
+    pub app: App,
+    pub data_lock: Option<DataLock>, // Keep alive while the app runs
+}
+
+// `data_dir` is the profile's data directory (None without a home directory). When another
+// instance holds it, the app runs read-only: no state file writes, trash or backups.
+pub fn prepare_app(
+    config: Config,
+    data_dir: Option<&Path>,
+    first_run: bool,
+    fetcher: Arc<dyn FeedFetcher + Send + Sync>,
+) -> anyhow::Result<Startup> {
+    let mut read_only = None;
+    let data_lock = match data_dir.map(DataLock::acquire) {
+        Some(Ok(lock)) => Some(lock),
+        Some(Err(e @ DataLockError::Held { .. })) => {
+            log::warn!("{}, starting read-only", e);
+            read_only = Some(e.to_string());
+            None
+        }
+        Some(Err(e)) => return Err(e.into()),
+        None => None,
+    };
+    let writable_dir = data_dir.filter(|_| read_only.is_none());
+
+    // Starred episodes and other per-episode user state
+    let episode_states = match data_dir.map(|dir| dir.join("state.json")) {
+        Some(path) if read_only.is_some() => EpisodeStateStore::load(&path)?.without_saving(),
+        Some(path) => EpisodeStateStore::load(&path)?,
+        None => EpisodeStateStore::new(),
+    };
+    // Unsubscribed podcasts are kept for a while so they can be restored
+    let trash = writable_dir.map(|dir| Trash::new(dir.join("trash")));
+    let retention = chrono::Duration::days(config.trash_retention_days as i64);
+    if let Some(trash) = &trash
+        && let Err(e) = trash.purge_older_than(retention, Utc::now())
+    {
+        log::warn!("Could not purge old podcasts from the trash: {}", e);
+    }
+    // Snapshots of the data directory taken before bulk operations
+    let backups = writable_dir.map(|dir| {
+        Backups::new(dir.to_path_buf(), dir.join("backups")).with_excluded(config.download_dir())
+    });
+
+    let mut app = App::with_config(config)
+        .with_fetcher(fetcher)
+        .with_episode_states(episode_states)
+        .with_welcome(first_run)
+        .with_read_only(read_only);
+    if let Some(trash) = trash {
+        app = app.with_trash(trash);
+    }
+    if let Some(backups) = backups {
+        app = app.with_backups(backups);
+    }
+    if let Some(dir) = writable_dir {
+        app = app.with_save_dir(dir.to_path_buf());
+    }
+    Ok(Startup { app, data_lock })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::podcast_download::FakeFetcher;
+
+    #[test]
+    fn test_first_start_shows_the_welcome_screen() {
+        let dir = std::env::temp_dir().join(format!("rustero-startup-{}", std::process::id()));
+        let fetcher = || Arc::new(FakeFetcher::new(""));
+
+        let first = prepare_app(Config::default(), Some(&dir), true, fetcher()).unwrap();
+        assert!(first.app.podcasts.is_empty());
+        assert!(first.app.showing_welcome());
+        assert!(!first.app.is_read_only());
+
+        // A second instance on the same data directory doesn't get to write it
+        let second = prepare_app(Config::default(), Some(&dir), false, fetcher()).unwrap();
+        assert!(second.app.is_read_only());
+        assert!(!second.app.showing_welcome());
+
+        drop(first);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
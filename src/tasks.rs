@@ -0,0 +1,216 @@
+use std::time::{Duration, Instant};
+
+// Finished tasks kept around for the task panel, oldest dropped first
+const MAX_RECENT_TASKS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskId(u64);
+
+// What a background task is doing, for grouping in the activity indicator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TaskKind {
+    Subscribe,
+    Refresh,
+    Download,
+}
+
+impl TaskKind {
+    pub fn verb(&self) -> &'static str {
+        match self {
+            TaskKind::Subscribe => "subscribing",
+            TaskKind::Refresh => "refreshing",
+            TaskKind::Download => "downloading",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskStatus {
+    Running,
+    Done,
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
+    pub id: TaskId,
+    pub kind: TaskKind,
+    pub label: String, // Usually the feed URL
+    pub status: TaskStatus,
+    pub started: Instant,
+    pub finished: Option<Instant>,
+    pub progress: Option<(u64, Option<u64>)>, // (done, total) for tasks that report it
+}
+
+impl TaskInfo {
+    pub fn is_running(&self) -> bool {
+        self.status == TaskStatus::Running
+    }
+
+    // 0.0..=1.0, when the total is known
+    pub fn ratio(&self) -> Option<f64> {
+        match self.progress? {
+            (done, Some(total)) if total > 0 => Some((done as f64 / total as f64).min(1.0)),
+            _ => None,
+        }
+    }
+
+    // Time spent so far, or in total once finished
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        self.finished.unwrap_or(now).saturating_duration_since(self.started)
+    }
+}
+
+// Running and recently finished background tasks, fed by
+// `AppEvent::TaskStarted` / `AppEvent::TaskFinished`
+#[derive(Debug, Default)]
+pub struct TaskTracker {
+    next_id: u64,
+    tasks: Vec<TaskInfo>, // In start order
+}
+
+impl TaskTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Ids are handed out before the task is spawned so both lifecycle events can carry it
+    pub fn next_id(&mut self) -> TaskId {
+        self.next_id += 1;
+        TaskId(self.next_id)
+    }
+
+    pub fn started(&mut self, id: TaskId, kind: TaskKind, label: String) {
+        self.tasks.push(TaskInfo {
+            id,
+            kind,
+            label,
+            status: TaskStatus::Running,
+            started: Instant::now(),
+            finished: None,
+            progress: None,
+        });
+    }
+
+    pub fn progress(&mut self, id: TaskId, done: u64, total: Option<u64>) {
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id && t.is_running()) {
+            task.progress = Some((done, total));
+        }
+    }
+
+    pub fn get(&self, id: TaskId) -> Option<&TaskInfo> {
+        self.tasks.iter().find(|t| t.id == id)
+    }
+
+    pub fn finished(&mut self, id: TaskId, error: Option<String>) {
+        self.set_status(id, error.map_or(TaskStatus::Done, TaskStatus::Failed));
+    }
+
+    pub fn cancelled(&mut self, id: TaskId) {
+        self.set_status(id, TaskStatus::Cancelled);
+    }
+
+    // Only running tasks change state; a late "finished" after a cancel is ignored
+    fn set_status(&mut self, id: TaskId, status: TaskStatus) {
+        let Some(task) = self.tasks.iter_mut().find(|t| t.id == id && t.is_running()) else {
+            return;
+        };
+        task.status = status;
+        task.finished = Some(Instant::now());
+        self.prune();
+    }
+
+    fn prune(&mut self) {
+        let finished = self.tasks.iter().filter(|t| !t.is_running()).count();
+        let mut excess = finished.saturating_sub(MAX_RECENT_TASKS);
+        self.tasks.retain(|t| {
+            let drop = excess > 0 && !t.is_running();
+            excess -= usize::from(drop);
+            !drop
+        });
+    }
+
+    // Running tasks first (oldest on top), then finished ones (most recently started on top)
+    pub fn list(&self) -> Vec<&TaskInfo> {
+        let running = self.tasks.iter().filter(|t| t.is_running());
+        let finished = self.tasks.iter().rev().filter(|t| !t.is_running());
+        running.chain(finished).collect()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.tasks.iter().any(|t| t.is_running())
+    }
+
+    pub fn is_running_for(&self, kind: TaskKind, label: &str) -> bool {
+        self.tasks.iter().any(|t| t.is_running() && t.kind == kind && t.label == label)
+    }
+
+    pub fn running_count(&self, kind: TaskKind) -> usize {
+        self.tasks.iter().filter(|t| t.is_running() && t.kind == kind).count()
+    }
+
+    // Running tasks per kind, in a stable order and without empty kinds
+    pub fn summary(&self) -> Vec<(TaskKind, usize)> {
+        [TaskKind::Subscribe, TaskKind::Refresh, TaskKind::Download]
+            .into_iter()
+            .map(|kind| (kind, self.running_count(kind)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_follow_lifecycle() {
+        let mut tasks = TaskTracker::new();
+        assert!(!tasks.is_busy());
+
+        let a = tasks.next_id();
+        let b = tasks.next_id();
+        let c = tasks.next_id();
+        assert_ne!(a, b);
+        tasks.started(a, TaskKind::Refresh, "a".to_string());
+        tasks.started(b, TaskKind::Refresh, "b".to_string());
+        tasks.started(c, TaskKind::Subscribe, "c".to_string());
+        assert_eq!(tasks.summary(), vec![(TaskKind::Subscribe, 1), (TaskKind::Refresh, 2)]);
+
+        tasks.finished(c, None);
+        tasks.finished(a, Some("timeout".to_string()));
+        assert_eq!(tasks.summary(), vec![(TaskKind::Refresh, 1)]);
+        // Finishing twice (or an unknown id) is harmless
+        tasks.finished(a, None);
+        tasks.cancelled(b);
+        assert!(!tasks.is_busy());
+
+        let statuses: Vec<_> = tasks.list().iter().map(|t| t.status.clone()).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                TaskStatus::Done, // c, started last
+                TaskStatus::Cancelled,
+                TaskStatus::Failed("timeout".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keeps_running_tasks_and_recent_history() {
+        let mut tasks = TaskTracker::new();
+        let running = tasks.next_id();
+        tasks.started(running, TaskKind::Refresh, "slow".to_string());
+        for i in 0..MAX_RECENT_TASKS + 5 {
+            let id = tasks.next_id();
+            tasks.started(id, TaskKind::Refresh, i.to_string());
+            tasks.finished(id, None);
+        }
+
+        let list = tasks.list();
+        assert_eq!(list.len(), MAX_RECENT_TASKS + 1);
+        assert_eq!(list[0].label, "slow");
+        assert_eq!(list[1].label, (MAX_RECENT_TASKS + 4).to_string()); // Newest finished
+    }
+}
@@ -0,0 +1,141 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+
+// Built-in themes, selected by name in the config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    HighContrast, // Bright, bold colors on black for low-vision users
+    Monochrome,   // No colors at all, only modifiers; safe on 16-color/limited terminals
+}
+
+// All styles the UI uses, so panels never hard-code colors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub base: Style,          // Panel blocks and regular text
+    pub item: Style,          // Unselected list entries
+    pub selected_item: Style, // Selected list entries
+    pub highlight: Style,     // ratatui list highlight
+    pub player_block: Style,
+    pub player_text: Style,
+    pub player_gauge: Style, // fg = elapsed part of the progress bar, bg = remaining part
+    pub focused_border: Style, // Border of the panel receiving navigation keys
+    pub search_match: Style,
+    pub search_current_match: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::from_name(ThemeName::Default)
+    }
+}
+
+impl Theme {
+    pub fn from_name(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Self::default_theme(),
+            ThemeName::HighContrast => Self::high_contrast(),
+            ThemeName::Monochrome => Self::monochrome(),
+        }
+    }
+
+    fn default_theme() -> Self {
+        Self {
+            base: Style::default().fg(Color::White),
+            item: Style::default().fg(Color::White),
+            selected_item: Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            highlight: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            player_block: Style::default().fg(Color::Green),
+            player_text: Style::default().fg(Color::LightGreen),
+            player_gauge: Style::default().fg(Color::Green).bg(Color::DarkGray),
+            focused_border: Style::default().fg(Color::Cyan),
+            search_match: Style::default().fg(Color::Black).bg(Color::Yellow),
+            search_current_match: Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+
+    fn high_contrast() -> Self {
+        let base = Style::default().fg(Color::White).bg(Color::Black);
+        Self {
+            base,
+            item: base,
+            selected_item: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            highlight: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            player_block: base.add_modifier(Modifier::BOLD),
+            player_text: Style::default()
+                .fg(Color::Yellow)
+                .bg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+            player_gauge: Style::default().fg(Color::Yellow).bg(Color::Black),
+            focused_border: Style::default()
+                .fg(Color::Yellow)
+                .bg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+            search_match: Style::default().fg(Color::Black).bg(Color::White),
+            search_current_match: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+
+    fn monochrome() -> Self {
+        // Color::Reset keeps the terminal's own foreground/background
+        let base = Style::default().fg(Color::Reset).bg(Color::Reset);
+        Self {
+            base,
+            item: base,
+            selected_item: base.add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            highlight: base.add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            player_block: base,
+            player_text: base.add_modifier(Modifier::BOLD),
+            player_gauge: base,
+            focused_border: base.add_modifier(Modifier::BOLD),
+            search_match: base.add_modifier(Modifier::UNDERLINED),
+            search_current_match: base.add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monochrome_uses_no_colors() {
+        let theme = Theme::from_name(ThemeName::Monochrome);
+        for style in [
+            theme.base,
+            theme.item,
+            theme.selected_item,
+            theme.highlight,
+            theme.player_block,
+            theme.player_text,
+            theme.player_gauge,
+            theme.focused_border,
+            theme.search_match,
+            theme.search_current_match,
+        ] {
+            assert!(matches!(style.fg, Some(Color::Reset)));
+            assert!(matches!(style.bg, Some(Color::Reset)));
+        }
+        assert!(theme.selected_item.add_modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_theme_name_from_config_string() {
+        let name: ThemeName = serde_json::from_str("\"high-contrast\"").unwrap();
+        assert_eq!(name, ThemeName::HighContrast);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::episode_download::sanitize_file_name;
+use crate::errors::TrashError;
+use crate::podcast::Podcast;
+use chrono::{DateTime, Duration, Utc};
+use std::path::{Path, PathBuf};
+
+// A podcast waiting in the trash, newest first in `Trash::entries`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashEntry {
+    pub path: PathBuf,
+    pub title: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+// Unsubscribed podcasts, kept as JSON for a while so an unsubscribe can be undone.
+// Files are named "<unix timestamp>-<title>.json", which is all `entries` needs to read.
+#[derive(Debug, Clone)]
+pub struct Trash {
+    dir: PathBuf,
+}
+
+impl Trash {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn put(&self, podcast: &Podcast, now: DateTime<Utc>) -> Result<PathBuf, TrashError> {
+        let io_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| TrashError::Io { path, source }
+        };
+        std::fs::create_dir_all(&self.dir).map_err(io_error(&self.dir))?;
+        let path = self.dir.join(format!(
+            "{}-{}.json",
+            now.timestamp(),
+            sanitize_file_name(podcast.title())
+        ));
+        let json = serde_json::to_string_pretty(podcast)
+            .map_err(|source| TrashError::Parse { path: path.clone(), source })?;
+        std::fs::write(&path, json).map_err(io_error(&path))?;
+        Ok(path)
+    }
+
+    // A missing trash directory is an empty trash; files that don't look like ours are skipped
+    pub fn entries(&self) -> Result<Vec<TrashEntry>, TrashError> {
+        let dir = match std::fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => return Err(TrashError::Io { path: self.dir.clone(), source }),
+        };
+        let mut entries: Vec<TrashEntry> =
+            dir.filter_map(|entry| parse_entry(entry.ok()?.path())).collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+        Ok(entries)
+    }
+
+    // Reads the podcast back and removes it from the trash
+    pub fn restore(&self, entry: &TrashEntry) -> Result<Podcast, TrashError> {
+        let podcast = self.read(entry)?;
+        self.remove(entry)?;
+        Ok(podcast)
+    }
+
+    // Reads the podcast back, leaving it in the trash
+    pub fn read(&self, entry: &TrashEntry) -> Result<Podcast, TrashError> {
+        let path = &entry.path;
+        let content = std::fs::read_to_string(path)
+            .map_err(|source| TrashError::Io { path: path.clone(), source })?;
+        serde_json::from_str(&content)
+            .map_err(|source| TrashError::Parse { path: path.clone(), source })
+    }
+
+    pub fn remove(&self, entry: &TrashEntry) -> Result<(), TrashError> {
+        let path = &entry.path;
+        std::fs::remove_file(path).map_err(|source| TrashError::Io { path: path.clone(), source })
+    }
+
+    // Deletes entries older than `max_age`, returning how many went
+    pub fn purge_older_than(
+        &self,
+        max_age: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<usize, TrashError> {
+        let mut purged = 0;
+        for entry in self.entries()?.into_iter().filter(|e| now - e.deleted_at > max_age) {
+            std::fs::remove_file(&entry.path)
+                .map_err(|source| TrashError::Io { path: entry.path.clone(), source })?;
+            purged += 1;
+        }
+        Ok(purged)
+    }
+}
+
+fn parse_entry(path: PathBuf) -> Option<TrashEntry> {
+    let stem = path.file_name()?.to_str()?.strip_suffix(".json")?;
+    let (timestamp, title) = stem.split_once('-')?;
+    let deleted_at = DateTime::from_timestamp(timestamp.parse().ok()?, 0)?;
+    let title = title.to_string();
+    Some(TrashEntry { path, title, deleted_at })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::podcast::PodcastURL;
+
+    #[test]
+    fn test_put_restore_and_purge() {
+        let dir = std::env::temp_dir().join(format!("rustero-trash-{}", std::process::id()));
+        let trash = Trash::new(dir.clone());
+        let podcast = |title: &str| {
+            let url = PodcastURL::new(&format!("http://example.com/{}", title));
+            Podcast::new(url, title.to_string(), None, None, None, vec![])
+        };
+        let now = Utc::now();
+        trash.put(&podcast("Old"), now - Duration::days(40)).unwrap();
+        trash.put(&podcast("New/Show"), now).unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a podcast").unwrap();
+
+        let entries = trash.entries().unwrap();
+        let titles: Vec<&str> = entries.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["New_Show", "Old"]);
+
+        let restored = trash.restore(&entries[0]).unwrap();
+        assert_eq!(restored.title(), "New/Show");
+        assert_eq!(trash.purge_older_than(Duration::days(30), now).unwrap(), 1);
+        assert!(trash.entries().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,12 +1,29 @@
 use ratatui::{
     Frame, // Added Wrap for Paragraphs
-    backend::Backend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style}, // Added Rect for inner areas if needed
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap}, // Added Modifier for more styling options
+    Terminal,
+    backend::{Backend, TestBackend},
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::block::{Position, Title},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap}, // Added Modifier for more styling options
 };
 
-use crate::app::App;
+use crate::app::{App, FocusedPanel, Popup, TaskRow};
+use crate::episode_download::{DownloadPriority, DownloadState, format_size};
+use crate::keymap::{KeyScope, help_bindings, hint_line};
+use crate::opml::OpmlFeed;
+use crate::podcast::{EpisodeKey, Podcast};
+use crate::show_notes::format_episode_description;
+use crate::tasks::{TaskKind, TaskStatus};
+use crate::widgets::checklist::ChecklistState;
+use crate::widgets::confirm_dialog::{ConfirmChoice, ConfirmDialog};
+use crate::widgets::file_picker::FilePickerState;
+use crate::widgets::input_line::InputLine;
+use crate::widgets::scrollable_paragraph::{ScrollableParagraphState, SearchMatch};
+use std::path::Path;
+use std::time::Duration;
 // Assuming App is in crate::app
 
 pub fn ui<B: Backend>(f: &mut Frame, app: &App) {
@@ -16,14 +33,95 @@ pub fn ui<B: Backend>(f: &mut Frame, app: &App) {
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Player top
+            // Player top; a single borderless line in compact mode
+            Constraint::Length(if app.compact_player { 1 } else { 3 }),
             Constraint::Min(0),    // Content below
+            Constraint::Length(1), // Status line, or key hints while there's nothing to report
         ])
         .split(f.size());
 
     let player_chunk = main_chunks[0];
     let content_chunk = main_chunks[1];
+    // Running background tasks are summarized at the right end of the status line
+    let activity = activity_text(app);
+    let status_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(activity.chars().count() as u16)])
+        .split(main_chunks[2]);
+    // The command being typed takes the status line's place; with nothing to report it
+    // lists the keys that do something right now
+    if let Some(command) = &app.command_input {
+        let line = command.to_line(":", app.theme.base, app.theme.selected_item);
+        f.render_widget(Paragraph::new(line).style(app.theme.base), status_chunks[0]);
+    } else if let Some(message) = &app.status_message {
+        f.render_widget(Paragraph::new(message.as_str()).style(app.theme.base), status_chunks[0]);
+    } else {
+        let hints = hint_line(key_scope(app));
+        f.render_widget(Paragraph::new(hints).style(app.theme.item), status_chunks[0]);
+    }
+    f.render_widget(Paragraph::new(activity).style(app.theme.item), status_chunks[1]);
+    app.set_page_height(content_chunk.height.saturating_sub(2) as usize); // Minus borders
+
+    // === Player Panel ===
+    render_player(f, app, player_chunk);
+
+    // Reading mode: show notes take over the whole content area
+    if app.show_notes_fullscreen {
+        render_show_notes(f, app, content_chunk);
+    } else if app.showing_welcome() {
+        render_welcome(f, app, content_chunk);
+    } else {
+        render_panels(f, app, content_chunk);
+    }
 
+    // === Popups (drawn last, bottom of the stack first) ===
+    for popup in app.popups() {
+        render_popup(f, app, popup);
+    }
+}
+
+// Which keys apply right now, innermost first: popup, then text input, then the screen
+fn key_scope(app: &App) -> KeyScope {
+    match app.top_popup() {
+        Some(Popup::Confirm(..)) => KeyScope::Confirm,
+        Some(Popup::FilePicker(..)) => KeyScope::FilePicker,
+        Some(Popup::ImportSelection { .. }) => KeyScope::ImportSelection,
+        Some(Popup::Tasks { .. }) => KeyScope::Tasks,
+        Some(Popup::Note { .. }) => KeyScope::Note,
+        Some(Popup::Help(_)) => KeyScope::Help,
+        Some(Popup::PodcastInfo(_) | Popup::Report { .. }) => KeyScope::ReadOnlyPopup,
+        None if app.command_input.is_some()
+            || app.show_notes_search_input.is_some()
+            || app.episode_search_input.is_some() =>
+        {
+            KeyScope::Typing
+        }
+        None if app.showing_welcome() => KeyScope::Welcome,
+        None => KeyScope::Panel(app.focused_panel),
+    }
+}
+
+fn render_popup(f: &mut Frame, app: &App, popup: &Popup) {
+    match popup {
+        Popup::Help(scroll) => render_help(f, app, scroll),
+        Popup::Confirm(_, dialog) => render_confirm_dialog(f, app, dialog),
+        Popup::PodcastInfo(url) => {
+            // The podcast may have been removed while the popup was open
+            if let Some(podcast) = app.podcasts.iter().find(|p| p.url() == url) {
+                render_podcast_info(f, app, podcast);
+            }
+        }
+        Popup::FilePicker(purpose, picker) => render_file_picker(f, app, picker, purpose.title()),
+        Popup::ImportSelection { source, feeds, .. } => {
+            render_import_selection(f, app, source, feeds)
+        }
+        Popup::Report { title, lines } => render_report(f, app, title, lines),
+        Popup::Tasks { selected } => render_tasks(f, app, *selected),
+        Popup::Note { title, input, .. } => render_note(f, app, title, input),
+    }
+}
+
+fn render_panels(f: &mut Frame, app: &App, content_chunk: Rect) {
     // Content layout: Podcasts | Episodes | Show Notes
     let content_columns = Layout::default()
         .direction(Direction::Horizontal)
@@ -34,106 +132,1012 @@ pub fn ui<B: Backend>(f: &mut Frame, app: &App) {
         ])
         .split(content_chunk);
 
-    let podcasts_chunk = content_columns[0];
-    let episodes_chunk = content_columns[1];
-    let show_notes_chunk = content_columns[2];
-
-    // === Player Panel ===
-    let (player_title, player_text) =
-        if let Some((podcast_title, episode_title)) = &app.playing_episode {
-            ("Now Playing".to_string(), format!("▶ {} - {}", podcast_title, episode_title))
-        } else {
-            ("Not Playing".to_string(), " ".to_string()) // Display a space or empty string
-        };
+    render_podcasts(f, app, content_columns[0]);
+    render_episodes(f, app, content_columns[1]);
+    render_show_notes(f, app, content_columns[2]);
+}
 
-    let player_widget = Paragraph::new(player_text)
-        .style(Style::default().fg(Color::LightGreen)) // Style for the text
-        .wrap(Wrap { trim: true }) // Wrap text if it's too long
-        .block(
-            Block::default()
-                .title(player_title)
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::Green)), // Style for the block
-        );
-    f.render_widget(player_widget, player_chunk);
+// First run: ways to fill the library instead of three empty panels
+fn render_welcome(f: &mut Frame, app: &App, area: Rect) {
+    let option = |key: &str, action: &str| Line::from(format!("  {:<6}{}", key, action));
+    let lines = vec![
+        Line::from(""),
+        Line::from("  No podcasts yet. To get started:"),
+        Line::from(""),
+        option("i", "Import an OPML file or a list of feed URLs"),
+        option("a", "Add a feed by its URL (or just paste one)"),
+        option("Esc", "Skip to the empty library"),
+        option("q", "Quit"),
+    ];
+    let welcome = Paragraph::new(lines).style(app.theme.item).block(
+        Block::default()
+            .title("Welcome to rustero")
+            .borders(Borders::ALL)
+            .style(app.theme.base)
+            .border_style(app.theme.focused_border),
+    );
+    f.render_widget(welcome, area);
+}
 
-    // === Podcasts Panel (Left) ===
-    let podcast_list_items: Vec<ListItem> = app
-        .podcasts
-        .iter()
-        .enumerate()
-        .map(|(i, podcast)| {
-            let item_style = if Some(i) == app.selected_podcast_index {
-                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+// === Podcasts Panel (Left) ===
+fn render_podcasts(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let rows = app.podcast_rows();
+    // Say why the list is empty, so it doesn't look like nothing is happening
+    let placeholder = if !rows.is_empty() {
+        None
+    } else if app.podcasts.is_empty() && app.tasks().running_count(TaskKind::Subscribe) > 0 {
+        Some("Loading podcasts...")
+    } else if app.podcasts.is_empty() {
+        Some("No podcasts yet - press a to add one")
+    } else {
+        Some("No podcasts match the filters")
+    };
+    let podcast_list_items: Vec<ListItem> = rows
+        .into_iter()
+        .map(|i| {
+            let item_style = if Some(i) == app.selected_podcast_index() {
+                theme.selected_item
             } else {
-                Style::default().fg(Color::White)
+                theme.item
             };
-            ListItem::new(podcast.title().to_string()).style(item_style) // Ensure title is String or Text
+            match app.podcasts.get(i) {
+                Some(podcast) => {
+                    let pin =
+                        if app.episode_states().is_pinned(podcast.url()) { "📌 " } else { "" };
+                    // Details are in the info popup ('i')
+                    let failed = app.last_refresh(podcast.url()).is_some_and(|o| o.error.is_some());
+                    let warning = if failed { "⚠ " } else { "" };
+                    ListItem::new(format!("{}{}{}", warning, pin, podcast.title()))
+                        .style(item_style)
+                }
+                None => ListItem::new("★ Favorites").style(item_style),
+            }
         })
+        .chain(placeholder.map(|text| ListItem::new(text).style(theme.item)))
         .collect();
 
+    let filters: Vec<&str> =
+        app.language_filter.iter().chain(&app.category_filter).map(String::as_str).collect();
+    let title = if filters.is_empty() {
+        "Podcasts".to_string()
+    } else {
+        format!("Podcasts ({})", filters.join(", "))
+    };
+    let mut block = panel_block(app, title, FocusedPanel::Podcasts);
+    if let Some(podcast) = app.selected_podcast() {
+        block = block.title(Title::from(podcast_summary(podcast)).position(Position::Bottom));
+    }
     let podcasts_list_widget = List::new(podcast_list_items)
-        .block(
-            Block::default()
-                .title("Podcasts")
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::White)),
-        )
-        .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)) // Consistent with item_style
+        .block(block)
+        .highlight_style(theme.highlight) // Consistent with item_style
         .highlight_symbol(">> "); // Optional: symbol for selected item
-    f.render_widget(podcasts_list_widget, podcasts_chunk);
+    // Stateful so long lists scroll to keep the selection visible
+    let mut list_state = ListState::default().with_selected(app.selected_podcast_row());
+    f.render_stateful_widget(podcasts_list_widget, area, &mut list_state);
+}
 
-    // === Episodes Panel (Middle) ===
-    let episodes_list_widget = if let Some(selected_podcast) = app.selected_podcast() {
-        let episode_list_items: Vec<ListItem> = selected_podcast
-            .episodes()
-            .iter()
+// One line for the panel's bottom border: who makes it, where it lives, how fresh it is
+fn podcast_summary(podcast: &Podcast) -> String {
+    let website = podcast.website_url().map(|url| {
+        let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+        url.trim_end_matches('/').to_string()
+    });
+    let updated = format!("updated {}", podcast.last_updated().format("%Y-%m-%d"));
+    podcast
+        .author()
+        .map(String::from)
+        .into_iter()
+        .chain(website)
+        .chain(Some(updated))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+// === Episodes Panel (Middle) ===
+fn render_episodes(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let episodes_list_widget = if app.selected_podcast().is_some() || app.favorites_selected() {
+        let episodes = app.visible_entries();
+        let refreshing = app.selected_podcast().is_some_and(|p| app.is_refreshing(p.url()));
+        let placeholder = if !episodes.is_empty() {
+            None
+        } else if refreshing {
+            Some("Refreshing...")
+        } else if app.favorites_selected() {
+            Some("No starred episodes")
+        } else {
+            Some("No episodes")
+        };
+        let episode_list_items: Vec<ListItem> = episodes
+            .into_iter()
             .enumerate()
-            .map(|(i, episode)| {
-                let item_style = if Some(i) == app.selected_episode_index {
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            .map(|(i, (podcast, episode))| {
+                let item_style = if Some(i) == app.selected_episode_index() {
+                    theme.selected_item
                 } else {
-                    Style::default().fg(Color::White)
+                    theme.item
                 };
-                ListItem::new(episode.title().to_string()).style(item_style)
+                let key = EpisodeKey::new(podcast.url(), episode.id());
+                let states = app.episode_states();
+                let new = if states.is_new(&key) { "NEW " } else { "" };
+                let star = if states.is_starred(&key) { "★ " } else { "" };
+                let note = if states.note(&key).is_some() { "✎ " } else { "" };
+                // Only listed while hidden episodes are shown
+                let hidden = if states.is_hidden(&key) { "[hidden] " } else { "" };
+                let badge = if episode.is_video() { "[video] " } else { "" };
+                let explicit = if episode.is_explicit() { "[E] " } else { "" };
+                let download = download_badge(app, &key);
+                ListItem::new(format!(
+                    "{}{}{}{}{}{}{}{}",
+                    new,
+                    hidden,
+                    star,
+                    note,
+                    download,
+                    badge,
+                    explicit,
+                    episode.title()
+                ))
+                .style(item_style)
             })
+            .chain(placeholder.map(|text| ListItem::new(text).style(theme.item)))
             .collect();
 
-        List::new(episode_list_items)
-            .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
-            .highlight_symbol(">> ")
+        List::new(episode_list_items).highlight_style(theme.highlight).highlight_symbol(">> ")
     } else {
         // Display placeholder if no podcast is selected
         List::new(vec![ListItem::new("No podcast selected")])
     };
 
-    f.render_widget(
+    let query = app.episode_query.as_ref().map(|q| format!("\"{}\"", q));
+    let filters: Vec<&str> = app
+        .date_filter
+        .label()
+        .into_iter()
+        .chain(app.show_hidden_episodes.then_some("incl. hidden"))
+        .chain(app.hide_explicit.then_some("no explicit"))
+        .chain(query.as_deref())
+        .chain(
+            app.selected_podcast()
+                .is_some_and(|p| app.is_refreshing(p.url()))
+                .then_some("refreshing..."),
+        )
+        .collect();
+    let episodes_title = match &app.episode_search_input {
+        Some(input) => input.to_line("Episodes - Filter: ", Style::default(), theme.selected_item),
+        None if filters.is_empty() => Line::from("Episodes"),
+        None => Line::from(format!("Episodes ({})", filters.join(", "))),
+    };
+    let mut list_state = ListState::default()
+        .with_selected(app.selected_episode_index())
+        .with_offset(app.episode_list_offset());
+    f.render_stateful_widget(
         episodes_list_widget.block(
             // Apply the block to the conditionally created List
-            Block::default()
-                .title("Episodes")
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::White)),
+            panel_block(app, episodes_title, FocusedPanel::Episodes),
         ),
-        episodes_chunk,
+        area,
+        &mut list_state,
     );
+    app.set_episode_list_offset(list_state.offset());
+}
+
+// === Show Notes Panel (Right) ===
+fn render_show_notes(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let state = &app.show_notes_state;
+    let width = area.width.saturating_sub(2) as usize;
+    app.set_show_notes_width(width);
 
-    // === Show Notes Panel (Right) ===
-    let show_notes_text = if let Some(episode) = app.selected_episode() {
-        // Assuming Episode has a description method that returns Option<&str>
-        // And that description contains the show notes (might need HTML stripping/formatting)
-        episode.description().unwrap_or("No show notes available.").to_string()
+    let show_notes_text: Vec<Line> = if let Some(episode) = app.selected_episode() {
+        // Formatted (HTML stripped, wrapped to the panel's inner width) and cached by App
+        let lines = app.show_notes_lines(episode, width);
+        let matches = state.matches(&lines);
+        let current = state.current_match().and_then(|i| matches.get(i)).copied();
+        lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| highlight_matches(line, i, &matches, current, app))
+            .collect()
     } else {
-        "Select an episode to see show notes.".to_string()
+        vec![Line::from("Select an episode to see show notes.")]
+    };
+
+    let title = match (&app.show_notes_search_input, state.query()) {
+        (Some(input), _) => {
+            input.to_line("Show Notes - Search: ", Style::default(), theme.selected_item)
+        }
+        (None, Some(query)) => {
+            let lines = app.current_show_notes_lines().unwrap_or_default();
+            let count = state.matches(&lines).len();
+            let position = state.current_match().map_or(0, |i| i + 1);
+            Line::from(format!("Show Notes - /{} ({}/{})", query, position, count))
+        }
+        (None, None) => Line::from("Show Notes"),
     };
 
     let show_notes_widget = Paragraph::new(show_notes_text)
-        .wrap(Wrap { trim: true }) // Important for long text
+        .wrap(Wrap { trim: true }) // Only kicks in for the placeholder text
+        .scroll((state.offset().min(u16::MAX as usize) as u16, 0))
+        .block(panel_block(app, title, FocusedPanel::ShowNotes).style(theme.base));
+    f.render_widget(show_notes_widget, area);
+}
+
+// Splits a line into spans so search matches stand out
+fn highlight_matches(
+    line: &str,
+    line_index: usize,
+    matches: &[SearchMatch],
+    current: Option<SearchMatch>,
+    app: &App,
+) -> Line<'static> {
+    let theme = &app.theme;
+    let chars: Vec<char> = line.chars().collect();
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for m in matches.iter().filter(|m| m.line == line_index) {
+        if m.start > pos {
+            spans.push(Span::raw(chars[pos..m.start].iter().collect::<String>()));
+        }
+        let style: Style =
+            if Some(*m) == current { theme.search_current_match } else { theme.search_match };
+        spans.push(Span::styled(chars[m.start..m.end].iter().collect::<String>(), style));
+        pos = m.end;
+    }
+    if pos == 0 {
+        return Line::from(line.to_string());
+    }
+    if pos < chars.len() {
+        spans.push(Span::raw(chars[pos..].iter().collect::<String>()));
+    }
+    Line::from(spans)
+}
+
+fn render_tasks(f: &mut Frame, app: &App, selected: usize) {
+    let area = centered_rect(80, 16, f.size());
+    let now = std::time::Instant::now();
+    let rows = app.task_rows();
+    let mut queue_position = 0;
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| {
+            let task = match row {
+                TaskRow::Task(task) => task,
+                TaskRow::Queued(item) => {
+                    queue_position += 1;
+                    let priority = match item.priority {
+                        DownloadPriority::User => "",
+                        DownloadPriority::Auto => " (auto)",
+                    };
+                    return ListItem::new(format!(
+                        "{:<12} {:>6}  {}  queued #{}{}",
+                        TaskKind::Download.verb(),
+                        "-",
+                        item.title,
+                        queue_position,
+                        priority
+                    ));
+                }
+            };
+            let status = match &task.status {
+                TaskStatus::Running => match task.ratio() {
+                    Some(ratio) => format!("running {:.0}%", ratio * 100.0),
+                    None => "running".to_string(),
+                },
+                TaskStatus::Done => "done".to_string(),
+                TaskStatus::Failed(error) => format!("failed: {}", error),
+                TaskStatus::Cancelled => "cancelled".to_string(),
+            };
+            ListItem::new(format!(
+                "{:<12} {:>6}  {}  {}",
+                task.kind.verb(),
+                format_duration(task.elapsed(now)),
+                task.label,
+                status
+            ))
+        })
+        .collect();
+    let empty = items.is_empty();
+    let list = List::new(items)
         .block(
             Block::default()
-                .title("Show Notes")
+                .title("Tasks  [c] Cancel  [+/-] Reorder queue  [Esc] Close")
                 .borders(Borders::ALL)
-                .style(Style::default().fg(Color::White)),
+                .style(app.theme.base)
+                .border_style(app.theme.focused_border),
+        )
+        .style(app.theme.item)
+        .highlight_style(app.theme.selected_item);
+    let mut state = ListState::default();
+    if !empty {
+        state.select(Some(selected.min(rows.len() - 1)));
+    }
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+    if empty {
+        let inner =
+            Rect { x: area.x + 1, y: area.y + 1, width: area.width.saturating_sub(2), height: 1 };
+        f.render_widget(Paragraph::new("No background tasks").style(app.theme.item), inner);
+    }
+}
+
+fn render_note(f: &mut Frame, app: &App, episode_title: &str, input: &InputLine) {
+    let area = centered_rect(70, 6, f.size());
+    let text = vec![
+        input.to_line("", app.theme.base, app.theme.selected_item),
+        Line::from(""),
+        Line::styled("[Enter] Save  [Esc] Cancel  (an empty note is removed)", app.theme.item),
+    ];
+    let popup = Paragraph::new(text).block(
+        Block::default()
+            .title(format!("Note: {}", episode_title))
+            .borders(Borders::ALL)
+            .style(app.theme.base)
+            .border_style(app.theme.focused_border),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+// Scrolls when the screen is too short; the bottom border says how to get around
+fn render_help(f: &mut Frame, app: &App, scroll: &ScrollableParagraphState) {
+    let lines: Vec<Line> = help_bindings()
+        .map(|b| Line::from(format!("{:<14}{}{}", b.keys, b.action, b.scope.help_suffix())))
+        .collect();
+    // Leaves the hint bar visible
+    let height = (lines.len() as u16 + 2).min(f.size().height.saturating_sub(2));
+    let area = centered_rect(60, height, f.size());
+    let page = area.height.saturating_sub(2) as usize;
+    app.set_help_page_height(page);
+    let offset = scroll.offset().min(lines.len().saturating_sub(page));
+    let (title, footer) = if page < lines.len() {
+        let last = (offset + page).min(lines.len());
+        let title = format!("Help ({}-{} of {})", offset + 1, last, lines.len());
+        (title, "↑ ↓ PgUp PgDn scroll · any other key closes")
+    } else {
+        ("Help".to_string(), "Press any key to close")
+    };
+    let popup = Paragraph::new(lines).scroll((offset as u16, 0)).block(
+        Block::default()
+            .title(title)
+            .title(Title::from(Span::styled(footer, app.theme.item)).position(Position::Bottom))
+            .borders(Borders::ALL)
+            .style(app.theme.base)
+            .border_style(app.theme.focused_border),
+    );
+    f.render_widget(Clear, area); // Don't let the panels below shine through
+    f.render_widget(popup, area);
+}
+
+fn render_report(f: &mut Frame, app: &App, title: &str, lines: &[String]) {
+    let mut text: Vec<Line> = lines.iter().map(|line| Line::from(line.as_str())).collect();
+    let area = centered_rect(60, text.len() as u16 + 4, f.size());
+    text.push(Line::from(""));
+    text.push(Line::styled("Press any key to close", app.theme.item));
+    let popup = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(title.to_string())
+            .borders(Borders::ALL)
+            .style(app.theme.base)
+            .border_style(app.theme.focused_border),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn render_podcast_info(f: &mut Frame, app: &App, podcast: &Podcast) {
+    let area = centered_rect(70, 20, f.size());
+    let field = |label: &str, value: String| Line::from(format!("{:<14}{}", label, value));
+    let or_unknown = |value: Option<&str>| value.unwrap_or("-").to_string();
+
+    let last_refresh = match app.last_refresh(podcast.url()) {
+        Some(outcome) => match &outcome.error {
+            None => format!("{} (ok)", outcome.at.format("%Y-%m-%d %H:%M")),
+            Some(error) => format!("{} failed: {}", outcome.at.format("%Y-%m-%d %H:%M"), error),
+        },
+        None => format!("not yet (loaded {})", podcast.last_updated().format("%Y-%m-%d %H:%M")),
+    };
+    let categories = podcast.categories().join(", ");
+    let downloads = match app.disk_usage(podcast.url()) {
+        (0, _) => "none".to_string(),
+        (count, bytes) => format!("{} episode(s), {}", count, format_size(bytes)),
+    };
+
+    let mut lines = vec![
+        field("Feed:", podcast.url().to_string()),
+        field("Website:", or_unknown(podcast.website_url())),
+        field("Author:", or_unknown(podcast.author())),
+        field("Language:", or_unknown(podcast.language())),
+        field("Categories:", if categories.is_empty() { "-".to_string() } else { categories }),
+        field("Explicit:", if podcast.is_explicit() { "yes" } else { "no" }.to_string()),
+        field("Episodes:", podcast.episodes().len().to_string()),
+        field("Downloaded:", downloads),
+        field("Last refresh:", last_refresh),
+        Line::from(""),
+    ];
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let description = format_episode_description(podcast.description().unwrap_or(""), inner_width);
+    lines.extend(description.into_iter().map(Line::from));
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!("{} [any key to close]", podcast.title()))
+            .borders(Borders::ALL)
+            .style(app.theme.base)
+            .border_style(app.theme.focused_border),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn render_file_picker(f: &mut Frame, app: &App, picker: &FilePickerState, title: &str) {
+    let area = centered_rect(60, 20, f.size());
+    let block = Block::default()
+        .title(format!("{}: {}", title, picker.dir().display()))
+        .borders(Borders::ALL)
+        .style(app.theme.base)
+        .border_style(app.theme.focused_border);
+    f.render_widget(Clear, area);
+
+    if let Some(error) = picker.error() {
+        let message = Paragraph::new(format!("Cannot list directory: {}", error))
+            .wrap(Wrap { trim: true })
+            .block(block);
+        f.render_widget(message, area);
+        return;
+    }
+    let items: Vec<ListItem> = picker
+        .entries()
+        .iter()
+        .map(|entry| {
+            let name = if entry.is_dir { format!("{}/", entry.name) } else { entry.name.clone() };
+            ListItem::new(name).style(app.theme.item)
+        })
+        .collect();
+    let list = List::new(items).block(block).highlight_style(app.theme.selected_item);
+    let mut state = ListState::default().with_selected(Some(picker.selected()));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_import_selection(
+    f: &mut Frame,
+    app: &App,
+    source: &Path,
+    feeds: &ChecklistState<OpmlFeed>,
+) {
+    let area = centered_rect(70, 20, f.size());
+    let file_name = source.file_name().unwrap_or(source.as_os_str()).to_string_lossy();
+    let block = Block::default()
+        .title(format!(
+            "Import from {}: {} of {} feed(s)",
+            file_name,
+            feeds.checked_count(),
+            feeds.len()
+        ))
+        .borders(Borders::ALL)
+        .style(app.theme.base)
+        .border_style(app.theme.focused_border);
+    let items: Vec<ListItem> = feeds
+        .items()
+        .map(|(feed, checked)| {
+            let name = feed.title.as_deref().unwrap_or(feed.url.as_str());
+            let subscribed = app.podcasts.iter().any(|p| p.url() == &feed.url);
+            let subscribed = if subscribed { " (subscribed)" } else { "" };
+            let check = if checked { "[x]" } else { "[ ]" };
+            ListItem::new(format!("{} {}{}", check, name, subscribed)).style(app.theme.item)
+        })
+        .collect();
+    let list = List::new(items).block(block).highlight_style(app.theme.selected_item);
+    let mut state = ListState::default().with_selected(Some(feeds.selected()));
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_confirm_dialog(f: &mut Frame, app: &App, dialog: &ConfirmDialog) {
+    let area = centered_rect(50, 7, f.size());
+    let button = |label: &str, choice: ConfirmChoice| {
+        let style =
+            if dialog.focused() == choice { app.theme.selected_item } else { app.theme.item };
+        Span::styled(format!("[ {} ]", label), style)
+    };
+    let text = vec![
+        Line::from(dialog.message().to_string()),
+        Line::from(""),
+        Line::from(vec![
+            button("Yes", ConfirmChoice::Yes),
+            Span::raw("   "),
+            button("No", ConfirmChoice::No),
+        ])
+        .alignment(ratatui::layout::Alignment::Center),
+    ];
+    let popup = Paragraph::new(text).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .title(dialog.title().to_string())
+            .borders(Borders::ALL)
+            .style(app.theme.base)
+            .border_style(app.theme.focused_border),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+// A box `percent_x` wide and `height` rows tall in the middle of `area`
+fn centered_rect(percent_x: u16, height: u16, area: Rect) -> Rect {
+    let width = (area.width as u32 * percent_x.min(100) as u32 / 100) as u16;
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+// Bordered panel block, with the border highlighted when the panel has focus
+fn panel_block(app: &App, title: impl Into<Line<'static>>, panel: FocusedPanel) -> Block<'static> {
+    let block = Block::default().title(title).borders(Borders::ALL).style(app.theme.base);
+    if app.focused_panel == panel { block.border_style(app.theme.focused_border) } else { block }
+}
+
+fn render_player(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let title = if app.playing_episode.is_some() { "Now Playing" } else { "Not Playing" };
+    let block = Block::default().title(title).borders(Borders::ALL).style(theme.player_block);
+
+    let text = match &app.playing_episode {
+        Some((podcast_title, episode_title)) => {
+            format!("▶ {} - {}", podcast_title, episode_title)
+        }
+        // Compact mode has no title, so say it in the line itself
+        None if app.compact_player => "Not Playing".to_string(),
+        None => " ".to_string(), // Display a space or empty string
+    };
+
+    if let (Some(_), Some(progress)) = (&app.playing_episode, app.playback_progress) {
+        let label = format!(
+            "{}  {} / -{}",
+            text,
+            format_duration(progress.position),
+            format_duration(progress.remaining())
         );
-    f.render_widget(show_notes_widget, show_notes_chunk);
+        let gauge = Gauge::default()
+            .gauge_style(theme.player_gauge)
+            .ratio(progress.ratio())
+            .label(Span::styled(label, theme.player_text))
+            .use_unicode(true);
+        let gauge = if app.compact_player { gauge } else { gauge.block(block) };
+        f.render_widget(gauge, area);
+    } else {
+        let paragraph = Paragraph::new(text)
+            .style(theme.player_text) // Style for the text
+            .wrap(Wrap { trim: true }); // Wrap text if it's too long
+        let paragraph = if app.compact_player { paragraph } else { paragraph.block(block) };
+        f.render_widget(paragraph, area);
+    }
+}
+
+// "[↓ 42%] " while downloading, "[✓] " once saved
+fn download_badge(app: &App, episode: &EpisodeKey) -> String {
+    match app.download_state(episode) {
+        Some(DownloadState::Downloading { task, .. }) => {
+            match app.tasks().get(*task).and_then(|t| t.ratio()) {
+                Some(ratio) => format!("[↓ {:.0}%] ", ratio * 100.0),
+                None => "[↓] ".to_string(),
+            }
+        }
+        Some(DownloadState::Queued) => "[queued] ".to_string(),
+        Some(DownloadState::Downloaded(_)) => "[✓] ".to_string(),
+        Some(DownloadState::Corrupt { .. }) => "[incomplete] ".to_string(),
+        Some(DownloadState::Failed(_)) => "[!] ".to_string(),
+        None => String::new(),
+    }
+}
+
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+// e.g. "⠹ subscribing 1 · refreshing 3 ", or empty when nothing runs
+fn activity_text(app: &App) -> String {
+    // Stays visible after the startup message is gone
+    let read_only = if app.is_read_only() { " read-only" } else { "" };
+    let summary = app.tasks().summary();
+    if summary.is_empty() {
+        return if app.is_read_only() { format!("{} ", read_only) } else { String::new() };
+    }
+    let counts: Vec<String> =
+        summary.iter().map(|(kind, count)| format!("{} {}", kind.verb(), count)).collect();
+    let spinner = SPINNER_FRAMES[app.spinner_frame % SPINNER_FRAMES.len()];
+    format!("{} {} {} ", read_only, spinner, counts.join(" · "))
+}
+
+// H:MM:SS for long episodes, M:SS otherwise
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+// Headless rendering: draws the full UI for `app` into an in-memory buffer instead of a
+// real terminal, so layouts can be snapshot-tested.
+pub fn render_to_buffer(app: &App, width: u16, height: u16) -> std::io::Result<Buffer> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    terminal.draw(|f| ui::<TestBackend>(f, app))?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+// One String per buffer row, handy for asserting on rendered text
+pub fn buffer_lines(buffer: &Buffer) -> Vec<String> {
+    let width = buffer.area.width as usize;
+    buffer
+        .content
+        .chunks(width.max(1))
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{FilePickerPurpose, PlaybackProgress};
+    use crate::events::AppEvent;
+    use crate::podcast::{Episode, EpisodeID, Podcast, PodcastURL};
+
+    fn app_with_podcast() -> App {
+        let mut app = App::new();
+        app.podcasts.push(Podcast::new(
+            PodcastURL::new("http://example.com/feed"),
+            "Rust Daily News".to_string(),
+            None,
+            None,
+            None,
+            vec![],
+        ));
+        app
+    }
+
+    #[test]
+    fn test_render_to_buffer_has_requested_size() {
+        let buffer = render_to_buffer(&App::new(), 80, 24).unwrap();
+        assert_eq!(buffer.area.width, 80);
+        assert_eq!(buffer.area.height, 24);
+        assert_eq!(buffer_lines(&buffer).len(), 24);
+    }
+
+    #[test]
+    fn test_render_shows_panels_and_podcasts() {
+        let app = app_with_podcast();
+        let screen = buffer_lines(&render_to_buffer(&app, 120, 20).unwrap()).join("\n");
+
+        assert!(screen.contains("Not Playing"));
+        assert!(screen.contains("Podcasts"));
+        assert!(screen.contains("Episodes"));
+        assert!(screen.contains("Show Notes"));
+        assert!(screen.contains("Rust Daily News"));
+        assert!(screen.contains("No podcast selected"));
+    }
+
+    #[test]
+    fn test_player_shows_elapsed_and_remaining_time() {
+        let mut app = app_with_podcast();
+        app.playing_episode = Some(("Rust Daily News".to_string(), "Ep 1".to_string()));
+        app.set_playback_progress(Some(PlaybackProgress {
+            position: Duration::from_secs(83),
+            duration: Duration::from_secs(3723),
+        }));
+
+        let screen = buffer_lines(&render_to_buffer(&app, 120, 20).unwrap()).join("\n");
+        assert!(screen.contains("Now Playing"));
+        assert!(screen.contains("1:23 / -1:00:40"));
+    }
+
+    #[test]
+    fn test_welcome_replaces_empty_panels() {
+        let app = App::new().with_welcome(true);
+        let screen = buffer_lines(&render_to_buffer(&app, 120, 20).unwrap()).join("\n");
+        assert!(screen.contains("Welcome to rustero"));
+        assert!(screen.contains("Import an OPML file"));
+        assert!(!screen.contains("Episodes"));
+
+        let mut app = app_with_podcast().with_welcome(true);
+        let screen = buffer_lines(&render_to_buffer(&app, 120, 20).unwrap()).join("\n");
+        assert!(!screen.contains("Welcome to rustero"));
+
+        app.podcasts.clear();
+        app.on_key(crossterm::event::KeyCode::Char('a'));
+        assert_eq!(app.command_input.as_ref().map(|i| i.text()), Some("add "));
+    }
+
+    #[test]
+    fn test_empty_and_loading_states() {
+        let mut app = App::new();
+        let screen = buffer_lines(&render_to_buffer(&app, 120, 20).unwrap()).join("\n");
+        assert!(screen.contains("No podcasts yet - press a to add one"));
+
+        let mut ids = crate::tasks::TaskTracker::new();
+        let label = "http://example.com/feed".to_string();
+        let id = ids.next_id();
+        app.handle_event(AppEvent::TaskStarted { id, kind: TaskKind::Subscribe, label });
+        let screen = buffer_lines(&render_to_buffer(&app, 120, 20).unwrap()).join("\n");
+        assert!(screen.contains("Loading podcasts..."));
+
+        let mut app = app_with_podcast();
+        app.set_selected_podcast_index(Some(0));
+        let screen = buffer_lines(&render_to_buffer(&app, 120, 20).unwrap()).join("\n");
+        assert!(screen.contains("No episodes"));
+
+        let label = "http://example.com/feed".to_string();
+        app.handle_event(AppEvent::TaskStarted { id, kind: TaskKind::Refresh, label });
+        let screen = buffer_lines(&render_to_buffer(&app, 120, 20).unwrap()).join("\n");
+        assert!(screen.contains("Episodes (refreshing...)"));
+        assert!(screen.contains("Refreshing..."));
+    }
+
+    #[test]
+    fn test_podcasts_panel_footer_summarizes_selection() {
+        let mut app = App::new();
+        app.podcasts.push(
+            Podcast::new(
+                PodcastURL::new("http://example.com/feed"),
+                "Rust Daily News".to_string(),
+                None,
+                None,
+                Some("https://rust.example.com/".to_string()),
+                vec![],
+            )
+            .with_author(Some("Ferris".to_string())),
+        );
+        let screen = buffer_lines(&render_to_buffer(&app, 150, 20).unwrap()).join("\n");
+        assert!(!screen.contains("Ferris"));
+
+        app.select_next_podcast();
+        let lines = buffer_lines(&render_to_buffer(&app, 150, 20).unwrap());
+        let footer = &lines[lines.len() - 2]; // Above the status line
+        assert!(footer.contains("Ferris · rust.example.com · updated "), "{}", footer);
+    }
+
+    #[test]
+    fn test_hint_bar_follows_focus_and_messages() {
+        let mut app = app_with_podcast();
+        let last_line = |app: &App| {
+            buffer_lines(&render_to_buffer(app, 150, 20).unwrap()).pop().unwrap_or_default()
+        };
+        assert!(last_line(&app).starts_with("a add · i info"));
+
+        app.focused_panel = FocusedPanel::Episodes;
+        assert!(last_line(&app).starts_with("D / c download"));
+
+        app.status_message = Some("Pinned 'Rust Daily News'".to_string());
+        assert!(last_line(&app).starts_with("Pinned"));
+        app.on_key_event(crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Tab));
+        assert!(last_line(&app).starts_with("/  n  N search"));
+        app.push_popup(Popup::Help(ScrollableParagraphState::default()));
+        assert!(last_line(&app).starts_with("↑ ↓ / PgUp PgDn scroll · any other key close"));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(5)), "0:05");
+        assert_eq!(format_duration(Duration::from_secs(754)), "12:34");
+        assert_eq!(format_duration(Duration::from_secs(3600)), "1:00:00");
+    }
+
+    #[test]
+    fn test_compact_player_gives_content_the_extra_rows() {
+        let mut app = app_with_podcast();
+        app.compact_player = true;
+
+        let lines = buffer_lines(&render_to_buffer(&app, 120, 20).unwrap());
+        assert!(lines[0].starts_with("Not Playing"));
+        assert!(lines[1].contains("Podcasts"));
+    }
+
+    #[test]
+    fn test_fullscreen_show_notes_use_the_whole_width() {
+        let mut app = app_with_podcast();
+        app.podcasts[0].add_episode(Episode::new(
+            EpisodeID::new("ep1"),
+            "Ep 1".to_string(),
+            Some(format!("<p>{}</p>", "word ".repeat(40))),
+            chrono::Utc::now(),
+            None,
+            "http://example.com/ep1.mp3".to_string(),
+            None,
+        ));
+        app.set_selected_podcast_index(Some(0));
+        app.set_selected_episode_index(Some(0));
+        app.show_notes_fullscreen = true;
+
+        let lines = buffer_lines(&render_to_buffer(&app, 100, 20).unwrap());
+        assert!(!lines.iter().any(|l| l.contains("Podcasts")));
+        // Wrapped to the full inner width (98), so the first line holds 19 "word "s
+        assert_eq!(lines[4].matches("word").count(), 19);
+    }
+
+    #[test]
+    fn test_show_notes_search_scrolls_to_match() {
+        let mut app = app_with_podcast();
+        let notes: String = (1..=30).map(|i| format!("<p>line {}</p>", i)).collect();
+        app.podcasts[0].add_episode(Episode::new(
+            EpisodeID::new("ep1"),
+            "Ep 1".to_string(),
+            Some(notes + "<p>the needle</p>"),
+            chrono::Utc::now(),
+            None,
+            "http://example.com/ep1.mp3".to_string(),
+            None,
+        ));
+        app.set_selected_podcast_index(Some(0));
+        app.set_selected_episode_index(Some(0));
+        app.focused_panel = FocusedPanel::ShowNotes;
+        render_to_buffer(&app, 120, 20).unwrap(); // Lets the app learn the panel width
+
+        for key in "/needle".chars() {
+            app.on_key(crossterm::event::KeyCode::Char(key));
+        }
+        app.on_key(crossterm::event::KeyCode::Enter);
+
+        let screen = buffer_lines(&render_to_buffer(&app, 120, 20).unwrap()).join("\n");
+        assert!(screen.contains("/needle (1/1)"));
+        assert!(screen.contains("the needle"));
+        assert!(!screen.contains("line 1 "));
+    }
+
+    #[test]
+    fn test_activity_indicator_counts_running_tasks() {
+        use crate::events::AppEvent;
+        use crate::tasks::{TaskKind, TaskTracker};
+
+        let mut app = app_with_podcast();
+        let screen = buffer_lines(&render_to_buffer(&app, 100, 20).unwrap());
+        assert!(!screen[19].contains("refreshing")); // Just key hints while idle
+
+        let mut ids = TaskTracker::new();
+        let (a, b, c) = (ids.next_id(), ids.next_id(), ids.next_id());
+        let started = |id, kind| AppEvent::TaskStarted { id, kind, label: "feed".to_string() };
+        app.handle_event(started(a, TaskKind::Refresh));
+        app.handle_event(started(b, TaskKind::Refresh));
+        app.handle_event(started(c, TaskKind::Subscribe));
+        app.status_message = Some("Subscribing to http://example.com/new...".to_string());
+        let lines = buffer_lines(&render_to_buffer(&app, 100, 20).unwrap());
+        assert!(lines[19].starts_with("Subscribing to http://example.com/new..."));
+        assert!(lines[19].trim_end().ends_with("⠋ subscribing 1 · refreshing 2"));
+
+        app.advance_spinner();
+        app.handle_event(AppEvent::TaskFinished { id: c, error: Some("404".to_string()) });
+        let lines = buffer_lines(&render_to_buffer(&app, 100, 20).unwrap());
+        assert!(lines[19].trim_end().ends_with("⠙ refreshing 2"));
+
+        app.on_key(crossterm::event::KeyCode::Char('t'));
+        let screen = buffer_lines(&render_to_buffer(&app, 100, 30).unwrap()).join("\n");
+        assert!(screen.contains("Tasks  [c] Cancel"));
+        assert!(screen.contains("refreshing     0:00  feed  running"));
+        assert!(screen.contains("subscribing    0:00  feed  failed: 404"));
+    }
+
+    #[test]
+    fn test_subscribe_prompt_and_status_line() {
+        let mut app = app_with_podcast();
+        app.on_paste("http://example.com/new");
+        app.status_message = Some("Subscribed to 'Other'".to_string());
+
+        let lines = buffer_lines(&render_to_buffer(&app, 100, 20).unwrap());
+        assert!(lines.join("\n").contains("Subscribe to http://example.com/new?"));
+        assert!(lines[19].starts_with("Subscribed to 'Other'"));
+    }
+
+    #[test]
+    fn test_podcast_info_popup() {
+        let mut app = App::new();
+        app.podcasts.push(
+            Podcast::new(
+                PodcastURL::new("http://example.com/feed"),
+                "Rust Daily News".to_string(),
+                Some("<p>News about <b>Rust</b></p>".to_string()),
+                None,
+                Some("http://example.com".to_string()),
+                vec![],
+            )
+            .with_author(Some("Ferris".to_string()))
+            .with_categories(vec!["Technology".to_string(), "News".to_string()]),
+        );
+        app.set_selected_podcast_index(Some(0));
+        app.on_key(crossterm::event::KeyCode::Char('i'));
+        assert!(matches!(app.top_popup(), Some(Popup::PodcastInfo(_))));
+
+        let screen = buffer_lines(&render_to_buffer(&app, 100, 30).unwrap()).join("\n");
+        assert!(screen.contains("Author:       Ferris"));
+        assert!(screen.contains("Categories:   Technology, News"));
+        assert!(screen.contains("Language:     -"));
+        assert!(screen.contains("Downloaded:   none"));
+        assert!(screen.contains("News about Rust"));
+
+        app.on_key(crossterm::event::KeyCode::Esc);
+        assert!(app.top_popup().is_none());
+    }
+
+    #[test]
+    fn test_failed_refresh_is_marked_until_the_next_success() {
+        let mut app = app_with_podcast();
+        app.set_selected_podcast_index(Some(0));
+        let url = app.podcasts[0].url().clone();
+        app.handle_event(AppEvent::RefreshFailed { url, error: "HTTP 404".to_string() });
+        let lines = buffer_lines(&render_to_buffer(&app, 100, 30).unwrap());
+        assert!(lines.iter().any(|l| l.contains("⚠ Rust Daily News")));
+
+        app.on_key(crossterm::event::KeyCode::Char('i'));
+        let screen = buffer_lines(&render_to_buffer(&app, 100, 30).unwrap()).join("\n");
+        assert!(screen.contains("HTTP 404"));
+        app.on_key(crossterm::event::KeyCode::Esc);
+
+        let podcast = app.podcasts[0].clone();
+        app.handle_event(AppEvent::PodcastRefreshed(podcast));
+        let lines = buffer_lines(&render_to_buffer(&app, 100, 30).unwrap());
+        assert!(!lines.iter().any(|l| l.contains("⚠")));
+    }
+
+    #[test]
+    fn test_command_line_and_file_picker() {
+        let mut app = app_with_podcast();
+        app.on_key(crossterm::event::KeyCode::Char(':'));
+        for c in "imp".chars() {
+            app.on_key(crossterm::event::KeyCode::Char(c));
+        }
+        let lines = buffer_lines(&render_to_buffer(&app, 80, 20).unwrap());
+        assert!(lines[19].starts_with(":imp "));
+
+        app.push_popup(Popup::FilePicker(
+            FilePickerPurpose::ImportOpml,
+            FilePickerState::new(env!("CARGO_MANIFEST_DIR")).with_extensions(&["toml"]),
+        ));
+        app.command_input = None;
+        let screen = buffer_lines(&render_to_buffer(&app, 100, 30).unwrap()).join("\n");
+        assert!(screen.contains("Import OPML"));
+        assert!(screen.contains("src/"));
+        assert!(screen.contains("Cargo.toml"));
+        assert!(!screen.contains("podcast.json"));
+    }
+
+    #[test]
+    fn test_confirm_dialog_is_drawn_on_top() {
+        let mut app = app_with_podcast();
+        app.set_selected_podcast_index(Some(0));
+        app.on_key(crossterm::event::KeyCode::Char('x'));
+
+        let screen = buffer_lines(&render_to_buffer(&app, 100, 30).unwrap()).join("\n");
+        assert!(screen.contains("Unsubscribe from 'Rust Daily News'?"));
+        assert!(screen.contains("[ Yes ]   [ No ]"));
+    }
+
+    #[test]
+    fn test_popups_stack_and_close_top_first() {
+        let mut app = app_with_podcast();
+        app.set_selected_podcast_index(Some(0));
+        app.on_key(crossterm::event::KeyCode::Char('i'));
+        app.push_popup(Popup::Help(ScrollableParagraphState::default()));
+        assert_eq!(app.popups().len(), 2);
+
+        // Too short for every binding: the help scrolls down to the last one
+        let screen = |app: &App| buffer_lines(&render_to_buffer(app, 100, 30).unwrap()).join("\n");
+        let last = help_bindings().last().unwrap().action;
+        assert!(screen(&app).contains("Help (1-"));
+        assert!(screen(&app).contains("any other key closes"));
+        assert!(!screen(&app).contains(last));
+        app.on_key(crossterm::event::KeyCode::PageDown);
+        app.on_key(crossterm::event::KeyCode::PageDown);
+        app.on_key(crossterm::event::KeyCode::PageDown);
+        assert!(screen(&app).contains(last));
+        assert!(matches!(app.top_popup(), Some(Popup::Help(_))));
+
+        app.on_key(crossterm::event::KeyCode::Esc);
+        assert!(matches!(app.top_popup(), Some(Popup::PodcastInfo(_))));
+        app.on_key(crossterm::event::KeyCode::Esc);
+        assert!(app.popups().is_empty());
+        // Keys reach the panels again
+        app.on_key(crossterm::event::KeyCode::Char('?'));
+        assert!(matches!(app.top_popup(), Some(Popup::Help(_))));
+    }
 }
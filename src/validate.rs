@@ -0,0 +1,202 @@
+use crate::podcast::PodcastURL;
+use crate::podcast_download::{FeedFetcher, verify_xml};
+use crate::podcast_factory::{media_file, media_prefix, parse_pub_date};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,   // rustero (and likely other apps) can't use the feed or the item
+    Warning, // Works, but something is off
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+// What `rustero validate <url>` found out about a feed
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub findings: Vec<Finding>,
+    pub episodes: usize, // Items that would show up as episodes
+}
+
+impl ValidationReport {
+    fn error(&mut self, message: impl Into<String>) {
+        self.findings.push(Finding { severity: Severity::Error, message: message.into() });
+    }
+
+    fn warning(&mut self, message: impl Into<String>) {
+        self.findings.push(Finding { severity: Severity::Warning, message: message.into() });
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for finding in &self.findings {
+            let label = match finding.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            writeln!(f, "{}: {}", label, finding.message)?;
+        }
+        let errors = self.findings.iter().filter(|f| f.severity == Severity::Error).count();
+        write!(
+            f,
+            "{} episode(s), {} error(s), {} warning(s)",
+            self.episodes,
+            errors,
+            self.findings.len() - errors
+        )
+    }
+}
+
+const FEED_CONTENT_TYPES: &[&str] =
+    &["application/rss+xml", "application/atom+xml", "application/xml", "text/xml"];
+
+// Fetches and parses a feed the way a subscription would, collecting everything that
+// looks wrong instead of stopping at the first problem where possible
+pub async fn validate_feed(
+    url: &PodcastURL,
+    fetcher: &(dyn FeedFetcher + Send + Sync),
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    match reqwest::Url::parse(url.as_str()) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+        Ok(parsed) => report.warning(format!("Unusual URL scheme '{}'", parsed.scheme())),
+        Err(e) => {
+            report.error(format!("Invalid URL: {}", e));
+            return report;
+        }
+    }
+
+    // Some servers reject HEAD requests, so header problems are only warnings
+    match fetcher.fetch_headers(url.as_str()).await {
+        Ok(headers) => match headers.get("content-type") {
+            Some(content_type) => {
+                let lowercase = content_type.to_ascii_lowercase();
+                if !FEED_CONTENT_TYPES.iter().any(|t| lowercase.contains(t)) {
+                    report.warning(format!("Content-Type '{}' is not a feed type", content_type));
+                }
+            }
+            None => report.warning("No Content-Type header"),
+        },
+        Err(e) => report.warning(format!("Could not fetch headers: {}", e)),
+    }
+
+    let content = match fetcher.fetch(url.as_str()).await {
+        Ok(content) => content,
+        Err(e) => {
+            report.error(format!("Could not fetch the feed: {}", e));
+            return report;
+        }
+    };
+    if let Err(e) = verify_xml(&content) {
+        report.error(e.to_string());
+        return report;
+    }
+    let channel = match rss::Channel::read_from(content.as_bytes()) {
+        Ok(channel) => channel,
+        Err(e) => {
+            report.error(format!("Not a valid RSS feed: {}", e));
+            return report;
+        }
+    };
+
+    if channel.title().trim().is_empty() {
+        report.warning("The channel has no title");
+    }
+    if channel.items().is_empty() {
+        report.warning("The feed has no items");
+    }
+    let prefix = media_prefix(&channel);
+    let mut guids: HashMap<&str, usize> = HashMap::new();
+    for (index, item) in channel.items().iter().enumerate() {
+        let name = match item.title() {
+            Some(title) => format!("Item {} ('{}')", index + 1, title),
+            None => format!("Item {}", index + 1),
+        };
+        let has_media = media_file(item, prefix).is_some();
+        if item.title().is_none() {
+            report.error(format!("{} has no title and is skipped", name));
+        }
+        if !has_media {
+            report.error(format!("{} has no enclosure and is skipped", name));
+        }
+        if has_media && item.title().is_some() {
+            report.episodes += 1;
+        }
+        match item.pub_date() {
+            Some(date) if parse_pub_date(date).is_none() => {
+                report.error(format!("{} has an unparsable pubDate '{}'", name, date))
+            }
+            Some(_) => {}
+            None => report.warning(format!("{} has no pubDate", name)),
+        }
+        match item.guid().map(|g| g.value()) {
+            Some(guid) => {
+                if let Some(first) = guids.insert(guid, index) {
+                    report.error(format!(
+                        "{} repeats the GUID '{}' of item {}",
+                        name,
+                        guid,
+                        first + 1
+                    ));
+                }
+            }
+            None => report.warning(format!("{} has no GUID", name)),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::podcast_download::FakeFetcher;
+
+    #[tokio::test]
+    async fn test_reports_item_problems() {
+        let feed = r#"<?xml version="1.0"?>
+            <rss version="2.0"><channel><title>T</title><link>http://e.com</link><description>d</description>
+                <item><title>Good</title><guid>a</guid><pubDate>Tue, 05 Mar 2024 10:00:00 +0000</pubDate>
+                    <enclosure url="http://e.com/a.mp3" length="1" type="audio/mpeg"/></item>
+                <item><title>No audio</title><guid>b</guid><pubDate>Tue, 05 Mar 2024 10:00:00 +0000</pubDate></item>
+                <item><title>Bad date</title><guid>a</guid><pubDate>yesterday</pubDate>
+                    <enclosure url="http://e.com/c.mp3" length="1" type="audio/mpeg"/></item>
+            </channel></rss>"#;
+        let fetcher = FakeFetcher::new(feed);
+
+        let report = validate_feed(&PodcastURL::new("http://e.com/feed.xml"), &fetcher).await;
+        let errors: Vec<&str> = report
+            .findings
+            .iter()
+            .filter(|f| f.severity == Severity::Error)
+            .map(|f| f.message.as_str())
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "Item 2 ('No audio') has no enclosure and is skipped",
+                "Item 3 ('Bad date') has an unparsable pubDate 'yesterday'",
+                "Item 3 ('Bad date') repeats the GUID 'a' of item 1",
+            ]
+        );
+        assert_eq!(report.episodes, 2);
+        assert!(report.has_errors());
+    }
+
+    #[tokio::test]
+    async fn test_html_page_is_an_error() {
+        let fetcher = FakeFetcher::new("<!DOCTYPE html><html></html>");
+        let report = validate_feed(&PodcastURL::new("http://e.com/page"), &fetcher).await;
+        assert!(report.has_errors());
+        assert!(report.to_string().contains("HTML"));
+    }
+}
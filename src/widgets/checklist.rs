@@ -0,0 +1,93 @@
+// A list whose items can be ticked and unticked one by one, e.g. the feeds of an OPML file
+// before they get imported. Everything starts out ticked.
+#[derive(Debug, Clone)]
+pub struct ChecklistState<T> {
+    items: Vec<(T, bool)>,
+    selected: usize,
+}
+
+impl<T> ChecklistState<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        Self { items: items.into_iter().map(|item| (item, true)).collect(), selected: 0 }
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = (&T, bool)> {
+        self.items.iter().map(|(item, checked)| (item, *checked))
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn checked_count(&self) -> usize {
+        self.items.iter().filter(|(_, checked)| *checked).count()
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.items.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub fn select_last(&mut self) {
+        self.selected = self.items.len().saturating_sub(1);
+    }
+
+    pub fn toggle_selected(&mut self) {
+        if let Some((_, checked)) = self.items.get_mut(self.selected) {
+            *checked = !*checked;
+        }
+    }
+
+    // Unticks everything if all items are ticked, otherwise ticks everything
+    pub fn toggle_all(&mut self) {
+        let check = self.checked_count() < self.items.len();
+        for (_, checked) in &mut self.items {
+            *checked = check;
+        }
+    }
+
+    pub fn into_checked(self) -> Vec<T> {
+        self.items.into_iter().filter(|(_, checked)| *checked).map(|(item, _)| item).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticking_and_unticking() {
+        let mut list = ChecklistState::new(vec!["a", "b", "c"]);
+        assert_eq!(list.checked_count(), 3);
+
+        list.select_next();
+        list.toggle_selected();
+        assert_eq!(list.checked_count(), 2);
+        list.toggle_all();
+        assert_eq!(list.checked_count(), 3);
+        list.toggle_all();
+        assert_eq!(list.checked_count(), 0);
+
+        list.select_last();
+        list.select_next();
+        list.toggle_selected();
+        assert_eq!(list.into_checked(), vec!["c"]);
+    }
+}
@@ -0,0 +1,80 @@
+// Yes/no question shown before destructive actions (unsubscribe, delete, ...)
+use crossterm::event::KeyCode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmChoice {
+    Yes,
+    No,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfirmDialog {
+    title: String,
+    message: String,
+    focused: ConfirmChoice,
+}
+
+impl ConfirmDialog {
+    // Focus starts on "No", so a stray Enter doesn't destroy anything
+    pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { title: title.into(), message: message.into(), focused: ConfirmChoice::No }
+    }
+
+    pub fn with_default(mut self, choice: ConfirmChoice) -> Self {
+        self.focused = choice;
+        self
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn focused(&self) -> ConfirmChoice {
+        self.focused
+    }
+
+    // Returns the answer once the user made one; None while the dialog stays open
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<ConfirmChoice> {
+        match key {
+            KeyCode::Char('y' | 'Y') => Some(ConfirmChoice::Yes),
+            KeyCode::Char('n' | 'N') | KeyCode::Esc => Some(ConfirmChoice::No),
+            KeyCode::Enter => Some(self.focused),
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+                self.focused = match self.focused {
+                    ConfirmChoice::Yes => ConfirmChoice::No,
+                    ConfirmChoice::No => ConfirmChoice::Yes,
+                };
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enter_answers_with_focused_choice() {
+        let mut dialog = ConfirmDialog::new("Unsubscribe", "Really?");
+        assert_eq!(dialog.handle_key(KeyCode::Enter), Some(ConfirmChoice::No));
+        assert_eq!(dialog.handle_key(KeyCode::Right), None);
+        assert_eq!(dialog.handle_key(KeyCode::Enter), Some(ConfirmChoice::Yes));
+
+        let mut dialog = ConfirmDialog::new("Subscribe", "?").with_default(ConfirmChoice::Yes);
+        assert_eq!(dialog.handle_key(KeyCode::Enter), Some(ConfirmChoice::Yes));
+    }
+
+    #[test]
+    fn test_shortcuts_and_other_keys() {
+        let mut dialog = ConfirmDialog::new("t", "m");
+        assert_eq!(dialog.handle_key(KeyCode::Char('x')), None);
+        assert_eq!(dialog.handle_key(KeyCode::Char('y')), Some(ConfirmChoice::Yes));
+        assert_eq!(dialog.handle_key(KeyCode::Esc), Some(ConfirmChoice::No));
+    }
+}
@@ -0,0 +1,228 @@
+// Directory browser for picking a file, e.g. an OPML file to import.
+// Optionally only shows files with certain extensions; dotfiles are hidden unless toggled on.
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct FilePickerState {
+    dir: PathBuf,
+    entries: Vec<FileEntry>, // ".." first (unless at the root), then directories, then files
+    selected: usize,
+    error: Option<String>,   // Why the directory couldn't be listed
+    extensions: Vec<String>, // Lowercase, without the dot; empty means any file
+    show_hidden: bool,
+}
+
+impl FilePickerState {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let mut state = Self {
+            dir: dir.into(),
+            entries: Vec::new(),
+            selected: 0,
+            error: None,
+            extensions: Vec::new(),
+            show_hidden: false,
+        };
+        state.refresh();
+        state
+    }
+
+    // Builder methods
+    pub fn with_extensions(mut self, extensions: &[&str]) -> Self {
+        self.extensions =
+            extensions.iter().map(|e| e.trim_start_matches('.').to_ascii_lowercase()).collect();
+        self.refresh();
+        self
+    }
+
+    pub fn with_hidden_files(mut self, show_hidden: bool) -> Self {
+        self.show_hidden = show_hidden;
+        self.refresh();
+        self
+    }
+
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    pub fn toggle_hidden_files(&mut self) {
+        self.show_hidden = !self.show_hidden;
+        let selected_path = self.entries.get(self.selected).map(|e| e.path.clone());
+        self.refresh();
+        if let Some(index) =
+            self.entries.iter().position(|e| Some(&e.path) == selected_path.as_ref())
+        {
+            self.selected = index;
+        }
+    }
+
+    fn is_listed(&self, entry: &FileEntry) -> bool {
+        if entry.name.starts_with('.') && !self.show_hidden {
+            return false;
+        }
+        if entry.is_dir || self.extensions.is_empty() {
+            return true;
+        }
+        Path::new(&entry.name).extension().is_some_and(|ext| {
+            self.extensions.contains(&ext.to_string_lossy().to_ascii_lowercase())
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn entries(&self) -> &[FileEntry] {
+        &self.entries
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn refresh(&mut self) {
+        self.entries.clear();
+        self.selected = 0;
+        self.error = None;
+        if let Some(parent) = self.dir.parent() {
+            self.entries.push(FileEntry {
+                name: "..".to_string(),
+                path: parent.to_path_buf(),
+                is_dir: true,
+            });
+        }
+
+        let read_dir = match std::fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return;
+            }
+        };
+        let mut listed: Vec<FileEntry> = read_dir
+            .flatten()
+            .map(|entry| FileEntry {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                is_dir: entry.path().is_dir(), // Follows symlinks
+                path: entry.path(),
+            })
+            .filter(|entry| self.is_listed(entry))
+            .collect();
+        listed.sort_by(|a, b| {
+            b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+        self.entries.extend(listed);
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub fn select_last(&mut self) {
+        self.selected = self.entries.len().saturating_sub(1);
+    }
+
+    // Opens the selected directory, or returns the selected file
+    pub fn enter(&mut self) -> Option<PathBuf> {
+        let entry = self.entries.get(self.selected)?.clone();
+        if entry.is_dir {
+            self.change_dir(entry.path);
+            None
+        } else {
+            Some(entry.path)
+        }
+    }
+
+    pub fn go_up(&mut self) {
+        if let Some(parent) = self.dir.parent().map(Path::to_path_buf) {
+            let previous = self.dir.clone();
+            self.change_dir(parent);
+            // Keep the cursor on the directory we came from
+            if let Some(index) = self.entries.iter().position(|e| e.path == previous) {
+                self.selected = index;
+            }
+        }
+    }
+
+    fn change_dir(&mut self, dir: PathBuf) {
+        self.dir = dir;
+        self.refresh();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_tree(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("rustero-file-picker-{}", name));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("b.opml"), "").unwrap();
+        std::fs::write(root.join("A.txt"), "").unwrap();
+        std::fs::write(root.join(".hidden.opml"), "").unwrap();
+        std::fs::write(root.join("sub").join("inner.opml"), "").unwrap();
+        root
+    }
+
+    #[test]
+    fn test_lists_directories_first_and_navigates() {
+        let root = temp_tree("navigate");
+        let mut picker = FilePickerState::new(&root);
+        let names: Vec<&str> = picker.entries().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["..", "sub", "A.txt", "b.opml"]);
+
+        picker.select_next();
+        assert_eq!(picker.enter(), None); // Into "sub"
+        assert_eq!(picker.dir(), root.join("sub"));
+        picker.select_next();
+        assert_eq!(picker.enter(), Some(root.join("sub").join("inner.opml")));
+
+        picker.go_up();
+        assert_eq!(picker.dir(), root);
+        assert_eq!(picker.entries()[picker.selected()].name, "sub");
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_filters_by_extension_and_hidden_files() {
+        let root = temp_tree("filter");
+        let mut picker = FilePickerState::new(&root).with_extensions(&[".OPML"]);
+        let names: Vec<&str> = picker.entries().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["..", "sub", "b.opml"]);
+
+        picker.select_last();
+        picker.toggle_hidden_files();
+        let names: Vec<&str> = picker.entries().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["..", "sub", ".hidden.opml", "b.opml"]);
+        assert_eq!(picker.entries()[picker.selected()].name, "b.opml"); // Selection kept
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_unreadable_directory_reports_error() {
+        let picker = FilePickerState::new("/definitely/not/a/real/dir");
+        assert!(picker.error().is_some());
+        assert_eq!(picker.entries().len(), 1); // Just ".."
+    }
+}
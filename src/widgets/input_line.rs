@@ -0,0 +1,277 @@
+// Single-line text input with a cursor, word-wise editing and history recall,
+// shared by the '/' search and the ':' command line
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+
+const DEFAULT_HISTORY_SIZE: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct InputHistory {
+    entries: Vec<String>, // Oldest first
+    max_entries: usize,
+}
+
+impl Default for InputHistory {
+    fn default() -> Self {
+        Self { entries: Vec::new(), max_entries: DEFAULT_HISTORY_SIZE }
+    }
+}
+
+impl InputHistory {
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    // Blank entries and immediate repeats aren't worth remembering
+    pub fn push(&mut self, entry: &str) {
+        let entry = entry.trim();
+        if entry.is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return;
+        }
+        self.entries.push(entry.to_string());
+        if self.entries.len() > self.max_entries {
+            self.entries.remove(0);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InputLine {
+    text: String,
+    cursor: usize,                // In chars, 0..=len
+    history_index: Option<usize>, // Entry being shown while browsing history
+    draft: String,                // What was typed before browsing history
+}
+
+impl InputLine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.set_text(text);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    // Handles editing and history keys; Enter and Esc are left to the caller.
+    // Returns whether the key was used.
+    pub fn handle_key(&mut self, key: KeyEvent, history: &InputHistory) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('w') if ctrl => self.delete_word_before(),
+            KeyCode::Backspace if ctrl || alt => self.delete_word_before(),
+            KeyCode::Char('u') if ctrl => self.delete_to_start(),
+            KeyCode::Char('k') if ctrl => self.delete_to_end(),
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.len(),
+            KeyCode::Char(_) if ctrl || alt => return false,
+            KeyCode::Char(c) => self.insert_char(c),
+            KeyCode::Backspace => self.delete_before(),
+            KeyCode::Delete => self.delete_at(),
+            KeyCode::Left if ctrl || alt => self.move_word_left(),
+            KeyCode::Right if ctrl || alt => self.move_word_right(),
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.len(),
+            KeyCode::Up => self.history_prev(history),
+            KeyCode::Down => self.history_next(history),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        let at = self.byte_index(self.cursor);
+        self.text.insert(at, c);
+        self.cursor += 1;
+    }
+
+    // Pasted text; line breaks become spaces since the input is a single line
+    pub fn insert_str(&mut self, s: &str) {
+        for c in s.trim_matches(['\r', '\n']).chars() {
+            self.insert_char(if c == '\n' || c == '\r' { ' ' } else { c });
+        }
+    }
+
+    pub fn delete_before(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let at = self.byte_index(self.cursor);
+            self.text.remove(at);
+        }
+    }
+
+    pub fn delete_at(&mut self) {
+        if self.cursor < self.len() {
+            let at = self.byte_index(self.cursor);
+            self.text.remove(at);
+        }
+    }
+
+    pub fn delete_word_before(&mut self) {
+        let start = self.word_start_before(self.cursor);
+        let (from, to) = (self.byte_index(start), self.byte_index(self.cursor));
+        self.text.replace_range(from..to, "");
+        self.cursor = start;
+    }
+
+    pub fn delete_to_start(&mut self) {
+        let to = self.byte_index(self.cursor);
+        self.text.replace_range(..to, "");
+        self.cursor = 0;
+    }
+
+    pub fn delete_to_end(&mut self) {
+        let from = self.byte_index(self.cursor);
+        self.text.truncate(from);
+    }
+
+    pub fn move_word_left(&mut self) {
+        self.cursor = self.word_start_before(self.cursor);
+    }
+
+    pub fn move_word_right(&mut self) {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut i = self.cursor;
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        while i < chars.len() && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        self.cursor = i;
+    }
+
+    pub fn history_prev(&mut self, history: &InputHistory) {
+        let entries = history.entries();
+        let index = match self.history_index {
+            None if entries.is_empty() => return,
+            None => {
+                self.draft = self.text.clone();
+                entries.len() - 1
+            }
+            Some(index) => index.saturating_sub(1),
+        };
+        self.history_index = Some(index);
+        self.set_text(&entries[index]);
+    }
+
+    pub fn history_next(&mut self, history: &InputHistory) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+        if index + 1 < history.entries().len() {
+            self.history_index = Some(index + 1);
+            self.set_text(&history.entries()[index + 1]);
+        } else {
+            // Past the newest entry: back to what was being typed
+            self.history_index = None;
+            let draft = std::mem::take(&mut self.draft);
+            self.set_text(&draft);
+        }
+    }
+
+    // The text after `prefix`, with the character under the cursor drawn in `cursor_style`
+    pub fn to_line(&self, prefix: &str, style: Style, cursor_style: Style) -> Line<'static> {
+        let before: String = self.text.chars().take(self.cursor).collect();
+        let under: String =
+            self.text.chars().nth(self.cursor).map_or(" ".to_string(), String::from);
+        let after: String = self.text.chars().skip(self.cursor + 1).collect();
+        Line::from(vec![
+            Span::styled(format!("{}{}", prefix, before), style),
+            Span::styled(under, cursor_style),
+            Span::styled(after, style),
+        ])
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.cursor = self.len();
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.text.char_indices().nth(char_index).map_or(self.text.len(), |(i, _)| i)
+    }
+
+    fn word_start_before(&self, cursor: usize) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut i = cursor;
+        while i > 0 && chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        while i > 0 && !chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+        i
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(input: &mut InputLine, code: KeyCode, modifiers: KeyModifiers) {
+        input.handle_key(KeyEvent::new(code, modifiers), &InputHistory::default());
+    }
+
+    #[test]
+    fn test_cursor_movement_and_editing() {
+        let mut input = InputLine::new().with_text("hello wörld");
+        press(&mut input, KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(input.cursor(), 6);
+        press(&mut input, KeyCode::Char('X'), KeyModifiers::NONE);
+        assert_eq!(input.text(), "hello Xwörld");
+        press(&mut input, KeyCode::Delete, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(input.text(), "hello örld");
+        press(&mut input, KeyCode::End, KeyModifiers::NONE);
+        press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(input.text(), "hello ");
+        press(&mut input, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(input.text(), "");
+    }
+
+    #[test]
+    fn test_paste_inserts_at_cursor_on_one_line() {
+        let mut input = InputLine::new().with_text("ab");
+        press(&mut input, KeyCode::Left, KeyModifiers::NONE);
+        input.insert_str("1\n2\n");
+        assert_eq!(input.text(), "a1 2b");
+        assert_eq!(input.cursor(), 4);
+    }
+
+    #[test]
+    fn test_history_recall_keeps_draft() {
+        let mut history = InputHistory::default();
+        history.push("first");
+        history.push("second");
+        history.push("second");
+        history.push("  ");
+        assert_eq!(history.entries(), ["first", "second"]);
+
+        let mut input = InputLine::new().with_text("dra");
+        input.history_prev(&history);
+        assert_eq!(input.text(), "second");
+        input.history_prev(&history);
+        input.history_prev(&history);
+        assert_eq!(input.text(), "first");
+        input.history_next(&history);
+        input.history_next(&history);
+        assert_eq!(input.text(), "dra");
+    }
+}
@@ -0,0 +1,5 @@
+pub mod checklist;
+pub mod confirm_dialog;
+pub mod file_picker;
+pub mod input_line;
+pub mod scrollable_paragraph;
@@ -0,0 +1,180 @@
+// Scroll position and in-text search for pre-wrapped text such as the show notes
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub line: usize,
+    pub start: usize, // Char offsets within the line, end exclusive
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScrollableParagraphState {
+    offset: usize, // First visible line
+    query: Option<String>,
+    current_match: Option<usize>,
+}
+
+impl ScrollableParagraphState {
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    pub fn current_match(&self) -> Option<usize> {
+        self.current_match
+    }
+
+    // Back to the top without a search, e.g. when another episode is selected
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.offset = self.offset.saturating_sub(lines);
+    }
+
+    pub fn scroll_down(&mut self, lines: usize, line_count: usize) {
+        self.offset = (self.offset + lines).min(line_count.saturating_sub(1));
+    }
+
+    // Starts a search and jumps to the first match at or below the current position.
+    // Returns the number of matches.
+    pub fn search(&mut self, query: &str, lines: &[String]) -> usize {
+        if query.is_empty() {
+            self.clear_search();
+            return 0;
+        }
+        self.query = Some(query.to_string());
+        let matches = find_matches(lines, query);
+        self.current_match = matches
+            .iter()
+            .position(|m| m.line >= self.offset)
+            .or(if matches.is_empty() { None } else { Some(0) });
+        self.scroll_to_current(&matches);
+        matches.len()
+    }
+
+    pub fn clear_search(&mut self) {
+        self.query = None;
+        self.current_match = None;
+    }
+
+    pub fn next_match(&mut self, lines: &[String]) {
+        self.step_match(lines, true);
+    }
+
+    pub fn prev_match(&mut self, lines: &[String]) {
+        self.step_match(lines, false);
+    }
+
+    // Matches for the active query in `lines`, which may have been re-wrapped since the search
+    pub fn matches(&self, lines: &[String]) -> Vec<SearchMatch> {
+        self.query.as_deref().map(|q| find_matches(lines, q)).unwrap_or_default()
+    }
+
+    fn step_match(&mut self, lines: &[String], forward: bool) {
+        let matches = self.matches(lines);
+        if matches.is_empty() {
+            self.current_match = None;
+            return;
+        }
+        let last = matches.len() - 1;
+        self.current_match = Some(match (self.current_match, forward) {
+            (Some(i), true) if i < last => i + 1,
+            (Some(_), true) | (None, _) => 0, // Wrap around
+            (Some(0), false) => last,
+            (Some(i), false) => (i - 1).min(last),
+        });
+        self.scroll_to_current(&matches);
+    }
+
+    fn scroll_to_current(&mut self, matches: &[SearchMatch]) {
+        if let Some(m) = self.current_match.and_then(|i| matches.get(i)) {
+            self.offset = m.line;
+        }
+    }
+}
+
+// Case-insensitive search; offsets are in chars so they map directly onto rendered text
+pub fn find_matches(lines: &[String], query: &str) -> Vec<SearchMatch> {
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let query: Vec<char> = query.chars().map(fold).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    for (line_index, line) in lines.iter().enumerate() {
+        let chars: Vec<char> = line.chars().map(fold).collect();
+        let mut start = 0;
+        while start + query.len() <= chars.len() {
+            if chars[start..start + query.len()] == query[..] {
+                matches.push(SearchMatch { line: line_index, start, end: start + query.len() });
+                start += query.len();
+            } else {
+                start += 1;
+            }
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_find_matches_is_case_insensitive() {
+        let text = lines(&["Rust and rust", "no match", "RUST"]);
+        let matches = find_matches(&text, "rust");
+        assert_eq!(
+            matches,
+            vec![
+                SearchMatch { line: 0, start: 0, end: 4 },
+                SearchMatch { line: 0, start: 9, end: 13 },
+                SearchMatch { line: 2, start: 0, end: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_jumps_and_cycles_through_matches() {
+        let text = lines(&["intro", "rust one", "filler", "rust two"]);
+        let mut state = ScrollableParagraphState::default();
+
+        assert_eq!(state.search("rust", &text), 2);
+        assert_eq!(state.offset(), 1);
+
+        state.next_match(&text);
+        assert_eq!((state.current_match(), state.offset()), (Some(1), 3));
+        state.next_match(&text);
+        assert_eq!((state.current_match(), state.offset()), (Some(0), 1));
+        state.prev_match(&text);
+        assert_eq!((state.current_match(), state.offset()), (Some(1), 3));
+    }
+
+    #[test]
+    fn test_search_without_matches() {
+        let text = lines(&["nothing here"]);
+        let mut state = ScrollableParagraphState::default();
+        assert_eq!(state.search("rust", &text), 0);
+        assert_eq!(state.current_match(), None);
+        assert_eq!(state.offset(), 0);
+    }
+
+    #[test]
+    fn test_scroll_is_clamped() {
+        let mut state = ScrollableParagraphState::default();
+        state.scroll_down(10, 4);
+        assert_eq!(state.offset(), 3);
+        state.scroll_up(10);
+        assert_eq!(state.offset(), 0);
+    }
+}